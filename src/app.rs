@@ -0,0 +1,236 @@
+use std::{
+    borrow::Cow,
+    env, fmt,
+    path::{Path, PathBuf},
+    ptr,
+};
+
+use coreclr_hosting_shared::{char_t, StatusCode};
+
+use crate::{
+    context,
+    error::{ContainsNulError, Error, ExitCode, HostingError},
+    error_writer, install,
+    path_list::PathList,
+    pdcstr::{PdCStr, PdCString, ToPdCStr},
+    wrapper::Hostfxr,
+};
+
+/// Owned command-line arguments in the form expected by hostfxr, i.e. an `argv` array of nul-terminated platform strings
+/// which is itself terminated by a null pointer.
+pub struct Argv {
+    args: Vec<PdCString>,
+    pointers: Vec<*const char_t>,
+}
+
+impl Argv {
+    /// Converts the given arguments into platform strings.
+    pub fn new(args: impl IntoIterator<Item = impl ToPdCStr>) -> Result<Self, ContainsNulError> {
+        let args = args
+            .into_iter()
+            .map(|arg| arg.to_pdcstr().map(Cow::into_owned))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::from_platform_strings(args))
+    }
+
+    /// Returns the arguments the current process was started with, see [`env::args_os`].
+    pub fn from_env() -> Result<Self, ContainsNulError> {
+        Self::new(env::args_os())
+    }
+
+    fn from_platform_strings(args: Vec<PdCString>) -> Self {
+        let pointers = args
+            .iter()
+            .map(|arg| arg.as_ptr())
+            .chain([ptr::null()])
+            .collect();
+        Self { args, pointers }
+    }
+
+    /// Returns the number of arguments.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.args.len()
+    }
+
+    /// Returns whether there are no arguments.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.args.is_empty()
+    }
+
+    /// Returns the number of arguments as passed to hostfxr.
+    ///
+    /// # Panics
+    /// Panics if there are more than [`i32::MAX`] arguments.
+    #[must_use]
+    pub fn argc(&self) -> i32 {
+        i32::try_from(self.len()).expect("too many arguments")
+    }
+
+    /// Returns a pointer to the null-terminated `argv` array, which is valid for as long as `self` is.
+    #[must_use]
+    pub fn as_ptr(&self) -> *const *const char_t {
+        self.pointers.as_ptr()
+    }
+}
+
+impl Clone for Argv {
+    fn clone(&self) -> Self {
+        Self::from_platform_strings(self.args.clone())
+    }
+}
+
+impl fmt::Debug for Argv {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(&self.args).finish()
+    }
+}
+
+/// The paths passed to [`hostfxr_main_startupinfo`](Hostfxr::hostfxr_main_startupinfo), see [`main_startupinfo`].
+///
+/// Paths that are not set explicitly are determined like an apphost would.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StartupInfo {
+    host_path: Option<PathBuf>,
+    dotnet_root: Option<PathBuf>,
+    app_path: Option<PathBuf>,
+}
+
+impl StartupInfo {
+    /// Creates startup info with all paths set to their defaults.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the path of the host executable.
+    #[must_use]
+    pub fn with_host_path(mut self, host_path: impl Into<PathBuf>) -> Self {
+        self.host_path = Some(host_path.into());
+        self
+    }
+
+    /// Sets the root directory of the .NET installation to run the application with.
+    #[must_use]
+    pub fn with_dotnet_root(mut self, dotnet_root: impl Into<PathBuf>) -> Self {
+        self.dotnet_root = Some(dotnet_root.into());
+        self
+    }
+
+    /// Sets the path of the managed application (its `.dll`) to run.
+    #[must_use]
+    pub fn with_app_path(mut self, app_path: impl Into<PathBuf>) -> Self {
+        self.app_path = Some(app_path.into());
+        self
+    }
+
+    /// Returns the path of the host executable, which defaults to the current executable.
+    #[must_use]
+    pub fn host_path(&self) -> PathBuf {
+        self.host_path
+            .clone()
+            .or_else(|| env::current_exe().ok())
+            .unwrap_or_default()
+    }
+
+    /// Returns the root directory of the .NET installation, which defaults to the installation found by
+    /// [`install::dotnet_root`] or the directory of the host (like [`hostfxr_main`](Hostfxr::hostfxr_main)) if there is none.
+    #[must_use]
+    pub fn dotnet_root(&self) -> PathBuf {
+        self.dotnet_root
+            .clone()
+            .or_else(install::dotnet_root)
+            .unwrap_or_else(|| host_directory(&self.host_path()))
+    }
+
+    /// Returns the path of the managed application, which defaults to the host path with a `.dll` extension.
+    #[must_use]
+    pub fn app_path(&self) -> PathBuf {
+        self.app_path
+            .clone()
+            .unwrap_or_else(|| self.host_path().with_extension("dll"))
+    }
+}
+
+fn host_directory(host_path: &Path) -> PathBuf {
+    host_path
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default()
+}
+
+/// Runs the application described by the given command line (including the host path as the first argument)
+/// and returns its exit code once it completes.
+///
+/// The host path, .NET root and application path are determined by hostfxr from the location of the current executable.
+/// See [`HostingError::check_exit_code`] for how exit codes are told apart from hosting failures and
+/// [`hostfxr_main`](Hostfxr::hostfxr_main) for details.
+pub fn main(hostfxr: &Hostfxr, argv: &Argv) -> Result<ExitCode, HostingError> {
+    let (code, messages) = error_writer::capture_errors(hostfxr, || unsafe {
+        hostfxr.hostfxr_main(argv.argc(), argv.as_ptr())
+    });
+    HostingError::check_exit_code(code, messages)
+}
+
+/// Runs the application described by the given command line (including the host path as the first argument) and startup info
+/// and returns its exit code once it completes.
+///
+/// See [`HostingError::check_exit_code`] for how exit codes are told apart from hosting failures and
+/// [`hostfxr_main_startupinfo`](Hostfxr::hostfxr_main_startupinfo) for details.
+pub fn main_startupinfo(
+    hostfxr: &Hostfxr,
+    argv: &Argv,
+    startup_info: &StartupInfo,
+) -> Result<ExitCode, Error> {
+    let host_path = PdCString::from_os_str(startup_info.host_path())?;
+    let dotnet_root = PdCString::from_os_str(startup_info.dotnet_root())?;
+    let app_path = PdCString::from_os_str(startup_info.app_path())?;
+    let (code, messages) = error_writer::capture_errors(hostfxr, || unsafe {
+        hostfxr.hostfxr_main_startupinfo(
+            argv.argc(),
+            argv.as_ptr(),
+            host_path.as_ptr(),
+            dotnet_root.as_ptr(),
+            app_path.as_ptr(),
+        )
+    });
+    Ok(HostingError::check_exit_code(code, messages)?)
+}
+
+/// Gets the directories searched for native libraries by the application described by the given command line
+/// (including the host path as the first argument), without running it.
+///
+/// See [`hostfxr_get_native_search_directories`](Hostfxr::hostfxr_get_native_search_directories) for details.
+pub fn native_search_directories(hostfxr: &Hostfxr, argv: &Argv) -> Result<PathList, HostingError> {
+    let mut buffer: Vec<char_t> = Vec::new();
+    loop {
+        let buffer_size = i32::try_from(buffer.len()).unwrap_or(i32::MAX);
+        let mut required_buffer_size = 0;
+        let result = context::call(hostfxr, || unsafe {
+            hostfxr.hostfxr_get_native_search_directories(
+                argv.argc(),
+                argv.as_ptr(),
+                buffer.as_mut_ptr(),
+                buffer_size,
+                &raw mut required_buffer_size,
+            )
+        });
+        match result {
+            Ok(_) => break,
+            Err(err)
+                if err.is(StatusCode::HostApiBufferTooSmall)
+                    && required_buffer_size > buffer_size =>
+            {
+                buffer.resize(usize::try_from(required_buffer_size).unwrap(), 0);
+            }
+            Err(err) => return Err(err),
+        }
+    }
+    if buffer.is_empty() {
+        return Ok(PathList::new());
+    }
+    Ok(PathList::from_pdcstr(unsafe {
+        PdCStr::from_ptr(buffer.as_ptr())
+    }))
+}
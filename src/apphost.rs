@@ -0,0 +1,193 @@
+use std::{error, fmt, fs, io, path::Path};
+
+use crate::bundle;
+
+/// The placeholder for the path of the app embedded in the apphost template, the SHA-256 hash of `foobar`
+/// (see `EMBED_HASH_FULL_UTF8` in `corehost.cpp` in the runtime repository).
+pub const APP_PATH_PLACEHOLDER: &[u8; 64] =
+    b"c3ab8ff13720e8ad9047dd39466b3c8974e592c2fa383d4a3960714caef0c4f2";
+
+/// The maximum length of the app path in bytes, excluding the nul terminator (`EMBED_MAX - 1`).
+pub const MAX_APP_PATH_LENGTH: usize = 1024;
+
+/// Returns the position of the app path placeholder in the given apphost template.
+#[must_use]
+pub fn find_placeholder(image: &[u8]) -> Option<usize> {
+    image
+        .windows(APP_PATH_PLACEHOLDER.len())
+        .position(|window| window == APP_PATH_PLACEHOLDER)
+}
+
+/// Creates apphosts (the native launchers of framework-dependent apps) from the apphost template of the .NET SDK,
+/// like `HostWriter.CreateAppHost` does.
+///
+/// Only ELF templates (Linux and other Unix systems) are supported, as Windows and macOS apphosts require
+/// updating the PE subsystem and code signing, respectively.
+///
+/// # Example
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use hostfxr_sys::apphost::AppHostWriter;
+///
+/// AppHostWriter::from_file("packs/Microsoft.NETCore.App.Host.linux-x64/8.0.0/runtimes/linux-x64/native/apphost")?
+///     .write_to_file("App.dll", "publish/App")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct AppHostWriter {
+    template: Vec<u8>,
+    permissions: Option<fs::Permissions>,
+    bundle_header_offset: Option<u64>,
+}
+
+impl AppHostWriter {
+    /// Creates a writer for the given apphost template.
+    #[must_use]
+    pub fn new(template: Vec<u8>) -> Self {
+        Self {
+            template,
+            permissions: None,
+            bundle_header_offset: None,
+        }
+    }
+
+    /// Reads the apphost template at the given path, whose permissions are applied to the written apphosts.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, AppHostError> {
+        let path = path.as_ref();
+        let template = fs::read(path).map_err(|err| AppHostError::Io(err.kind()))?;
+        let permissions = fs::metadata(path)
+            .map_err(|err| AppHostError::Io(err.kind()))?
+            .permissions();
+        Ok(Self {
+            permissions: Some(permissions),
+            ..Self::new(template)
+        })
+    }
+
+    /// Marks the apphost as a single-file bundle whose manifest starts at the given offset.
+    ///
+    /// This is only needed if the bundled files are appended separately, as [`BundleWriter`](bundle::BundleWriter)
+    /// marks the host itself.
+    #[must_use]
+    pub fn with_bundle_header_offset(mut self, header_offset: u64) -> Self {
+        self.bundle_header_offset = Some(header_offset);
+        self
+    }
+
+    /// Writes an apphost that runs the app at the given path (relative to the apphost) and returns its contents.
+    pub fn write(&self, app_path: &str) -> Result<Vec<u8>, AppHostError> {
+        if !self.template.starts_with(b"\x7fELF") {
+            return Err(AppHostError::UnsupportedFormat);
+        }
+        if app_path.contains('\0') {
+            return Err(AppHostError::InvalidAppPath);
+        }
+        if app_path.len() > MAX_APP_PATH_LENGTH {
+            return Err(AppHostError::AppPathTooLong {
+                length: app_path.len(),
+            });
+        }
+        let placeholder =
+            find_placeholder(&self.template).ok_or(AppHostError::MissingPlaceholder)?;
+        // the placeholder is stored in a buffer of `MAX_APP_PATH_LENGTH + 1` bytes
+        if self.template.len() - placeholder <= MAX_APP_PATH_LENGTH {
+            return Err(AppHostError::MissingPlaceholder);
+        }
+
+        let mut image = self.template.clone();
+        let path_length = app_path.len().max(APP_PATH_PLACEHOLDER.len());
+        let path = &mut image[placeholder..placeholder + path_length];
+        path.fill(0);
+        path[..app_path.len()].copy_from_slice(app_path.as_bytes());
+
+        if let Some(header_offset) = self.bundle_header_offset {
+            let header_offset = i64::try_from(header_offset)
+                .ok()
+                .filter(|offset| *offset != 0)
+                .ok_or(AppHostError::InvalidBundleHeaderOffset)?;
+            let marker = bundle::find_marker(&image).ok_or(AppHostError::MissingBundleMarker)?;
+            if image[marker..marker + 8] != [0; 8] {
+                return Err(AppHostError::AlreadyBundled);
+            }
+            image[marker..marker + 8].copy_from_slice(&header_offset.to_le_bytes());
+        }
+        Ok(image)
+    }
+
+    /// Writes an apphost that runs the app at the given path (relative to the apphost) to `path`.
+    ///
+    /// The apphost gets the permissions of the template if it was read using [`from_file`](Self::from_file).
+    /// Otherwise, an existing file keeps its permissions and a new one is made executable on Unix.
+    pub fn write_to_file(
+        &self,
+        app_path: &str,
+        path: impl AsRef<Path>,
+    ) -> Result<(), AppHostError> {
+        let path = path.as_ref();
+        let image = self.write(app_path)?;
+        let io_error = |err: io::Error| AppHostError::Io(err.kind());
+        let existed = path.exists();
+        fs::write(path, image).map_err(io_error)?;
+        if let Some(permissions) = &self.permissions {
+            fs::set_permissions(path, permissions.clone()).map_err(io_error)?;
+        } else if !existed {
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+
+                fs::set_permissions(path, fs::Permissions::from_mode(0o755)).map_err(io_error)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Error returned when creating an apphost fails.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AppHostError {
+    /// The template could not be read or the apphost could not be written.
+    Io(io::ErrorKind),
+    /// The template is not an ELF binary.
+    UnsupportedFormat,
+    /// The template does not contain the app path placeholder, e.g. because it was already patched.
+    MissingPlaceholder,
+    /// The app path contains a nul character.
+    InvalidAppPath,
+    /// The app path is longer than [`MAX_APP_PATH_LENGTH`].
+    AppPathTooLong {
+        /// The length of the app path in bytes.
+        length: usize,
+    },
+    /// The template does not contain a bundle marker.
+    MissingBundleMarker,
+    /// The template is already bound to a bundle.
+    AlreadyBundled,
+    /// The bundle header offset is zero or too large.
+    InvalidBundleHeaderOffset,
+}
+
+impl fmt::Display for AppHostError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(kind) => write!(f, "failed to access apphost: {kind}"),
+            Self::UnsupportedFormat => f.write_str("apphost template is not an ELF binary"),
+            Self::MissingPlaceholder => {
+                f.write_str("apphost template does not contain the app path placeholder")
+            }
+            Self::InvalidAppPath => f.write_str("app path contains a nul character"),
+            Self::AppPathTooLong { length } => write!(
+                f,
+                "app path is {length} bytes long, but at most {MAX_APP_PATH_LENGTH} are supported"
+            ),
+            Self::MissingBundleMarker => {
+                f.write_str("apphost template does not contain a bundle marker")
+            }
+            Self::AlreadyBundled => f.write_str("apphost template is already bound to a bundle"),
+            Self::InvalidBundleHeaderOffset => f.write_str("invalid bundle header offset"),
+        }
+    }
+}
+
+impl error::Error for AppHostError {}
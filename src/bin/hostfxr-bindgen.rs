@@ -0,0 +1,64 @@
+//! Generates Rust bindings for the `[UnmanagedCallersOnly]` methods of a managed assembly.
+//!
+//! Usage: `hostfxr-bindgen <ASSEMBLY> [OUTPUT] [--crate-path <PATH>]`
+//!
+//! The bindings are written to `OUTPUT` or to stdout if it is omitted.
+
+use std::{env, process::ExitCode};
+
+use hostfxr_sys::bindgen::Bindings;
+
+const USAGE: &str = "usage: hostfxr-bindgen <ASSEMBLY> [OUTPUT] [--crate-path <PATH>]";
+
+fn main() -> ExitCode {
+    let mut positional = Vec::new();
+    let mut crate_path = None;
+    let mut args = env::args_os().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.to_str() {
+            Some("-h" | "--help") => {
+                println!("{USAGE}");
+                return ExitCode::SUCCESS;
+            }
+            Some("--crate-path") => match args.next().and_then(|path| path.into_string().ok()) {
+                Some(path) => crate_path = Some(path),
+                None => {
+                    eprintln!("{USAGE}");
+                    return ExitCode::FAILURE;
+                }
+            },
+            _ => positional.push(arg),
+        }
+    }
+
+    let (assembly, output) = match positional.as_slice() {
+        [assembly] => (assembly, None),
+        [assembly, output] => (assembly, Some(output)),
+        _ => {
+            eprintln!("{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut bindings = match Bindings::from_assembly(assembly) {
+        Ok(bindings) => bindings,
+        Err(err) => {
+            eprintln!("error: {}: {err}", assembly.to_string_lossy());
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Some(crate_path) = crate_path {
+        bindings = bindings.with_crate_path(crate_path);
+    }
+
+    match output {
+        Some(output) => {
+            if let Err(err) = bindings.write_to_file(output) {
+                eprintln!("error: {}: {err}", output.to_string_lossy());
+                return ExitCode::FAILURE;
+            }
+        }
+        None => print!("{}", bindings.generate()),
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,330 @@
+use std::{collections::HashMap, fmt::Write as _, fs, io, path::Path};
+
+use crate::{
+    metadata::{MetadataError, MetadataReader, UnmanagedCallersOnlyMethod},
+    type_name::AssemblyQualifiedName,
+};
+
+/// Maximum number of parameters supported by [`FunctionPtr`](crate::managed::FunctionPtr).
+const MAX_PARAMETERS: usize = 12;
+
+/// Rust keywords that have to be escaped when used as identifiers.
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern", "false",
+    "fn", "for", "gen", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "static", "struct", "trait", "true", "type", "union", "unsafe", "use",
+    "where", "while", "abstract", "become", "box", "do", "final", "macro", "override", "priv",
+    "try", "typeof", "unsized", "virtual", "yield",
+];
+
+/// Generates Rust bindings for the methods of an assembly marked with `[UnmanagedCallersOnly]`.
+///
+/// For each declaring type, a module is emitted containing the assembly qualified name of the type
+/// and, for each method, a function pointer type alias and a function loading the method through a
+/// [`FunctionPointerLoader`](crate::managed::FunctionPointerLoader) (i.e. [`get_function_pointer_fn`](crate::get_function_pointer_fn)):
+///
+/// ```text
+/// pub mod app_exports {
+///     pub const TYPE_NAME: &str = "App.Exports, App, Version=1.0.0.0, Culture=neutral, PublicKeyToken=null";
+///     pub type AddFn = unsafe extern "system" fn(a: i32, b: i32) -> i32;
+///     pub fn add(loader: &FunctionPointerLoader) -> Result<ManagedFunction<AddFn>, GetFunctionError> { .. }
+/// }
+/// ```
+///
+/// User-defined value types are referenced by their simple name and have to be declared as `#[repr(C)]` types in the
+/// module the bindings are included into.
+/// Methods with signatures that are not blittable, have more than 12 parameters or are overloaded are skipped.
+///
+/// # Example
+/// In a `build.rs`:
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let out_dir = std::path::PathBuf::from(std::env::var("OUT_DIR")?);
+/// hostfxr_sys::bindgen::Bindings::from_assembly("managed/bin/Release/net8.0/App.dll")?
+///     .write_to_file(out_dir.join("app.rs"))?;
+/// println!("cargo:rerun-if-changed=managed/bin/Release/net8.0/App.dll");
+/// # Ok(())
+/// # }
+/// ```
+/// and then `include!(concat!(env!("OUT_DIR"), "/app.rs"));` in the crate.
+#[derive(Clone, Debug)]
+pub struct Bindings {
+    assembly: Option<String>,
+    methods: Vec<UnmanagedCallersOnlyMethod>,
+    crate_path: String,
+}
+
+impl Bindings {
+    /// Creates bindings for the given methods.
+    #[must_use]
+    pub fn new(methods: Vec<UnmanagedCallersOnlyMethod>) -> Self {
+        Self {
+            assembly: None,
+            methods,
+            crate_path: "::hostfxr_sys".to_owned(),
+        }
+    }
+
+    /// Creates bindings for the assembly at the given path.
+    pub fn from_assembly(path: impl AsRef<Path>) -> Result<Self, MetadataError> {
+        let image = fs::read(path).map_err(|err| MetadataError::Io(err.kind()))?;
+        Self::from_bytes(&image)
+    }
+
+    /// Creates bindings for the given assembly image.
+    pub fn from_bytes(image: &[u8]) -> Result<Self, MetadataError> {
+        let reader = MetadataReader::new(image)?;
+        let mut bindings = Self::new(reader.unmanaged_callers_only_methods()?);
+        bindings.assembly = Some(reader.assembly_identity()?.to_string());
+        Ok(bindings)
+    }
+
+    /// Sets the path under which this crate is available to the generated code (`::hostfxr_sys` by default).
+    #[must_use]
+    pub fn with_crate_path(mut self, crate_path: impl Into<String>) -> Self {
+        self.crate_path = crate_path.into();
+        self
+    }
+
+    /// Returns the methods bindings are generated for.
+    #[must_use]
+    pub fn methods(&self) -> &[UnmanagedCallersOnlyMethod] {
+        &self.methods
+    }
+
+    /// Generates the Rust source of the bindings.
+    #[must_use]
+    pub fn generate(&self) -> String {
+        let mut out = String::new();
+        match &self.assembly {
+            Some(assembly) => writeln!(out, "// Bindings for {assembly}."),
+            None => writeln!(out, "// Bindings for managed methods."),
+        }
+        .unwrap();
+        out.push_str("// Generated by hostfxr-sys, do not edit manually.\n");
+
+        let mut types: Vec<(&AssemblyQualifiedName, Vec<&UnmanagedCallersOnlyMethod>)> = Vec::new();
+        for method in &self.methods {
+            match types
+                .iter_mut()
+                .find(|(name, _)| **name == method.type_name)
+            {
+                Some((_, methods)) => methods.push(method),
+                None => types.push((&method.type_name, vec![method])),
+            }
+        }
+
+        let mut module_names = HashMap::new();
+        for (type_name, methods) in types {
+            let full_name = type_name.type_name.full_name();
+            let mut module_name = snake_case(&full_name);
+            let count = module_names.entry(module_name.clone()).or_insert(0);
+            *count += 1;
+            if *count > 1 {
+                write!(module_name, "_{count}").unwrap();
+            }
+            self.generate_module(
+                &mut out,
+                &escape_keyword(module_name),
+                &full_name,
+                type_name,
+                &methods,
+            );
+        }
+        out
+    }
+
+    fn generate_module(
+        &self,
+        out: &mut String,
+        module_name: &str,
+        full_name: &str,
+        type_name: &AssemblyQualifiedName,
+        methods: &[&UnmanagedCallersOnlyMethod],
+    ) {
+        let krate = &self.crate_path;
+        writeln!(out).unwrap();
+        writeln!(out, "/// Bindings for `{full_name}`.").unwrap();
+        writeln!(out, "pub mod {module_name} {{").unwrap();
+        writeln!(out, "    #[allow(unused_imports)]").unwrap();
+        writeln!(out, "    use super::*;").unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "    /// The assembly qualified name of `{full_name}`.").unwrap();
+        writeln!(
+            out,
+            "    pub const TYPE_NAME: &str = {:?};",
+            type_name.to_string()
+        )
+        .unwrap();
+
+        for method in methods {
+            let name = &method.method_name;
+            writeln!(out).unwrap();
+
+            let overloaded = methods
+                .iter()
+                .filter(|other| other.method_name == *name)
+                .count()
+                > 1;
+            if overloaded {
+                writeln!(
+                    out,
+                    "    // `{full_name}.{name}` is skipped because it is overloaded."
+                )
+                .unwrap();
+                continue;
+            }
+            if method.signature.parameters.len() > MAX_PARAMETERS {
+                writeln!(
+                    out,
+                    "    // `{full_name}.{name}` is skipped because it has more than {MAX_PARAMETERS} parameters."
+                )
+                .unwrap();
+                continue;
+            }
+            let Some(fn_type) = rust_fn_type(method) else {
+                writeln!(
+                    out,
+                    "    // `{full_name}.{name}` is skipped because its signature is not blittable."
+                )
+                .unwrap();
+                continue;
+            };
+
+            let alias = format!("{}Fn", pascal_case(name));
+            let function = escape_keyword(snake_case(name));
+            match &method.entry_point {
+                Some(entry_point) => writeln!(
+                    out,
+                    "    /// Signature of `{full_name}.{name}` (exported as `{entry_point}`)."
+                ),
+                None => writeln!(out, "    /// Signature of `{full_name}.{name}`."),
+            }
+            .unwrap();
+            writeln!(out, "    pub type {alias} = {fn_type};").unwrap();
+            writeln!(out).unwrap();
+            writeln!(
+                out,
+                "    /// Loads `{full_name}.{name}` using the given loader."
+            )
+            .unwrap();
+            writeln!(out, "    ///").unwrap();
+            writeln!(out, "    /// # Errors").unwrap();
+            writeln!(
+                out,
+                "    /// Returns an error if the method could not be found."
+            )
+            .unwrap();
+            writeln!(out, "    pub fn {function}(").unwrap();
+            writeln!(
+                out,
+                "        loader: &{krate}::managed::FunctionPointerLoader,"
+            )
+            .unwrap();
+            writeln!(out, "    ) -> ::core::result::Result<").unwrap();
+            writeln!(out, "        {krate}::managed::ManagedFunction<{alias}>,").unwrap();
+            writeln!(out, "        {krate}::managed::GetFunctionError,").unwrap();
+            writeln!(out, "    > {{").unwrap();
+            writeln!(out, "        unsafe {{").unwrap();
+            writeln!(out, "            loader.get_function_pointer::<{alias}>(").unwrap();
+            writeln!(out, "                TYPE_NAME,").unwrap();
+            writeln!(out, "                {name:?},").unwrap();
+            writeln!(
+                out,
+                "                {krate}::managed::DelegateSignature::UnmanagedCallersOnly,"
+            )
+            .unwrap();
+            writeln!(out, "            )").unwrap();
+            writeln!(out, "        }}").unwrap();
+            writeln!(out, "    }}").unwrap();
+        }
+        writeln!(out, "}}").unwrap();
+    }
+
+    /// Writes the generated bindings to the given file, leaving it untouched if the contents did not change
+    /// (to avoid needless rebuilds when used from a build script).
+    pub fn write_to_file(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let source = self.generate();
+        if fs::read_to_string(path).is_ok_and(|existing| existing == source) {
+            return Ok(());
+        }
+        fs::write(path, source)
+    }
+}
+
+/// Returns the function pointer type of a method including parameter names.
+fn rust_fn_type(method: &UnmanagedCallersOnlyMethod) -> Option<String> {
+    let signature = &method.signature;
+    let mut fn_type = "unsafe extern \"system\" fn(".to_owned();
+    for (i, parameter) in signature.parameters.iter().enumerate() {
+        if i != 0 {
+            fn_type.push_str(", ");
+        }
+        let name = parameter
+            .name
+            .as_deref()
+            .map(snake_case)
+            .filter(|name| !name.is_empty())
+            .map_or_else(|| "_".to_owned(), escape_keyword);
+        write!(fn_type, "{name}: {}", parameter.parameter_type.rust_type()?).unwrap();
+    }
+    fn_type.push(')');
+    let return_type = signature.return_type.rust_type()?;
+    if return_type != "()" {
+        write!(fn_type, " -> {return_type}").unwrap();
+    }
+    Some(fn_type)
+}
+
+/// Converts a .NET identifier (like `GetHTTPResponse` or `Fixture.Exports+Nested`) into snake case.
+fn snake_case(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut snake = String::new();
+    for (i, c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !snake.is_empty() && !snake.ends_with('_') {
+                snake.push('_');
+            }
+            continue;
+        }
+        if c.is_uppercase() && i != 0 && !snake.ends_with('_') {
+            let previous = chars[i - 1];
+            let next = chars.get(i + 1);
+            if previous.is_lowercase()
+                || previous.is_ascii_digit()
+                || (previous.is_uppercase() && next.is_some_and(|next| next.is_lowercase()))
+            {
+                snake.push('_');
+            }
+        }
+        snake.extend(c.to_lowercase());
+    }
+    let snake = snake.trim_end_matches('_');
+    if snake.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{snake}")
+    } else {
+        snake.to_owned()
+    }
+}
+
+/// Converts a .NET identifier into pascal case.
+fn pascal_case(name: &str) -> String {
+    snake_case(name)
+        .split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_uppercase().chain(chars).collect()
+            })
+        })
+        .collect()
+}
+
+fn escape_keyword(name: String) -> String {
+    match name.as_str() {
+        "self" | "super" | "crate" => format!("{name}_"),
+        _ if KEYWORDS.contains(&name.as_str()) => format!("r#{name}"),
+        _ => name,
+    }
+}
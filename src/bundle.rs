@@ -0,0 +1,421 @@
+use std::{error, fmt, fs, io, path::Path};
+
+mod writer;
+pub use writer::BundleWriter;
+
+/// The signature following the bundle header offset in a host executable (see `bundle_marker.cpp` in the runtime repository).
+pub const BUNDLE_SIGNATURE: [u8; 32] = [
+    0x8b, 0x12, 0x02, 0xb9, 0x6a, 0x61, 0x20, 0x38, 0x72, 0x7b, 0x93, 0x02, 0x14, 0xd7, 0xa0, 0x32,
+    0x13, 0xf5, 0xb9, 0xe6, 0xef, 0xae, 0x33, 0x18, 0xee, 0x3b, 0x2d, 0xce, 0x24, 0xb3, 0x6a, 0xae,
+];
+
+/// `header_flags_t::netcoreapp3_compat_mode`
+const NETCOREAPP3_COMPAT_MODE: u64 = 0x1;
+
+/// Returns the position of the bundle marker in the given host executable, i.e. the position of the header offset
+/// which is followed by [`BUNDLE_SIGNATURE`].
+#[must_use]
+pub fn find_marker(image: &[u8]) -> Option<usize> {
+    image
+        .windows(BUNDLE_SIGNATURE.len())
+        .position(|window| window == BUNDLE_SIGNATURE)
+        .and_then(|position| position.checked_sub(8))
+}
+
+/// Returns the offset of the bundle header stored in the marker of the given host executable,
+/// or [`None`] if the executable does not contain a marker or is not bound to a bundle.
+#[must_use]
+pub fn find_header_offset(image: &[u8]) -> Option<u64> {
+    let marker = find_marker(image)?;
+    let offset = i64::from_le_bytes(image[marker..marker + 8].try_into().ok()?);
+    u64::try_from(offset).ok().filter(|offset| *offset != 0)
+}
+
+/// A single-file bundle, i.e. a host executable with the files of an application appended to it.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Bundle {
+    /// The offset of the bundle header in the executable, as passed to `hostfxr_main_bundle_startupinfo`.
+    pub header_offset: u64,
+    /// The manifest describing the bundled files.
+    pub manifest: Manifest,
+}
+
+impl Bundle {
+    /// Reads the bundle at the given path.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, BundleError> {
+        let image = fs::read(path).map_err(|err| BundleError::Io(err.kind()))?;
+        Self::from_bytes(&image)
+    }
+
+    /// Reads the bundle from the given executable image.
+    pub fn from_bytes(image: &[u8]) -> Result<Self, BundleError> {
+        let header_offset = find_header_offset(image).ok_or(BundleError::NotABundle)?;
+        Ok(Self {
+            header_offset,
+            manifest: Manifest::parse(image, header_offset)?,
+        })
+    }
+}
+
+/// The manifest of a bundle, see [`Bundle`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Manifest {
+    /// The major version of the bundle format (`1` for .NET Core 3, `2` for .NET 5 and `6` for .NET 6 and later).
+    pub major_version: u32,
+    /// The minor version of the bundle format.
+    pub minor_version: u32,
+    /// The unique id of the bundle, used to name the extraction directory.
+    pub bundle_id: String,
+    /// The location of the bundled `.deps.json`, if any (format version 2 and later).
+    pub deps_json: Option<FileLocation>,
+    /// The location of the bundled `.runtimeconfig.json`, if any (format version 2 and later).
+    pub runtime_config_json: Option<FileLocation>,
+    /// The raw `header_flags_t`.
+    pub flags: u64,
+    /// The bundled files.
+    pub files: Vec<FileEntry>,
+}
+
+impl Manifest {
+    /// The highest major version of the bundle format known to this crate.
+    pub const MAX_MAJOR_VERSION: u32 = 6;
+
+    /// Parses the manifest at the given offset of the bundle.
+    pub fn parse(image: &[u8], header_offset: u64) -> Result<Self, BundleError> {
+        let mut reader = Reader {
+            data: image,
+            offset: usize::try_from(header_offset).map_err(|_| BundleError::Truncated)?,
+        };
+        let major_version = reader.u32()?;
+        let minor_version = reader.u32()?;
+        if major_version == 0 || major_version > Self::MAX_MAJOR_VERSION {
+            return Err(BundleError::UnsupportedVersion {
+                major: major_version,
+                minor: minor_version,
+            });
+        }
+        let file_count = reader.i32()?;
+        let file_count = usize::try_from(file_count)
+            .map_err(|_| BundleError::InvalidManifest("negative file count"))?;
+        let bundle_id = reader.string()?;
+
+        let (deps_json, runtime_config_json, flags) = if major_version >= 2 {
+            (reader.location()?, reader.location()?, reader.u64()?)
+        } else {
+            (None, None, 0)
+        };
+
+        let mut files = Vec::new();
+        for _ in 0..file_count {
+            let offset = reader.offset_or_size()?;
+            let size = reader.offset_or_size()?;
+            let compressed_size = if major_version >= 6 {
+                reader.offset_or_size()?
+            } else {
+                0
+            };
+            let file_type = FileType::from_raw(reader.u8()?)?;
+            let relative_path = reader.string()?;
+            let stored_size = if compressed_size == 0 {
+                size
+            } else {
+                compressed_size
+            };
+            if offset
+                .checked_add(stored_size)
+                .is_none_or(|end| end > image.len() as u64)
+            {
+                return Err(BundleError::Truncated);
+            }
+            files.push(FileEntry {
+                offset,
+                size,
+                compressed_size,
+                file_type,
+                relative_path,
+            });
+        }
+
+        Ok(Self {
+            major_version,
+            minor_version,
+            bundle_id,
+            deps_json,
+            runtime_config_json,
+            flags,
+            files,
+        })
+    }
+
+    /// Returns whether the bundle was built for .NET Core 3 compatibility, i.e. all files are extracted to disk.
+    #[must_use]
+    pub fn is_netcoreapp3_compat_mode(&self) -> bool {
+        self.flags & NETCOREAPP3_COMPAT_MODE != 0
+    }
+
+    /// Returns the file with the given relative path.
+    #[must_use]
+    pub fn file(&self, relative_path: &str) -> Option<&FileEntry> {
+        self.files
+            .iter()
+            .find(|file| file.relative_path == relative_path)
+    }
+
+    /// Returns the bundled `.deps.json` file.
+    #[must_use]
+    pub fn deps_json_file(&self) -> Option<&FileEntry> {
+        self.files
+            .iter()
+            .find(|file| file.file_type == FileType::DepsJson)
+    }
+
+    /// Returns the bundled `.runtimeconfig.json` file.
+    #[must_use]
+    pub fn runtime_config_json_file(&self) -> Option<&FileEntry> {
+        self.files
+            .iter()
+            .find(|file| file.file_type == FileType::RuntimeConfigJson)
+    }
+}
+
+/// The location of a file in a bundle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FileLocation {
+    /// The offset of the file in the bundle.
+    pub offset: u64,
+    /// The size of the file in bytes.
+    pub size: u64,
+}
+
+/// A file in a bundle.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FileEntry {
+    /// The offset of the file in the bundle.
+    pub offset: u64,
+    /// The (uncompressed) size of the file in bytes.
+    pub size: u64,
+    /// The size of the deflate-compressed file in bytes, or `0` if the file is stored uncompressed.
+    pub compressed_size: u64,
+    /// The type of the file.
+    pub file_type: FileType,
+    /// The path of the file relative to the application directory, using `/` as separator.
+    pub relative_path: String,
+}
+
+impl FileEntry {
+    /// Returns whether the file is stored compressed.
+    #[must_use]
+    pub fn is_compressed(&self) -> bool {
+        self.compressed_size != 0
+    }
+
+    /// Returns the stored contents of the file in the given bundle, which are deflate-compressed if [`is_compressed`](Self::is_compressed).
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn data<'a>(&self, image: &'a [u8]) -> Result<&'a [u8], BundleError> {
+        let stored_size = if self.is_compressed() {
+            self.compressed_size
+        } else {
+            self.size
+        };
+        let start = self.offset as usize;
+        image
+            .get(start..start.saturating_add(stored_size as usize))
+            .ok_or(BundleError::Truncated)
+    }
+}
+
+/// The type of a file in a bundle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum FileType {
+    /// Any other file.
+    Unknown,
+    /// A managed assembly.
+    Assembly,
+    /// A native library.
+    NativeBinary,
+    /// The `.deps.json` of the application.
+    DepsJson,
+    /// The `.runtimeconfig.json` of the application.
+    RuntimeConfigJson,
+    /// A symbol file (`.pdb`).
+    Symbols,
+}
+
+impl FileType {
+    /// Converts the raw `file_type_t`.
+    pub fn from_raw(value: u8) -> Result<Self, BundleError> {
+        Ok(match value {
+            0 => Self::Unknown,
+            1 => Self::Assembly,
+            2 => Self::NativeBinary,
+            3 => Self::DepsJson,
+            4 => Self::RuntimeConfigJson,
+            5 => Self::Symbols,
+            _ => return Err(BundleError::InvalidManifest("invalid file type")),
+        })
+    }
+
+    /// Returns the raw `file_type_t`.
+    #[must_use]
+    pub const fn to_raw(self) -> u8 {
+        match self {
+            Self::Unknown => 0,
+            Self::Assembly => 1,
+            Self::NativeBinary => 2,
+            Self::DepsJson => 3,
+            Self::RuntimeConfigJson => 4,
+            Self::Symbols => 5,
+        }
+    }
+}
+
+/// Error returned when reading or writing a bundle fails.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BundleError {
+    /// The file could not be read or written.
+    Io(io::ErrorKind),
+    /// The executable does not contain a bundle marker or is not bound to a bundle.
+    NotABundle,
+    /// The bundle ends before the manifest or files it describes.
+    Truncated,
+    /// The bundle uses a format version unknown to this crate.
+    UnsupportedVersion {
+        /// The major version.
+        major: u32,
+        /// The minor version.
+        minor: u32,
+    },
+    /// The manifest is malformed.
+    InvalidManifest(&'static str),
+    /// The host executable does not contain a bundle marker.
+    MissingMarker,
+    /// The host executable is already bound to a bundle.
+    AlreadyBundled,
+    /// The same relative path was added to the bundle more than once.
+    DuplicateFile(String),
+}
+
+impl fmt::Display for BundleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(kind) => write!(f, "failed to access bundle: {kind}"),
+            Self::NotABundle => f.write_str("executable is not a single-file bundle"),
+            Self::Truncated => f.write_str("bundle is truncated"),
+            Self::UnsupportedVersion { major, minor } => {
+                write!(f, "unsupported bundle version {major}.{minor}")
+            }
+            Self::InvalidManifest(reason) => write!(f, "invalid bundle manifest: {reason}"),
+            Self::MissingMarker => f.write_str("host executable does not contain a bundle marker"),
+            Self::AlreadyBundled => f.write_str("host executable is already bound to a bundle"),
+            Self::DuplicateFile(path) => write!(f, "duplicate bundle file '{path}'"),
+        }
+    }
+}
+
+impl error::Error for BundleError {}
+
+/// Runs the application contained in the given bundle and returns its exit code once it completes.
+///
+/// `argv` should include the path of the bundle as the first argument and `startup_info` should describe the bundle,
+/// i.e. have the bundle as its host path (the default when running the current executable), the directory of the bundle
+/// as its .NET root for self-contained applications and the path of the main assembly as if it were next to the bundle
+/// as its application path (the default).
+///
+/// See [`HostingError::check_exit_code`](crate::error::HostingError::check_exit_code) for how exit codes are told apart from
+/// hosting failures and [`hostfxr_main_bundle_startupinfo`](crate::wrapper::Hostfxr::hostfxr_main_bundle_startupinfo) for details.
+#[cfg(all(feature = "wrapper", feature = "netcore3_0", feature = "undocumented"))]
+#[cfg_attr(
+    feature = "doc-cfg",
+    doc(cfg(all(feature = "wrapper", feature = "netcore3_0", feature = "undocumented")))
+)]
+pub fn main_bundle_startupinfo(
+    hostfxr: &crate::wrapper::Hostfxr,
+    argv: &crate::app::Argv,
+    startup_info: &crate::app::StartupInfo,
+    bundle: &Bundle,
+) -> Result<crate::error::ExitCode, crate::error::Error> {
+    use crate::{error::HostingError, error_writer, pdcstr::PdCString};
+
+    let host_path = PdCString::from_os_str(startup_info.host_path())?;
+    let dotnet_root = PdCString::from_os_str(startup_info.dotnet_root())?;
+    let app_path = PdCString::from_os_str(startup_info.app_path())?;
+    let header_offset = i64::try_from(bundle.header_offset).map_err(|_| {
+        HostingError::new(coreclr_hosting_shared::StatusCode::InvalidArgFailure as u32)
+    })?;
+    let (code, messages) = error_writer::capture_errors(hostfxr, || unsafe {
+        hostfxr.hostfxr_main_bundle_startupinfo(
+            argv.argc(),
+            argv.as_ptr(),
+            host_path.as_ptr(),
+            dotnet_root.as_ptr(),
+            app_path.as_ptr(),
+            header_offset,
+        )
+    });
+    Ok(HostingError::check_exit_code(code, messages)?)
+}
+
+/// Reads the little-endian values of the manifest as written by a .NET `BinaryWriter`.
+struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl Reader<'_> {
+    fn bytes<const N: usize>(&mut self) -> Result<[u8; N], BundleError> {
+        let bytes = self
+            .data
+            .get(self.offset..self.offset.saturating_add(N))
+            .ok_or(BundleError::Truncated)?;
+        self.offset += N;
+        Ok(bytes.try_into().unwrap())
+    }
+
+    fn u8(&mut self) -> Result<u8, BundleError> {
+        self.bytes::<1>().map(|[byte]| byte)
+    }
+
+    fn u32(&mut self) -> Result<u32, BundleError> {
+        self.bytes().map(u32::from_le_bytes)
+    }
+
+    fn i32(&mut self) -> Result<i32, BundleError> {
+        self.bytes().map(i32::from_le_bytes)
+    }
+
+    fn u64(&mut self) -> Result<u64, BundleError> {
+        self.bytes().map(u64::from_le_bytes)
+    }
+
+    fn offset_or_size(&mut self) -> Result<u64, BundleError> {
+        let value = i64::from_le_bytes(self.bytes()?);
+        u64::try_from(value).map_err(|_| BundleError::InvalidManifest("negative offset or size"))
+    }
+
+    fn location(&mut self) -> Result<Option<FileLocation>, BundleError> {
+        let offset = self.offset_or_size()?;
+        let size = self.offset_or_size()?;
+        Ok((offset != 0 || size != 0).then_some(FileLocation { offset, size }))
+    }
+
+    /// Reads a string prefixed by its 7-bit encoded length.
+    fn string(&mut self) -> Result<String, BundleError> {
+        let mut length = 0usize;
+        for shift in (0..35).step_by(7) {
+            let byte = self.u8()?;
+            length |= usize::from(byte & 0x7F) << shift;
+            if byte & 0x80 == 0 {
+                let bytes = self
+                    .data
+                    .get(self.offset..self.offset.saturating_add(length))
+                    .ok_or(BundleError::Truncated)?;
+                self.offset += length;
+                return String::from_utf8(bytes.to_vec())
+                    .map_err(|_| BundleError::InvalidManifest("invalid string"));
+            }
+        }
+        Err(BundleError::InvalidManifest("invalid string length"))
+    }
+}
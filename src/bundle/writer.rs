@@ -0,0 +1,267 @@
+use std::{collections::HashSet, fmt::Write as _, fs, path::Path};
+
+use super::{find_marker, BundleError, FileType, Manifest, NETCOREAPP3_COMPAT_MODE};
+use crate::metadata::{sha1::sha1, MetadataReader};
+
+/// The alignment of assemblies in bundles, which are mapped directly from the bundle.
+const DEFAULT_ASSEMBLY_ALIGNMENT: u64 = 16;
+
+/// Writes single-file bundles, see [`Bundle`](super::Bundle).
+///
+/// The files are appended to the host executable (an apphost or singlefilehost) in the order they were added,
+/// followed by the manifest, and the offset of the manifest is patched into the bundle marker of the host.
+/// Files are always stored uncompressed.
+///
+/// # Example
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use hostfxr_sys::bundle::BundleWriter;
+///
+/// let mut writer = BundleWriter::new(std::fs::read("singlefilehost")?);
+/// for name in ["App.dll", "App.deps.json", "App.runtimeconfig.json"] {
+///     writer.add_file(name, std::fs::read(format!("publish/{name}"))?);
+/// }
+/// writer.write_to_file("App")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct BundleWriter {
+    host: Vec<u8>,
+    files: Vec<(String, FileType, Vec<u8>)>,
+    major_version: u32,
+    minor_version: u32,
+    bundle_id: Option<String>,
+    netcoreapp3_compat_mode: bool,
+    assembly_alignment: u64,
+}
+
+impl BundleWriter {
+    /// Creates a writer for bundling files into the given host executable, using the format of .NET 6 and later.
+    #[must_use]
+    pub fn new(host: Vec<u8>) -> Self {
+        Self {
+            host,
+            files: Vec::new(),
+            major_version: Manifest::MAX_MAJOR_VERSION,
+            minor_version: 0,
+            bundle_id: None,
+            netcoreapp3_compat_mode: false,
+            assembly_alignment: DEFAULT_ASSEMBLY_ALIGNMENT,
+        }
+    }
+
+    /// Sets the version of the bundle format, which has to match the host (`1` for .NET Core 3, `2` for .NET 5 and `6` for .NET 6 and later).
+    #[must_use]
+    pub fn with_version(mut self, major_version: u32, minor_version: u32) -> Self {
+        self.major_version = major_version;
+        self.minor_version = minor_version;
+        self
+    }
+
+    /// Sets the id of the bundle, which defaults to one derived from the contents of the bundled files.
+    #[must_use]
+    pub fn with_bundle_id(mut self, bundle_id: impl Into<String>) -> Self {
+        self.bundle_id = Some(bundle_id.into());
+        self
+    }
+
+    /// Sets whether all files are extracted to disk when running the bundle, like with .NET Core 3.
+    #[must_use]
+    pub fn with_netcoreapp3_compat_mode(mut self, netcoreapp3_compat_mode: bool) -> Self {
+        self.netcoreapp3_compat_mode = netcoreapp3_compat_mode;
+        self
+    }
+
+    /// Sets the alignment of assemblies in the bundle, which defaults to 16 bytes.
+    /// The .NET SDK uses 4096 bytes for Linux on arm64.
+    ///
+    /// # Panics
+    /// Panics if `alignment` is zero.
+    #[must_use]
+    pub fn with_assembly_alignment(mut self, alignment: u64) -> Self {
+        assert!(alignment != 0, "alignment must not be zero");
+        self.assembly_alignment = alignment;
+        self
+    }
+
+    /// Adds a file with the given path relative to the application directory, inferring its type, see [`FileType::infer`].
+    pub fn add_file(&mut self, relative_path: impl Into<String>, data: Vec<u8>) -> &mut Self {
+        let relative_path = relative_path.into();
+        let file_type = FileType::infer(&relative_path, &data);
+        self.add_file_with_type(relative_path, file_type, data)
+    }
+
+    /// Adds a file of the given type with the given path relative to the application directory.
+    pub fn add_file_with_type(
+        &mut self,
+        relative_path: impl Into<String>,
+        file_type: FileType,
+        data: Vec<u8>,
+    ) -> &mut Self {
+        let relative_path = relative_path.into().replace('\\', "/");
+        self.files.push((relative_path, file_type, data));
+        self
+    }
+
+    /// Writes the bundle and returns its contents.
+    pub fn write(&self) -> Result<Vec<u8>, BundleError> {
+        if !matches!(self.major_version, 1 | 2 | 6) {
+            return Err(BundleError::UnsupportedVersion {
+                major: self.major_version,
+                minor: self.minor_version,
+            });
+        }
+        let marker = find_marker(&self.host).ok_or(BundleError::MissingMarker)?;
+        if self.host[marker..marker + 8] != [0; 8] {
+            return Err(BundleError::AlreadyBundled);
+        }
+        let mut seen = HashSet::new();
+        if let Some((relative_path, ..)) = self
+            .files
+            .iter()
+            .find(|(relative_path, ..)| !seen.insert(relative_path))
+        {
+            return Err(BundleError::DuplicateFile(relative_path.clone()));
+        }
+        let file_count = i32::try_from(self.files.len())
+            .map_err(|_| BundleError::InvalidManifest("too many files"))?;
+
+        let mut bundle = self.host.clone();
+        let mut entries = Vec::with_capacity(self.files.len());
+        for (_, file_type, data) in &self.files {
+            if *file_type == FileType::Assembly {
+                let misalignment = bundle.len() as u64 % self.assembly_alignment;
+                if misalignment != 0 {
+                    let padding = self.assembly_alignment - misalignment;
+                    bundle.resize(bundle.len() + usize::try_from(padding).unwrap(), 0);
+                }
+            }
+            entries.push((bundle.len() as u64, data.len() as u64));
+            bundle.extend_from_slice(data);
+        }
+
+        let header_offset = bundle.len() as u64;
+        bundle.extend_from_slice(&self.major_version.to_le_bytes());
+        bundle.extend_from_slice(&self.minor_version.to_le_bytes());
+        bundle.extend_from_slice(&file_count.to_le_bytes());
+        write_string(&mut bundle, &self.bundle_id());
+        if self.major_version >= 2 {
+            for location_type in [FileType::DepsJson, FileType::RuntimeConfigJson] {
+                let location = self
+                    .files
+                    .iter()
+                    .zip(&entries)
+                    .find(|((_, file_type, _), _)| *file_type == location_type)
+                    .map_or((0, 0), |(_, location)| *location);
+                write_u64(&mut bundle, location.0);
+                write_u64(&mut bundle, location.1);
+            }
+            let flags = if self.netcoreapp3_compat_mode {
+                NETCOREAPP3_COMPAT_MODE
+            } else {
+                0
+            };
+            write_u64(&mut bundle, flags);
+        }
+        for ((relative_path, file_type, _), (offset, size)) in self.files.iter().zip(&entries) {
+            write_u64(&mut bundle, *offset);
+            write_u64(&mut bundle, *size);
+            if self.major_version >= 6 {
+                // compressed size
+                write_u64(&mut bundle, 0);
+            }
+            bundle.push(file_type.to_raw());
+            write_string(&mut bundle, relative_path);
+        }
+
+        bundle[marker..marker + 8].copy_from_slice(&header_offset.to_le_bytes());
+        Ok(bundle)
+    }
+
+    /// Writes the bundle to the given path, marking it as executable on Unix.
+    pub fn write_to_file(&self, path: impl AsRef<Path>) -> Result<(), BundleError> {
+        let path = path.as_ref();
+        let bundle = self.write()?;
+        let io_error = |err: std::io::Error| BundleError::Io(err.kind());
+        fs::write(path, bundle).map_err(io_error)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            fs::set_permissions(path, fs::Permissions::from_mode(0o755)).map_err(io_error)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the bundle id, deriving it from the paths and contents of the files if it was not set explicitly.
+    fn bundle_id(&self) -> String {
+        if let Some(bundle_id) = &self.bundle_id {
+            return bundle_id.clone();
+        }
+        let mut contents = Vec::new();
+        for (relative_path, _, data) in &self.files {
+            write_string(&mut contents, relative_path);
+            contents.extend_from_slice(&sha1(data));
+        }
+        let mut bundle_id = String::new();
+        for byte in &sha1(&contents)[..6] {
+            write!(bundle_id, "{byte:02x}").unwrap();
+        }
+        bundle_id
+    }
+}
+
+impl FileType {
+    /// Infers the type of a bundled file from its path and contents, like the .NET SDK does.
+    #[must_use]
+    pub fn infer(relative_path: &str, data: &[u8]) -> Self {
+        let file_name = relative_path.rsplit(['/', '\\']).next().unwrap_or_default();
+        let is_top_level = !relative_path.contains(['/', '\\']);
+        if is_top_level && file_name.ends_with(".deps.json") {
+            Self::DepsJson
+        } else if is_top_level && file_name.ends_with(".runtimeconfig.json") {
+            Self::RuntimeConfigJson
+        } else if Path::new(file_name)
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("pdb"))
+        {
+            Self::Symbols
+        } else if MetadataReader::new(data).is_ok() {
+            Self::Assembly
+        } else if is_native_image(data) {
+            Self::NativeBinary
+        } else {
+            Self::Unknown
+        }
+    }
+}
+
+/// Returns whether the given data starts like a PE, ELF or Mach-O image.
+fn is_native_image(data: &[u8]) -> bool {
+    data.starts_with(b"MZ")
+        || data.starts_with(b"\x7fELF")
+        || data.starts_with(&[0xCF, 0xFA, 0xED, 0xFE])
+        || data.starts_with(&[0xCE, 0xFA, 0xED, 0xFE])
+        || data.starts_with(&[0xCA, 0xFE, 0xBA, 0xBE])
+}
+
+fn write_u64(out: &mut Vec<u8>, value: u64) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+/// Writes a string prefixed by its 7-bit encoded length, like `BinaryWriter.Write(string)`.
+#[allow(clippy::cast_possible_truncation)]
+fn write_string(out: &mut Vec<u8>, value: &str) {
+    let mut length = value.len();
+    loop {
+        let byte = (length & 0x7F) as u8;
+        length >>= 7;
+        if length == 0 {
+            out.push(byte);
+            break;
+        }
+        out.push(byte | 0x80);
+    }
+    out.extend_from_slice(value.as_bytes());
+}
@@ -0,0 +1,409 @@
+#[cfg(feature = "serde")]
+use std::{
+    env, fs,
+    io::{self, Write as _},
+    path::PathBuf,
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use std::{fmt, marker::PhantomData, mem::ManuallyDrop, ptr};
+
+use coreclr_hosting_shared::StatusCode;
+
+#[cfg(feature = "serde")]
+use crate::runtime_config::RuntimeConfig;
+use crate::{
+    app::Argv,
+    delegate::{LoadAssemblyAndGetFunctionPointer, RuntimeDelegate},
+    error::{Error, ExitCode, HostingError, HostingSuccess},
+    error_writer, hostfxr_handle, hostfxr_initialize_parameters,
+    managed::AssemblyDelegateLoader,
+    path_list::PathList,
+    pdcstr::{PdCStr, PdCString, ToPdCStr},
+    wrapper::Hostfxr,
+};
+
+/// Marker for a [`HostfxrContext`] initialized using [`hostfxr_initialize_for_runtime_config`](Hostfxr::hostfxr_initialize_for_runtime_config).
+#[derive(Debug)]
+pub enum InitializedForRuntimeConfig {}
+
+/// Marker for a [`HostfxrContext`] initialized using [`hostfxr_initialize_for_dotnet_command_line`](Hostfxr::hostfxr_initialize_for_dotnet_command_line).
+#[derive(Debug)]
+pub enum InitializedForCommandLine {}
+
+/// An initialized host context, which is closed when dropped.
+///
+/// The type parameter `I` records how the context was initialized
+/// (either [`InitializedForRuntimeConfig`] or [`InitializedForCommandLine`]).
+pub struct HostfxrContext<'lib, I> {
+    hostfxr: &'lib Hostfxr,
+    handle: hostfxr_handle,
+    is_primary: bool,
+    _context_type: PhantomData<I>,
+}
+
+impl<'lib> HostfxrContext<'lib, InitializedForRuntimeConfig> {
+    /// Initializes the hosting components using a `.runtimeconfig.json` file.
+    ///
+    /// See [`hostfxr_initialize_for_runtime_config`](Hostfxr::hostfxr_initialize_for_runtime_config) for details.
+    pub fn initialize_for_runtime_config(
+        hostfxr: &'lib Hostfxr,
+        runtime_config_path: impl ToPdCStr,
+        parameters: Option<&hostfxr_initialize_parameters>,
+    ) -> Result<Self, Error> {
+        let runtime_config_path = runtime_config_path.to_pdcstr()?;
+        let mut handle = ptr::null();
+        let success = call(hostfxr, || unsafe {
+            hostfxr.hostfxr_initialize_for_runtime_config(
+                runtime_config_path.as_ptr(),
+                parameters.map_or(ptr::null(), ptr::from_ref),
+                &raw mut handle,
+            )
+        })?;
+        Ok(unsafe { Self::from_handle(hostfxr, handle, success == HostingSuccess::Success) })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'lib> HostfxrContext<'lib, InitializedForRuntimeConfig> {
+    /// Initializes the hosting components using the given runtime config instead of a `.runtimeconfig.json` file,
+    /// e.g. for hosting a component whose framework is known in code.
+    ///
+    /// The config is written to a temporary file that is only accessible to the current user and removed once the
+    /// context is initialized, as the hosting components only read it during initialization.
+    /// See [`initialize_for_runtime_config`](Self::initialize_for_runtime_config) for details.
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "serde")))]
+    pub fn initialize_for_runtime_config_model(
+        hostfxr: &'lib Hostfxr,
+        runtime_config: &RuntimeConfig,
+        parameters: Option<&hostfxr_initialize_parameters>,
+    ) -> Result<Self, Error> {
+        let file = TempRuntimeConfig::write(runtime_config).map_err(|err| Error::Io(err.kind()))?;
+        Self::initialize_for_runtime_config(hostfxr, file.path.as_path(), parameters)
+    }
+}
+
+impl<'lib> HostfxrContext<'lib, InitializedForCommandLine> {
+    /// Initializes the hosting components for running an application using the given command line,
+    /// e.g. `["app.dll", "--flag"]` (without the path of the host executable).
+    ///
+    /// See [`hostfxr_initialize_for_dotnet_command_line`](Hostfxr::hostfxr_initialize_for_dotnet_command_line) for details.
+    pub fn initialize_for_dotnet_command_line(
+        hostfxr: &'lib Hostfxr,
+        command_line: impl IntoIterator<Item = impl ToPdCStr>,
+        parameters: Option<&hostfxr_initialize_parameters>,
+    ) -> Result<Self, Error> {
+        let argv = Argv::new(command_line)?;
+        let mut handle = ptr::null();
+        let success = call(hostfxr, || unsafe {
+            hostfxr.hostfxr_initialize_for_dotnet_command_line(
+                argv.argc(),
+                argv.as_ptr(),
+                parameters.map_or(ptr::null(), ptr::from_ref),
+                &raw mut handle,
+            )
+        })?;
+        Ok(unsafe { Self::from_handle(hostfxr, handle, success == HostingSuccess::Success) })
+    }
+
+    /// Loads the runtime and runs the application, returning its exit code once it completes.
+    ///
+    /// A context can only be run once, so it is consumed and closed afterwards.
+    /// See [`HostingError::check_exit_code`] for how exit codes are told apart from hosting failures and
+    /// [`hostfxr_run_app`](Hostfxr::hostfxr_run_app) for details.
+    pub fn run(self) -> Result<ExitCode, HostingError> {
+        let (code, messages) = error_writer::capture_errors(self.hostfxr, || unsafe {
+            self.hostfxr.hostfxr_run_app(self.handle)
+        });
+        HostingError::check_exit_code(code, messages)
+    }
+}
+
+impl<'lib, I> HostfxrContext<'lib, I> {
+    /// Creates a context from a raw handle, taking ownership of it.
+    ///
+    /// # Safety
+    /// `handle` must be a valid host context handle initialized in the way described by `I`.
+    #[must_use]
+    pub unsafe fn from_handle(
+        hostfxr: &'lib Hostfxr,
+        handle: hostfxr_handle,
+        is_primary: bool,
+    ) -> Self {
+        Self {
+            hostfxr,
+            handle,
+            is_primary,
+            _context_type: PhantomData,
+        }
+    }
+
+    /// Returns the raw handle of this context.
+    #[must_use]
+    pub const fn handle(&self) -> hostfxr_handle {
+        self.handle
+    }
+
+    /// Returns the library this context belongs to.
+    #[must_use]
+    pub const fn hostfxr(&self) -> &'lib Hostfxr {
+        self.hostfxr
+    }
+
+    /// Returns whether this is the first context initialized in the process, i.e. the one that loads the runtime.
+    #[must_use]
+    pub const fn is_primary(&self) -> bool {
+        self.is_primary
+    }
+
+    /// Gets the value of the given runtime property, or [`None`] if it is not set.
+    ///
+    /// See [`hostfxr_get_runtime_property_value`](Hostfxr::hostfxr_get_runtime_property_value) for details.
+    pub fn runtime_property_value(&self, name: impl ToPdCStr) -> Result<Option<PdCString>, Error> {
+        let name = name.to_pdcstr()?;
+        let mut value = ptr::null();
+        let result = call(self.hostfxr, || unsafe {
+            self.hostfxr.hostfxr_get_runtime_property_value(
+                self.handle,
+                name.as_ptr(),
+                &raw mut value,
+            )
+        });
+        match result {
+            Ok(_) if value.is_null() => Ok(None),
+            Ok(_) => Ok(Some(unsafe { PdCStr::from_ptr(value) }.to_owned())),
+            Err(err) if err.is(StatusCode::HostPropertyNotFound) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Sets the value of the given runtime property, which is only possible before the runtime is loaded.
+    ///
+    /// See [`hostfxr_set_runtime_property_value`](Hostfxr::hostfxr_set_runtime_property_value) for details.
+    pub fn set_runtime_property_value(
+        &self,
+        name: impl ToPdCStr,
+        value: impl ToPdCStr,
+    ) -> Result<(), Error> {
+        let name = name.to_pdcstr()?;
+        let value = value.to_pdcstr()?;
+        call(self.hostfxr, || unsafe {
+            self.hostfxr.hostfxr_set_runtime_property_value(
+                self.handle,
+                name.as_ptr(),
+                value.as_ptr(),
+            )
+        })?;
+        Ok(())
+    }
+
+    /// Removes the given runtime property, which is only possible before the runtime is loaded.
+    ///
+    /// See [`hostfxr_set_runtime_property_value`](Hostfxr::hostfxr_set_runtime_property_value) for details.
+    pub fn remove_runtime_property(&self, name: impl ToPdCStr) -> Result<(), Error> {
+        let name = name.to_pdcstr()?;
+        call(self.hostfxr, || unsafe {
+            self.hostfxr
+                .hostfxr_set_runtime_property_value(self.handle, name.as_ptr(), ptr::null())
+        })?;
+        Ok(())
+    }
+
+    /// Gets the value of the given runtime property as a [`PathList`], or [`None`] if it is not set.
+    pub fn runtime_property_path_list(
+        &self,
+        name: impl ToPdCStr,
+    ) -> Result<Option<PathList>, Error> {
+        Ok(self
+            .runtime_property_value(name)?
+            .map(|value| PathList::from_pdcstr(&value)))
+    }
+
+    /// Sets the value of the given runtime property to a [`PathList`], which is only possible before the runtime is loaded.
+    pub fn set_runtime_property_path_list(
+        &self,
+        name: impl ToPdCStr,
+        paths: &PathList,
+    ) -> Result<(), Error> {
+        self.set_runtime_property_value(name, paths.to_pdcstring())
+    }
+
+    /// Gets the `TRUSTED_PLATFORM_ASSEMBLIES` runtime property, the paths of the assemblies of the app and its frameworks.
+    pub fn trusted_platform_assemblies(&self) -> Result<Option<PathList>, Error> {
+        self.runtime_property_path_list("TRUSTED_PLATFORM_ASSEMBLIES")
+    }
+
+    /// Gets the `NATIVE_DLL_SEARCH_DIRECTORIES` runtime property, the directories searched for native libraries.
+    pub fn native_dll_search_directories(&self) -> Result<Option<PathList>, Error> {
+        self.runtime_property_path_list("NATIVE_DLL_SEARCH_DIRECTORIES")
+    }
+
+    /// Gets the `PLATFORM_RESOURCE_ROOTS` runtime property, the directories searched for satellite assemblies.
+    pub fn platform_resource_roots(&self) -> Result<Option<PathList>, Error> {
+        self.runtime_property_path_list("PLATFORM_RESOURCE_ROOTS")
+    }
+
+    /// Gets the `APP_PATHS` runtime property, the directories searched for assemblies of the app
+    /// (only set if the app has no `.deps.json`).
+    pub fn app_paths(&self) -> Result<Option<PathList>, Error> {
+        self.runtime_property_path_list("APP_PATHS")
+    }
+
+    /// Gets a typed delegate from the currently loaded runtime or from a newly created one.
+    ///
+    /// Contexts initialized for a command line only support
+    /// [`LoadAssemblyAndGetFunctionPointer`] and
+    /// [`GetFunctionPointer`](crate::delegate::GetFunctionPointer).
+    ///
+    /// See [`hostfxr_get_runtime_delegate`](Hostfxr::hostfxr_get_runtime_delegate) for details.
+    pub fn get_delegate<D: RuntimeDelegate>(&self) -> Result<D::Fn, HostingError> {
+        let mut delegate = ptr::null();
+        call(self.hostfxr, || unsafe {
+            self.hostfxr
+                .hostfxr_get_runtime_delegate(self.handle, D::TYPE, &raw mut delegate)
+        })?;
+        if delegate.is_null() {
+            return Err(HostingError::new(StatusCode::HostApiFailed as u32));
+        }
+        Ok(unsafe { D::from_ptr(delegate) })
+    }
+
+    /// Gets a delegate loader for loading an assembly and retrieving function pointers to its static methods.
+    ///
+    /// See [`AssemblyDelegateLoader`] for details.
+    pub fn get_delegate_loader(&self) -> Result<AssemblyDelegateLoader, HostingError> {
+        let delegate = self.get_delegate::<LoadAssemblyAndGetFunctionPointer>()?;
+        Ok(unsafe { AssemblyDelegateLoader::from_delegate(delegate) })
+    }
+
+    /// Gets a loader for retrieving function pointers to static methods of types in the default load context.
+    ///
+    /// See [`FunctionPointerLoader`](crate::managed::FunctionPointerLoader) for details.
+    #[cfg(feature = "net5_0")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "net5_0")))]
+    pub fn get_function_pointer_loader(
+        &self,
+    ) -> Result<crate::managed::FunctionPointerLoader, HostingError> {
+        let delegate = self.get_delegate::<crate::delegate::GetFunctionPointer>()?;
+        Ok(unsafe { crate::managed::FunctionPointerLoader::from_delegate(delegate) })
+    }
+
+    /// Gets a loader for loading assemblies from a path.
+    ///
+    /// See [`AssemblyLoader`](crate::managed::AssemblyLoader) for details.
+    #[cfg(feature = "net8_0")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "net8_0")))]
+    pub fn get_assembly_loader(&self) -> Result<crate::managed::AssemblyLoader, HostingError> {
+        let delegate = self.get_delegate::<crate::delegate::LoadAssembly>()?;
+        Ok(unsafe { crate::managed::AssemblyLoader::from_delegate(delegate) })
+    }
+
+    /// Gets a loader for loading assemblies from memory.
+    ///
+    /// See [`AssemblyBytesLoader`](crate::managed::AssemblyBytesLoader) for details.
+    #[cfg(feature = "net8_0")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "net8_0")))]
+    pub fn get_assembly_bytes_loader(
+        &self,
+    ) -> Result<crate::managed::AssemblyBytesLoader, HostingError> {
+        let delegate = self.get_delegate::<crate::delegate::LoadAssemblyBytes>()?;
+        Ok(unsafe { crate::managed::AssemblyBytesLoader::from_delegate(delegate) })
+    }
+
+    /// Closes this context.
+    ///
+    /// Dropping the context closes it as well, but ignores any error.
+    pub fn close(self) -> Result<(), HostingError> {
+        let this = ManuallyDrop::new(self);
+        this.close_raw()
+    }
+
+    fn close_raw(&self) -> Result<(), HostingError> {
+        call(self.hostfxr, || unsafe {
+            self.hostfxr.hostfxr_close(self.handle)
+        })
+        .map(|_| ())
+    }
+}
+
+impl<I> Drop for HostfxrContext<'_, I> {
+    fn drop(&mut self) {
+        let _ = self.close_raw();
+    }
+}
+
+impl<I> fmt::Debug for HostfxrContext<'_, I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HostfxrContext")
+            .field("handle", &self.handle)
+            .field("is_primary", &self.is_primary)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A runtime config written to a private temporary directory, which is removed when dropped.
+#[cfg(feature = "serde")]
+struct TempRuntimeConfig {
+    dir: PathBuf,
+    path: PathBuf,
+}
+
+#[cfg(feature = "serde")]
+impl TempRuntimeConfig {
+    fn write(runtime_config: &RuntimeConfig) -> io::Result<Self> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        let mut attempts = 0;
+        let dir = loop {
+            let nanos = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |duration| duration.subsec_nanos());
+            let dir = env::temp_dir().join(format!(
+                "hostfxr-sys-{}-{}-{nanos:08x}",
+                process::id(),
+                COUNTER.fetch_add(1, Ordering::Relaxed)
+            ));
+            let mut builder = fs::DirBuilder::new();
+            #[cfg(unix)]
+            std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+            match builder.create(&dir) {
+                Ok(()) => break dir,
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists && attempts < 16 => {
+                    attempts += 1;
+                }
+                Err(err) => return Err(err),
+            }
+        };
+
+        // from here on the directory is removed on failure.
+        let this = Self {
+            path: dir.join("runtimeconfig.json"),
+            dir,
+        };
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let mut file = options.open(&this.path)?;
+        file.write_all(runtime_config.to_json().as_bytes())?;
+        file.sync_all()?;
+        Ok(this)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Drop for TempRuntimeConfig {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Performs a hosting call, capturing the errors reported during it.
+pub(crate) fn call(
+    hostfxr: &Hostfxr,
+    f: impl FnOnce() -> i32,
+) -> Result<HostingSuccess, HostingError> {
+    let (code, messages) = error_writer::capture_errors(hostfxr, f);
+    HostingError::check(code).map_err(|err| err.with_messages(messages))
+}
@@ -0,0 +1,79 @@
+use crate::hostfxr_delegate_type;
+
+/// A runtime delegate that can be retrieved using [`hostfxr_get_runtime_delegate`].
+///
+/// Links each [`hostfxr_delegate_type`] to the signature of the function pointer returned for it,
+/// so that [`HostfxrContext::get_delegate`] can return correctly typed function pointers.
+///
+/// This trait is sealed and implemented by the marker types in this module.
+///
+/// [`hostfxr_get_runtime_delegate`]: crate::wrapper::Hostfxr::hostfxr_get_runtime_delegate
+/// [`HostfxrContext::get_delegate`]: crate::context::HostfxrContext::get_delegate
+pub trait RuntimeDelegate: sealed::Sealed {
+    /// The delegate type passed to [`hostfxr_get_runtime_delegate`](crate::wrapper::Hostfxr::hostfxr_get_runtime_delegate).
+    const TYPE: hostfxr_delegate_type;
+
+    /// The function pointer type of the delegate.
+    type Fn: Copy;
+
+    /// Converts the pointer returned by [`hostfxr_get_runtime_delegate`](crate::wrapper::Hostfxr::hostfxr_get_runtime_delegate) into the function pointer type.
+    ///
+    /// # Safety
+    /// `ptr` must be a non-null delegate returned for [`Self::TYPE`](RuntimeDelegate::TYPE).
+    #[doc(hidden)]
+    unsafe fn from_ptr(ptr: *const ()) -> Self::Fn;
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+macro_rules! runtime_delegates {
+    ($(
+        $( #[$attrs:meta] )*
+        $name:ident => $delegate_type:ident as $fn_type:ident,
+    )*) => {
+        $(
+            $( #[$attrs] )*
+            #[derive(Debug)]
+            pub enum $name {}
+
+            $( #[$attrs] )*
+            impl sealed::Sealed for $name {}
+
+            $( #[$attrs] )*
+            impl RuntimeDelegate for $name {
+                const TYPE: hostfxr_delegate_type = hostfxr_delegate_type::$delegate_type;
+                type Fn = crate::$fn_type;
+
+                unsafe fn from_ptr(ptr: *const ()) -> Self::Fn {
+                    unsafe { core::mem::transmute::<*const (), Self::Fn>(ptr) }
+                }
+            }
+        )*
+    };
+}
+
+runtime_delegates! {
+    /// Marker for [`hdt_load_assembly_and_get_function_pointer`](hostfxr_delegate_type::hdt_load_assembly_and_get_function_pointer),
+    /// which returns a [`load_assembly_and_get_function_pointer_fn`](crate::load_assembly_and_get_function_pointer_fn).
+    LoadAssemblyAndGetFunctionPointer => hdt_load_assembly_and_get_function_pointer as load_assembly_and_get_function_pointer_fn,
+
+    /// Marker for [`hdt_get_function_pointer`](hostfxr_delegate_type::hdt_get_function_pointer),
+    /// which returns a [`get_function_pointer_fn`](crate::get_function_pointer_fn).
+    #[cfg(feature = "net5_0")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "net5_0")))]
+    GetFunctionPointer => hdt_get_function_pointer as get_function_pointer_fn,
+
+    /// Marker for [`hdt_load_assembly`](hostfxr_delegate_type::hdt_load_assembly),
+    /// which returns a [`load_assembly_fn`](crate::load_assembly_fn).
+    #[cfg(feature = "net8_0")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "net8_0")))]
+    LoadAssembly => hdt_load_assembly as load_assembly_fn,
+
+    /// Marker for [`hdt_load_assembly_bytes`](hostfxr_delegate_type::hdt_load_assembly_bytes),
+    /// which returns a [`load_assembly_bytes_fn`](crate::load_assembly_bytes_fn).
+    #[cfg(feature = "net8_0")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "net8_0")))]
+    LoadAssemblyBytes => hdt_load_assembly_bytes as load_assembly_bytes_fn,
+}
@@ -0,0 +1,498 @@
+use std::{error, fmt, fs, io, path::Path};
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
+
+/// The contents of a `.deps.json` file, which lists the dependencies of an application or framework and the assets
+/// they contribute.
+///
+/// Keys not modeled by this type are kept in [`extra`](Self::extra) (and the `extra` fields of the nested types),
+/// and all maps keep their original order, so reading and writing a file does not lose any information.
+///
+/// See the [documentation of the file format](https://github.com/dotnet/sdk/blob/main/documentation/specs/runtime-configuration-file.md)
+/// for details.
+///
+/// # Example
+/// ```
+/// use hostfxr_sys::deps_json::{DepsJson, Library, TargetLibrary};
+///
+/// let mut deps = DepsJson::new(".NETCoreApp,Version=v8.0");
+/// deps.add_library(
+///     "Component",
+///     "1.0.0",
+///     Library::project(),
+///     TargetLibrary::new().with_runtime_asset("Component.dll"),
+/// );
+/// assert_eq!(deps.target_libraries().count(), 1);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DepsJson {
+    /// The target whose assets are used at runtime.
+    ///
+    /// Like the host, files without a `runtimeTarget` and files which only specify its name (as a string) are accepted,
+    /// the latter is written back as an object.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_runtime_target",
+        skip_serializing_if = "is_default"
+    )]
+    pub runtime_target: RuntimeTarget,
+    /// The options the application was compiled with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compilation_options: Option<CompilationOptions>,
+    /// The dependencies and their assets for each target, keyed by target name.
+    #[serde(default)]
+    pub targets: IndexMap<String, Target>,
+    /// The metadata of each library, keyed by `name/version`.
+    #[serde(default)]
+    pub libraries: IndexMap<String, Library>,
+    /// The RID fallback graph, mapping each runtime identifier to the ones it is compatible with, most specific first.
+    ///
+    /// Only present in the files of self-contained applications and (older) frameworks.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub runtimes: IndexMap<String, Vec<String>>,
+    /// Any other top-level keys.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// The dependencies of a target, keyed by `name/version`.
+pub type Target = IndexMap<String, TargetLibrary>;
+
+/// The `runtimeTarget` object of a [`DepsJson`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeTarget {
+    /// The name of the target, e.g. `.NETCoreApp,Version=v8.0`, followed by `/<rid>` for self-contained applications.
+    pub name: String,
+    /// The signature of the target, usually empty.
+    #[serde(default)]
+    pub signature: String,
+    /// Any other keys.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl RuntimeTarget {
+    /// Returns the framework part of the target name, e.g. `.NETCoreApp,Version=v8.0`.
+    #[must_use]
+    pub fn framework(&self) -> &str {
+        self.name
+            .split_once('/')
+            .map_or(&self.name, |(framework, _)| framework)
+    }
+
+    /// Returns the runtime identifier part of the target name, which is only present for self-contained applications.
+    #[must_use]
+    pub fn rid(&self) -> Option<&str> {
+        self.name.split_once('/').map(|(_, rid)| rid)
+    }
+}
+
+/// Deserializes a [`RuntimeTarget`] from either an object or, like older files, only its name.
+fn deserialize_runtime_target<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<RuntimeTarget, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum RuntimeTargetRepr {
+        Name(String),
+        Target(RuntimeTarget),
+    }
+
+    Ok(match RuntimeTargetRepr::deserialize(deserializer)? {
+        RuntimeTargetRepr::Name(name) => RuntimeTarget {
+            name,
+            ..RuntimeTarget::default()
+        },
+        RuntimeTargetRepr::Target(target) => target,
+    })
+}
+
+fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+/// The `compilationOptions` object of a [`DepsJson`], which is empty unless the application preserves its compilation context.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompilationOptions {
+    /// The defined preprocessor symbols.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub defines: Option<Vec<String>>,
+    /// The version of the language, e.g. `12.0`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language_version: Option<String>,
+    /// The target platform, e.g. `AnyCPU`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub platform: Option<String>,
+    /// Whether unsafe code is allowed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_unsafe: Option<bool>,
+    /// Whether warnings are treated as errors.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warnings_as_errors: Option<bool>,
+    /// Whether optimizations are enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub optimize: Option<bool>,
+    /// The key file used for signing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_file: Option<String>,
+    /// Whether the assembly is delay signed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delay_sign: Option<bool>,
+    /// Whether the assembly is public signed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_sign: Option<bool>,
+    /// The kind of debug information, e.g. `portable`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub debug_type: Option<String>,
+    /// Whether the assembly has an entry point.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emit_entry_point: Option<bool>,
+    /// Whether XML documentation is generated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub xml_doc: Option<bool>,
+    /// Any other keys.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// A library in a [`Target`], i.e. its dependencies and the assets it contributes on that target.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TargetLibrary {
+    /// The versions of the libraries this library depends on, keyed by name.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub dependencies: IndexMap<String, String>,
+    /// The managed assemblies, keyed by path relative to the library.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub runtime: IndexMap<String, AssetInfo>,
+    /// The native libraries, keyed by path relative to the library.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub native: IndexMap<String, AssetInfo>,
+    /// The RID-specific assets, keyed by path relative to the library.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub runtime_targets: IndexMap<String, RuntimeTargetAsset>,
+    /// The satellite resource assemblies, keyed by path relative to the library.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub resources: IndexMap<String, ResourceAsset>,
+    /// The reference assemblies used for compilation, keyed by path relative to the library.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub compile: IndexMap<String, AssetInfo>,
+    /// Whether the library is only used for compilation and ignored at runtime.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compile_only: Option<bool>,
+    /// Any other keys.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl TargetLibrary {
+    /// Creates a library without dependencies or assets.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a dependency on the given version of a library.
+    #[must_use]
+    pub fn with_dependency(mut self, name: impl Into<String>, version: impl Into<String>) -> Self {
+        self.dependencies.insert(name.into(), version.into());
+        self
+    }
+
+    /// Adds a managed assembly at the given path relative to the library.
+    #[must_use]
+    pub fn with_runtime_asset(mut self, path: impl Into<String>) -> Self {
+        self.runtime.insert(path.into(), AssetInfo::default());
+        self
+    }
+
+    /// Adds a native library at the given path relative to the library.
+    #[must_use]
+    pub fn with_native_asset(mut self, path: impl Into<String>) -> Self {
+        self.native.insert(path.into(), AssetInfo::default());
+        self
+    }
+
+    /// Adds a satellite resource assembly for the given culture at the given path relative to the library.
+    #[must_use]
+    pub fn with_resource_asset(
+        mut self,
+        path: impl Into<String>,
+        locale: impl Into<String>,
+    ) -> Self {
+        self.resources.insert(
+            path.into(),
+            ResourceAsset {
+                locale: locale.into(),
+                extra: Map::new(),
+            },
+        );
+        self
+    }
+
+    /// Returns whether the library is only used for compilation.
+    #[must_use]
+    pub fn is_compile_only(&self) -> bool {
+        self.compile_only == Some(true)
+    }
+}
+
+/// The metadata of a managed or native asset.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetInfo {
+    /// The assembly version, e.g. `8.0.0.0`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assembly_version: Option<String>,
+    /// The file version, e.g. `8.0.23.53103`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_version: Option<String>,
+    /// The path of the asset relative to the application, if it differs from the one it is keyed by.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub local_path: Option<String>,
+    /// Any other keys.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// A RID-specific asset in [`TargetLibrary::runtime_targets`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeTargetAsset {
+    /// The runtime identifier the asset is for, e.g. `linux-x64`.
+    pub rid: String,
+    /// The kind of the asset.
+    pub asset_type: AssetType,
+    /// The version information of the asset.
+    #[serde(flatten)]
+    pub info: AssetInfo,
+}
+
+/// The kind of a [`RuntimeTargetAsset`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AssetType {
+    /// A managed assembly.
+    Runtime,
+    /// A native library.
+    Native,
+}
+
+/// A satellite resource assembly in [`TargetLibrary::resources`].
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceAsset {
+    /// The culture of the resources, e.g. `de`.
+    pub locale: String,
+    /// Any other keys.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// The metadata of a library in [`DepsJson::libraries`].
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Library {
+    /// The kind of the library, e.g. `project`, `package`, `reference` or `runtimepack`.
+    #[serde(rename = "type")]
+    pub library_type: String,
+    /// Whether the library can be serviced.
+    #[serde(default)]
+    pub serviceable: bool,
+    /// The SHA-512 hash of the package, empty for projects.
+    #[serde(default)]
+    pub sha512: String,
+    /// The path of the package in a package cache, e.g. `newtonsoft.json/13.0.3`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// The path of the hash file of the package relative to [`path`](Self::path).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash_path: Option<String>,
+    /// The name of the runtime store manifest the package was resolved from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub runtime_store_manifest_name: Option<String>,
+    /// Any other keys.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Library {
+    /// Creates the metadata of a project, whose assets are located next to the application.
+    #[must_use]
+    pub fn project() -> Self {
+        Self {
+            library_type: "project".to_owned(),
+            ..Self::default()
+        }
+    }
+
+    /// Creates the metadata of a package with the given hash and path in the package cache.
+    #[must_use]
+    pub fn package(sha512: impl Into<String>, path: impl Into<String>) -> Self {
+        Self {
+            library_type: "package".to_owned(),
+            serviceable: true,
+            sha512: sha512.into(),
+            path: Some(path.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Returns whether the library is a project.
+    #[must_use]
+    pub fn is_project(&self) -> bool {
+        self.library_type.eq_ignore_ascii_case("project")
+    }
+
+    /// Returns whether the library is a package.
+    #[must_use]
+    pub fn is_package(&self) -> bool {
+        self.library_type.eq_ignore_ascii_case("package")
+    }
+}
+
+/// A library of the runtime target together with its metadata, see [`DepsJson::target_libraries`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ResolvedLibrary<'a> {
+    /// The name of the library.
+    pub name: &'a str,
+    /// The version of the library.
+    pub version: &'a str,
+    /// The dependencies and assets of the library.
+    pub target: &'a TargetLibrary,
+    /// The metadata of the library, if listed in [`DepsJson::libraries`].
+    pub library: Option<&'a Library>,
+}
+
+impl DepsJson {
+    /// Creates an empty file for the given runtime target.
+    #[must_use]
+    pub fn new(runtime_target: impl Into<String>) -> Self {
+        let name = runtime_target.into();
+        let mut targets = IndexMap::new();
+        targets.insert(name.clone(), Target::new());
+        Self {
+            runtime_target: RuntimeTarget {
+                name,
+                ..RuntimeTarget::default()
+            },
+            compilation_options: Some(CompilationOptions::default()),
+            targets,
+            ..Self::default()
+        }
+    }
+
+    /// Parses the contents of a `.deps.json` file.
+    pub fn from_json(json: &str) -> Result<Self, DepsJsonError> {
+        serde_json::from_str(json).map_err(DepsJsonError::from)
+    }
+
+    /// Reads the `.deps.json` file at the given path.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, DepsJsonError> {
+        let json = fs::read_to_string(path).map_err(|err| DepsJsonError::Io(err.kind()))?;
+        Self::from_json(&json)
+    }
+
+    /// Formats the file as pretty-printed JSON.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("deps json is always serializable")
+    }
+
+    /// Writes the file as pretty-printed JSON to the given path.
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), DepsJsonError> {
+        fs::write(path, self.to_json()).map_err(|err| DepsJsonError::Io(err.kind()))
+    }
+
+    /// Returns the libraries of the [runtime target](Self::runtime_target), if the file contains it.
+    #[must_use]
+    pub fn target(&self) -> Option<&Target> {
+        self.targets.get(&self.runtime_target.name)
+    }
+
+    /// Returns the libraries of the runtime target mutably, inserting an empty target if there is none.
+    pub fn target_mut(&mut self) -> &mut Target {
+        self.targets
+            .entry(self.runtime_target.name.clone())
+            .or_default()
+    }
+
+    /// Returns the libraries of the runtime target that are used at runtime, in the order they are listed in,
+    /// which is the order the host resolves their assets in.
+    pub fn target_libraries(&self) -> impl Iterator<Item = ResolvedLibrary<'_>> {
+        self.target()
+            .into_iter()
+            .flatten()
+            .filter(|(_, target)| !target.is_compile_only())
+            .map(|(key, target)| {
+                let (name, version) = split_library_key(key);
+                ResolvedLibrary {
+                    name,
+                    version,
+                    target,
+                    library: self.libraries.get(key),
+                }
+            })
+    }
+
+    /// Returns the library of the runtime target with the given name, ignoring case like the host does.
+    #[must_use]
+    pub fn find_library(&self, name: &str) -> Option<ResolvedLibrary<'_>> {
+        self.target_libraries()
+            .find(|library| library.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Adds a library to the runtime target and its metadata to [`libraries`](Self::libraries),
+    /// replacing any library with the same name and version.
+    pub fn add_library(
+        &mut self,
+        name: &str,
+        version: &str,
+        library: Library,
+        target: TargetLibrary,
+    ) {
+        let key = format!("{name}/{version}");
+        self.target_mut().insert(key.clone(), target);
+        self.libraries.insert(key, library);
+    }
+}
+
+/// Splits a library key of the form `name/version` into its name and version.
+///
+/// The version is empty if the key does not contain a `/`.
+#[must_use]
+pub fn split_library_key(key: &str) -> (&str, &str) {
+    key.split_once('/').unwrap_or((key, ""))
+}
+
+/// Error returned when reading a deps file fails.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DepsJsonError {
+    /// The file could not be read or written.
+    Io(io::ErrorKind),
+    /// The file is not valid JSON or does not match the format.
+    Json(String),
+}
+
+impl From<serde_json::Error> for DepsJsonError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Json(err.to_string())
+    }
+}
+
+impl fmt::Display for DepsJsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(kind) => write!(f, "failed to access deps file: {kind}"),
+            Self::Json(message) => write!(f, "invalid deps file: {message}"),
+        }
+    }
+}
+
+impl error::Error for DepsJsonError {}
@@ -0,0 +1,628 @@
+use std::{
+    error,
+    ffi::OsString,
+    fmt, fs, io,
+    path::{Path, PathBuf, MAIN_SEPARATOR_STR},
+};
+
+use indexmap::IndexMap;
+use serde_json::Value;
+
+use crate::{
+    deps_json::{AssetInfo, AssetType, DepsJson, DepsJsonError, ResolvedLibrary},
+    install,
+    path_list::{PathList, PathListError},
+    runtime_config::RuntimeConfig,
+};
+
+/// Predicts the assets hostpolicy resolves for an application, i.e. the runtime properties
+/// `TRUSTED_PLATFORM_ASSEMBLIES`, `NATIVE_DLL_SEARCH_DIRECTORIES` and `PLATFORM_RESOURCE_ROOTS`,
+/// without initializing the hosting components.
+///
+/// The assets are resolved from the `.deps.json` files of the application and its frameworks like the host does:
+/// - RID-specific assets are selected using the fallbacks of the target runtime identifier,
+/// - assets are looked up next to the application or in the directory of their framework (RID-specific ones at their
+///   relative path, e.g. `runtimes/linux-x64/native/`), and assets of packages are also looked up in the probing paths,
+///   which use the layout of the NuGet package cache,
+/// - if an application and a framework (or two frameworks) contain the same assembly, the framework wins unless the
+///   other one has a higher assembly version (or a higher file version if the assembly versions are equal),
+/// - if a `.deps.json` file is missing, all assemblies in its directory are used.
+///
+/// Servicing and the runtime store are not taken into account. The resolved paths are in the order they are resolved,
+/// which may differ from the one of the host, so results should be compared ignoring the order
+/// (see [`PathList::difference`]).
+///
+/// # Example
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use hostfxr_sys::{deps_resolver::DepsResolver, runtime_config::RuntimeConfig};
+///
+/// let runtime_config = RuntimeConfig::read_with_dev_config("bin/App.runtimeconfig.json")?;
+/// let assets = DepsResolver::new("bin/App.dll")
+///     .with_runtime_config(&runtime_config)
+///     .with_framework("/usr/share/dotnet/shared/Microsoft.NETCore.App/8.0.0")
+///     .resolve()?;
+/// for missing in &assets.missing_assets {
+///     println!("{} of {} was not found", missing.path, missing.library);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct DepsResolver {
+    app_path: PathBuf,
+    deps_file: Option<PathBuf>,
+    frameworks: Vec<PathBuf>,
+    probing_paths: Vec<PathBuf>,
+    rid: Option<String>,
+    rid_fallbacks: Option<Vec<String>>,
+    use_rid_graph: bool,
+}
+
+/// The assets resolved by a [`DepsResolver`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ResolvedAssets {
+    /// The paths of the managed assemblies, as in the `TRUSTED_PLATFORM_ASSEMBLIES` runtime property.
+    pub trusted_platform_assemblies: PathList,
+    /// The directories containing native libraries, as in the `NATIVE_DLL_SEARCH_DIRECTORIES` runtime property.
+    pub native_dll_search_directories: PathList,
+    /// The directories containing the culture directories of satellite resource assemblies,
+    /// as in the `PLATFORM_RESOURCE_ROOTS` runtime property.
+    pub platform_resource_roots: PathList,
+    /// The assets listed in a `.deps.json` file which could not be found.
+    pub missing_assets: Vec<MissingAsset>,
+}
+
+/// An asset which could not be found, see [`ResolvedAssets::missing_assets`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MissingAsset {
+    /// The library containing the asset, as `name/version`.
+    pub library: String,
+    /// The path of the asset relative to the library.
+    pub path: String,
+    /// The `.deps.json` file listing the asset.
+    pub deps_file: PathBuf,
+}
+
+/// An assembly on the trusted platform assemblies list, which may be replaced by one with a higher version.
+struct ResolvedAssembly {
+    path: PathBuf,
+    level: usize,
+    version: (Option<Vec<u64>>, Option<Vec<u64>>),
+}
+
+/// The state of a resolution in progress.
+#[derive(Default)]
+struct Resolution {
+    assemblies: IndexMap<String, ResolvedAssembly>,
+    native_dirs: Vec<PathBuf>,
+    resource_roots: Vec<PathBuf>,
+    missing_assets: Vec<MissingAsset>,
+}
+
+impl Resolution {
+    /// Adds an assembly unless one with the same name was already added by the same or a higher level with a
+    /// higher version.
+    fn add_assembly(&mut self, name: String, assembly: ResolvedAssembly) {
+        match self.assemblies.get_mut(&name) {
+            None => {
+                self.assemblies.insert(name, assembly);
+            }
+            Some(existing)
+                if existing.level < assembly.level && existing.version <= assembly.version =>
+            {
+                *existing = assembly;
+            }
+            Some(_) => {}
+        }
+    }
+}
+
+/// The application (level 0) or a framework (level 1 and up) whose assets are resolved.
+struct Level {
+    dir: PathBuf,
+    deps_file: PathBuf,
+    deps: Option<DepsJson>,
+}
+
+impl DepsResolver {
+    /// Creates a resolver for the managed application at the given path, whose `.deps.json` file is expected next to it.
+    ///
+    /// The runtime identifier defaults to the one of the current process (see [`current_rid`]).
+    #[must_use]
+    pub fn new(app_path: impl Into<PathBuf>) -> Self {
+        Self {
+            app_path: app_path.into(),
+            deps_file: None,
+            frameworks: Vec::new(),
+            probing_paths: Vec::new(),
+            rid: current_rid(),
+            rid_fallbacks: None,
+            use_rid_graph: false,
+        }
+    }
+
+    /// Sets the path of the `.deps.json` file of the application, like the `--depsfile` option of `dotnet exec`.
+    #[must_use]
+    pub fn with_deps_file(mut self, deps_file: impl Into<PathBuf>) -> Self {
+        self.deps_file = Some(deps_file.into());
+        self
+    }
+
+    /// Adds the directory of a resolved framework, e.g. `/usr/share/dotnet/shared/Microsoft.NETCore.App/8.0.0`,
+    /// whose `.deps.json` file is named after the parent directory.
+    ///
+    /// Frameworks have to be added in the order the host resolves them, i.e. starting with the ones referenced by
+    /// the application and ending with `Microsoft.NETCore.App`.
+    /// Self-contained applications do not reference any frameworks.
+    #[must_use]
+    pub fn with_framework(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.frameworks.push(dir.into());
+        self
+    }
+
+    /// Adds a directory in which assets of packages are looked up, using the layout of the NuGet package cache
+    /// (`<probing path>/<package>/<version>/<asset>`).
+    #[must_use]
+    pub fn with_probing_path(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.probing_paths.push(dir.into());
+        self
+    }
+
+    /// Applies the probing paths and the RID resolution setting (`System.Runtime.Loader.UseRidGraph`)
+    /// of the given runtime config.
+    ///
+    /// The frameworks are not resolved from the config and have to be added using [`with_framework`](Self::with_framework).
+    #[must_use]
+    pub fn with_runtime_config(mut self, runtime_config: &RuntimeConfig) -> Self {
+        let options = &runtime_config.runtime_options;
+        self.probing_paths
+            .extend(options.additional_probing_paths.iter().cloned());
+        match options
+            .config_properties
+            .get("System.Runtime.Loader.UseRidGraph")
+        {
+            Some(Value::Bool(value)) => self.use_rid_graph = *value,
+            Some(Value::String(value)) => self.use_rid_graph = value.eq_ignore_ascii_case("true"),
+            _ => {}
+        }
+        self
+    }
+
+    /// Sets the runtime identifier whose assets are selected, e.g. `linux-x64`.
+    #[must_use]
+    pub fn with_rid(mut self, rid: impl Into<String>) -> Self {
+        self.rid = Some(rid.into());
+        self
+    }
+
+    /// Sets whether the RID fallbacks are read from the RID graph in the `.deps.json` file of the application (if
+    /// self-contained) or `Microsoft.NETCore.App`, like the host did before .NET 8, instead of using [`portable_rid_fallbacks`].
+    #[must_use]
+    pub fn with_rid_graph(mut self, use_rid_graph: bool) -> Self {
+        self.use_rid_graph = use_rid_graph;
+        self
+    }
+
+    /// Sets the runtime identifiers whose assets are selected, most specific first, overriding
+    /// [`with_rid`](Self::with_rid) and [`with_rid_graph`](Self::with_rid_graph).
+    #[must_use]
+    pub fn with_rid_fallbacks<I, S>(mut self, rids: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.rid_fallbacks = Some(rids.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Resolves the assets of the application and its frameworks.
+    pub fn resolve(&self) -> Result<ResolvedAssets, ResolveError> {
+        let app_dir = self
+            .app_path
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        let app_name = self
+            .app_path
+            .file_stem()
+            .ok_or(ResolveError::InvalidAppPath)?;
+
+        let app_deps_file = self.deps_file.clone().unwrap_or_else(|| {
+            let mut file_name = app_name.to_os_string();
+            file_name.push(".deps.json");
+            app_dir.join(file_name)
+        });
+        let mut levels = vec![Level::read(app_dir.to_path_buf(), app_deps_file)?];
+        for dir in &self.frameworks {
+            let name = dir
+                .parent()
+                .and_then(Path::file_name)
+                .ok_or_else(|| ResolveError::InvalidFrameworkDir(dir.clone()))?;
+            let mut file_name = name.to_os_string();
+            file_name.push(".deps.json");
+            levels.push(Level::read(dir.clone(), dir.join(file_name))?);
+        }
+        let rid_fallbacks = self.rid_fallbacks(&levels);
+
+        let mut resolution = Resolution::default();
+        resolution.add_assembly(
+            app_name.to_string_lossy().into_owned(),
+            ResolvedAssembly {
+                path: self.app_path.clone(),
+                level: 0,
+                version: (None, None),
+            },
+        );
+        for (index, level) in levels.iter().enumerate() {
+            if let Some(deps) = &level.deps {
+                for library in deps.target_libraries() {
+                    self.resolve_library(index, level, &library, &rid_fallbacks, &mut resolution);
+                }
+            } else {
+                for path in dir_assemblies(&level.dir)? {
+                    let name = path.file_stem().unwrap_or_default().to_string_lossy();
+                    let assembly = ResolvedAssembly {
+                        path: path.clone(),
+                        level: index,
+                        version: (None, None),
+                    };
+                    resolution.add_assembly(name.into_owned(), assembly);
+                }
+            }
+        }
+        for level in &levels {
+            push_dir(&mut resolution.native_dirs, Some(&level.dir));
+            push_dir(&mut resolution.resource_roots, Some(&level.dir));
+        }
+
+        Ok(ResolvedAssets {
+            trusted_platform_assemblies: PathList::try_from(
+                resolution
+                    .assemblies
+                    .into_values()
+                    .map(|assembly| assembly.path)
+                    .collect::<Vec<_>>(),
+            )?,
+            native_dll_search_directories: PathList::try_from(resolution.native_dirs)?,
+            platform_resource_roots: PathList::try_from(resolution.resource_roots)?,
+            missing_assets: resolution.missing_assets,
+        })
+    }
+
+    fn resolve_library(
+        &self,
+        index: usize,
+        level: &Level,
+        library: &ResolvedLibrary<'_>,
+        rid_fallbacks: &[String],
+        resolution: &mut Resolution,
+    ) {
+        let (assets, lookup) = select_assets(library, AssetType::Runtime, rid_fallbacks);
+        for (path, info) in assets {
+            let Some(resolved) = self.probe(level, library, path, info, lookup) else {
+                resolution
+                    .missing_assets
+                    .push(level.missing_asset(library, path));
+                continue;
+            };
+            let assembly = ResolvedAssembly {
+                path: resolved,
+                level: index,
+                version: (
+                    parse_version(info.assembly_version.as_deref()),
+                    parse_version(info.file_version.as_deref()),
+                ),
+            };
+            resolution.add_assembly(file_stem(path).to_owned(), assembly);
+        }
+
+        let (assets, lookup) = select_assets(library, AssetType::Native, rid_fallbacks);
+        for (path, info) in assets {
+            match self.probe(level, library, path, info, lookup) {
+                Some(resolved) => push_dir(&mut resolution.native_dirs, resolved.parent()),
+                None => resolution
+                    .missing_assets
+                    .push(level.missing_asset(library, path)),
+            }
+        }
+
+        for (path, resource) in &library.target.resources {
+            let info = AssetInfo::default();
+            match self.probe(
+                level,
+                library,
+                path,
+                &info,
+                LocalLookup::Culture(&resource.locale),
+            ) {
+                // the root is the directory containing the culture directory.
+                Some(resolved) => push_dir(
+                    &mut resolution.resource_roots,
+                    resolved.parent().and_then(Path::parent),
+                ),
+                None => resolution
+                    .missing_assets
+                    .push(level.missing_asset(library, path)),
+            }
+        }
+    }
+
+    fn rid_fallbacks(&self, levels: &[Level]) -> Vec<String> {
+        if let Some(rid_fallbacks) = &self.rid_fallbacks {
+            return rid_fallbacks.clone();
+        }
+        let Some(rid) = &self.rid else {
+            return Vec::new();
+        };
+        if self.use_rid_graph {
+            // the graph is read from the root framework, or the app itself if it is self-contained.
+            let graph = levels
+                .last()
+                .and_then(|level| level.deps.as_ref())
+                .and_then(|deps| deps.runtimes.get(rid));
+            let mut rid_fallbacks = vec![rid.clone()];
+            rid_fallbacks.extend(graph.into_iter().flatten().cloned());
+            rid_fallbacks
+        } else {
+            portable_rid_fallbacks(rid)
+        }
+    }
+
+    /// Looks up an asset next to the app or in the directory of its framework, and in the probing paths for packages.
+    fn probe(
+        &self,
+        level: &Level,
+        library: &ResolvedLibrary<'_>,
+        path: &str,
+        info: &AssetInfo,
+        lookup: LocalLookup<'_>,
+    ) -> Option<PathBuf> {
+        let local_path = match (&info.local_path, lookup) {
+            (Some(local_path), _) => level.dir.join(local_path),
+            (None, LocalLookup::FileName) => level.dir.join(file_name(path)),
+            (None, LocalLookup::RelativePath) => level.dir.join(path),
+            (None, LocalLookup::Culture(locale)) => level.dir.join(locale).join(file_name(path)),
+        };
+        if local_path.is_file() {
+            return Some(local_path);
+        }
+
+        let library_info = library.library?;
+        if !library_info.is_package() {
+            return None;
+        }
+        let package_path = library_info
+            .path
+            .clone()
+            .unwrap_or_else(|| format!("{}/{}", library.name, library.version).to_lowercase());
+        self.probing_paths
+            .iter()
+            .map(|dir| dir.join(&package_path).join(path))
+            .find(|candidate| candidate.is_file())
+    }
+}
+
+impl Level {
+    fn read(dir: PathBuf, deps_file: PathBuf) -> Result<Self, ResolveError> {
+        let deps = match DepsJson::read(&deps_file) {
+            Ok(deps) => Some(deps),
+            Err(DepsJsonError::Io(io::ErrorKind::NotFound)) => None,
+            Err(error) => {
+                return Err(ResolveError::DepsJson {
+                    path: deps_file,
+                    error,
+                })
+            }
+        };
+        Ok(Self {
+            dir,
+            deps_file,
+            deps,
+        })
+    }
+
+    fn missing_asset(&self, library: &ResolvedLibrary<'_>, path: &str) -> MissingAsset {
+        MissingAsset {
+            library: format!("{}/{}", library.name, library.version),
+            path: path.to_owned(),
+            deps_file: self.deps_file.clone(),
+        }
+    }
+}
+
+/// How an asset is looked up next to the app or in the directory of its framework.
+#[derive(Clone, Copy)]
+enum LocalLookup<'a> {
+    /// By its file name.
+    FileName,
+    /// By its relative path, as RID-specific assets keep their `runtimes/<rid>/...` layout when publishing portable apps.
+    RelativePath,
+    /// By its file name in the directory of its culture.
+    Culture(&'a str),
+}
+
+/// Selects the assets of the given type for the best matching RID, or the RID-agnostic ones if no RID matches.
+fn select_assets<'a>(
+    library: &ResolvedLibrary<'a>,
+    asset_type: AssetType,
+    rid_fallbacks: &[String],
+) -> (Vec<(&'a str, &'a AssetInfo)>, LocalLookup<'static>) {
+    let target = library.target;
+    let rid_specific = || {
+        target
+            .runtime_targets
+            .iter()
+            .filter(move |(_, asset)| asset.asset_type == asset_type)
+    };
+    if let Some(rid) = rid_fallbacks
+        .iter()
+        .find(|rid| rid_specific().any(|(_, asset)| asset.rid == **rid))
+    {
+        let assets = rid_specific()
+            .filter(|(_, asset)| asset.rid == *rid)
+            .map(|(path, asset)| (path.as_str(), &asset.info))
+            .collect();
+        return (assets, LocalLookup::RelativePath);
+    }
+    let assets = match asset_type {
+        AssetType::Runtime => &target.runtime,
+        AssetType::Native => &target.native,
+    };
+    let assets = assets
+        .iter()
+        .map(|(path, info)| (path.as_str(), info))
+        .collect();
+    (assets, LocalLookup::FileName)
+}
+
+/// Returns the assemblies in a directory without a `.deps.json` file, sorted by path.
+fn dir_assemblies(dir: &Path) -> Result<Vec<PathBuf>, ResolveError> {
+    let mut assemblies = Vec::new();
+    for entry in fs::read_dir(dir).map_err(|err| ResolveError::Io(err.kind()))? {
+        let path = entry.map_err(|err| ResolveError::Io(err.kind()))?.path();
+        let is_assembly = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("dll"));
+        if is_assembly && path.is_file() {
+            assemblies.push(path);
+        }
+    }
+    assemblies.sort();
+    Ok(assemblies)
+}
+
+/// Adds a directory with a trailing separator like the host does, unless it was already added.
+fn push_dir(dirs: &mut Vec<PathBuf>, dir: Option<&Path>) {
+    let Some(dir) = dir else {
+        return;
+    };
+    if !dirs.iter().any(|existing| existing == dir) {
+        let mut dir = OsString::from(dir);
+        dir.push(MAIN_SEPARATOR_STR);
+        dirs.push(dir.into());
+    }
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+fn file_stem(path: &str) -> &str {
+    let name = file_name(path);
+    name.rsplit_once('.').map_or(name, |(stem, _)| stem)
+}
+
+fn parse_version(version: Option<&str>) -> Option<Vec<u64>> {
+    version?.split('.').map(|part| part.parse().ok()).collect()
+}
+
+/// Returns the runtime identifier of the current process, e.g. `linux-x64`, or `None` if the platform is unknown.
+#[must_use]
+pub fn current_rid() -> Option<String> {
+    let os = if cfg!(windows) {
+        "win"
+    } else if cfg!(target_os = "macos") {
+        "osx"
+    } else if cfg!(target_os = "android") {
+        "linux-bionic"
+    } else if cfg!(all(target_os = "linux", target_env = "musl")) {
+        "linux-musl"
+    } else if cfg!(target_os = "linux") {
+        "linux"
+    } else if cfg!(target_os = "freebsd") {
+        "freebsd"
+    } else if cfg!(target_os = "illumos") {
+        "illumos"
+    } else if cfg!(target_os = "solaris") {
+        "solaris"
+    } else {
+        return None;
+    };
+    install::ARCH.map(|arch| format!("{os}-{arch}"))
+}
+
+/// Returns the fallbacks of the given runtime identifier in the portable RID graph, most specific first,
+/// which is what the host uses since .NET 8, e.g. `linux-x64`, `linux`, `unix-x64`, `unix`, `any` and `base`
+/// for `linux-x64`.
+#[must_use]
+pub fn portable_rid_fallbacks(rid: &str) -> Vec<String> {
+    let mut fallbacks = vec![rid.to_owned()];
+    let (os, arch) = match rid.rsplit_once('-') {
+        Some((os, arch)) => (os, Some(arch)),
+        None => (rid, None),
+    };
+    let mut push = |rid: String| {
+        if !fallbacks.contains(&rid) {
+            fallbacks.push(rid);
+        }
+    };
+    push(os.to_owned());
+    // e.g. linux-musl-x64 falls back to linux-x64.
+    if let Some((base_os, _)) = os.split_once('-') {
+        if let Some(arch) = arch {
+            push(format!("{base_os}-{arch}"));
+        }
+        push(base_os.to_owned());
+    }
+    if !matches!(os, "win" | "any" | "base") && !os.starts_with("win") {
+        if let Some(arch) = arch {
+            push(format!("unix-{arch}"));
+        }
+        push("unix".to_owned());
+    }
+    push("any".to_owned());
+    push("base".to_owned());
+    fallbacks
+}
+
+/// Error returned when resolving the assets of an application fails.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ResolveError {
+    /// The path of the application has no file name.
+    InvalidAppPath,
+    /// The framework directory is not of the form `<name>/<version>`.
+    InvalidFrameworkDir(PathBuf),
+    /// A `.deps.json` file could not be read.
+    DepsJson {
+        /// The path of the file.
+        path: PathBuf,
+        /// The error reading the file.
+        error: DepsJsonError,
+    },
+    /// The directory of an application or framework without a `.deps.json` file could not be listed.
+    Io(io::ErrorKind),
+    /// A resolved path cannot be added to a [`PathList`].
+    InvalidPath(PathListError),
+}
+
+impl From<PathListError> for ResolveError {
+    fn from(err: PathListError) -> Self {
+        Self::InvalidPath(err)
+    }
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidAppPath => f.write_str("app path has no file name"),
+            Self::InvalidFrameworkDir(dir) => {
+                write!(f, "'{}' is not a framework directory", dir.display())
+            }
+            Self::DepsJson { path, error } => write!(f, "'{}': {error}", path.display()),
+            Self::Io(kind) => write!(f, "failed to list assemblies: {kind}"),
+            Self::InvalidPath(err) => write!(f, "invalid resolved path: {err}"),
+        }
+    }
+}
+
+impl error::Error for ResolveError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::DepsJson { error, .. } => Some(error),
+            Self::InvalidPath(err) => Some(err),
+            _ => None,
+        }
+    }
+}
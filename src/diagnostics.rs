@@ -0,0 +1,521 @@
+use std::path::PathBuf;
+
+/// A diagnostic extracted from the error output of hostfxr and hostpolicy.
+///
+/// The hosting components only report errors as free text through the error writer (see [`hostfxr_set_error_writer`]).
+/// [`HostingDiagnostic::parse`] classifies those messages into the well-known failure cases.
+///
+/// [`hostfxr_set_error_writer`]: crate::wrapper::Hostfxr::hostfxr_set_error_writer
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum HostingDiagnostic {
+    /// A framework required by the application (or none of its compatible versions) is not installed.
+    MissingFramework {
+        /// The name of the missing framework (e.g. `Microsoft.NETCore.App`).
+        name: String,
+        /// The requested version of the framework.
+        version: String,
+        /// The architecture the framework was searched for, if reported.
+        architecture: Option<String>,
+        /// The path of the application, if reported.
+        app_path: Option<PathBuf>,
+        /// The .NET installation that was searched, if reported.
+        dotnet_root: Option<PathBuf>,
+        /// Other versions of the framework that were found.
+        found_versions: Vec<FrameworkVersionLocation>,
+    },
+    /// The hostpolicy library could not be found.
+    MissingHostpolicy {
+        /// The file name of the library (e.g. `libhostpolicy.so`).
+        library: String,
+        /// The directory the library was expected in.
+        directory: Option<PathBuf>,
+        /// The `.runtimeconfig.json` that did not specify a framework, causing the app to be run as self-contained.
+        runtime_config: Option<PathBuf>,
+    },
+    /// A `.runtimeconfig.json` (and its `.runtimeconfig.dev.json`) could not be processed.
+    InvalidRuntimeConfig {
+        /// The path of the `.runtimeconfig.json`.
+        path: PathBuf,
+        /// The path of the `.runtimeconfig.dev.json`, if reported.
+        dev_path: Option<PathBuf>,
+    },
+    /// A json file (e.g. a `.runtimeconfig.json` or a `.deps.json`) could not be parsed.
+    InvalidJson {
+        /// The path of the json file.
+        path: PathBuf,
+        /// The line of the error.
+        line: Option<u32>,
+        /// The column of the error.
+        column: Option<u32>,
+        /// The message of the json parser.
+        message: String,
+    },
+    /// A file could not be opened.
+    UnreadableFile {
+        /// The path of the file.
+        path: PathBuf,
+        /// The reason as reported by the os.
+        reason: String,
+    },
+    /// The runtime config is incompatible with the runtime that was already loaded into the process.
+    IncompatibleConfig {
+        /// The name of the framework which is incompatible.
+        framework: String,
+        /// The version requested by the runtime config, if reported.
+        requested_version: Option<String>,
+        /// The version of the framework in the loaded runtime, or [`None`] if the framework is not part of it.
+        loaded_version: Option<String>,
+    },
+    /// A component was initialized using a self-contained runtime config, which is not supported.
+    SelfContainedComponent,
+    /// An assembly listed in a `.deps.json` could not be found.
+    MissingDependency {
+        /// The file name of the dependency manifest (e.g. `app.deps.json`).
+        manifest: String,
+        /// The package containing the assembly.
+        package: Option<String>,
+        /// The version of the package.
+        version: Option<String>,
+        /// The relative path of the assembly inside the package.
+        path: Option<String>,
+    },
+    /// The application passed to hostfxr does not exist.
+    AppNotFound {
+        /// The path of the application.
+        path: PathBuf,
+    },
+    /// The CoreCLR library could not be located.
+    CoreClrNotFound,
+    /// The runtime failed to initialize.
+    CoreClrInitFailure {
+        /// The `HRESULT` returned by CoreCLR.
+        hresult: u32,
+    },
+    /// No compatible .NET SDK is installed.
+    MissingSdk {
+        /// The SDK version requested by `global.json`, if reported.
+        requested_version: Option<String>,
+        /// The path of the `global.json`, if reported.
+        global_json: Option<PathBuf>,
+    },
+    /// A message which could not be classified.
+    Other {
+        /// The message as reported by the hosting components.
+        message: String,
+    },
+}
+
+/// A framework version found during framework resolution.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FrameworkVersionLocation {
+    /// The version of the framework.
+    pub version: String,
+    /// The directory containing the framework versions.
+    pub path: PathBuf,
+}
+
+impl HostingDiagnostic {
+    /// Parses the messages passed to the error writer into diagnostics.
+    ///
+    /// Messages may contain multiple lines and multi-line errors may be split across multiple messages.
+    /// Lines that only add explanations or links to a recognized error are skipped, other lines are reported as [`HostingDiagnostic::Other`].
+    /// As the output for missing frameworks and SDKs ends with a variable amount of advice, any lines following them
+    /// are attributed to them until another recognized error starts.
+    pub fn parse<I, S>(messages: I) -> Vec<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut parser = Parser::default();
+        for message in messages {
+            for line in message.as_ref().lines() {
+                parser.line(line);
+            }
+        }
+        parser.finish()
+    }
+}
+
+#[derive(Default)]
+struct Parser {
+    diagnostics: Vec<HostingDiagnostic>,
+    current: Option<Pending>,
+}
+
+enum Pending {
+    MissingFramework {
+        name: Option<String>,
+        version: Option<String>,
+        architecture: Option<String>,
+        app_path: Option<PathBuf>,
+        dotnet_root: Option<PathBuf>,
+        found_versions: Vec<FrameworkVersionLocation>,
+        in_found_versions: bool,
+        header: String,
+    },
+    Complete(HostingDiagnostic),
+}
+
+impl Parser {
+    fn line(&mut self, line: &str) {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            return;
+        }
+
+        if let Some(pending) = self.start(trimmed) {
+            self.flush();
+            self.current = Some(pending);
+        } else if !self.continue_current(trimmed) {
+            self.flush();
+            self.diagnostics.push(HostingDiagnostic::Other {
+                message: trimmed.to_owned(),
+            });
+        }
+    }
+
+    fn finish(mut self) -> Vec<HostingDiagnostic> {
+        self.flush();
+        self.diagnostics
+    }
+
+    fn flush(&mut self) {
+        let Some(pending) = self.current.take() else {
+            return;
+        };
+        let diagnostic = match pending {
+            Pending::MissingFramework {
+                name: Some(name),
+                version: Some(version),
+                architecture,
+                app_path,
+                dotnet_root,
+                found_versions,
+                ..
+            } => HostingDiagnostic::MissingFramework {
+                name,
+                version,
+                architecture,
+                app_path,
+                dotnet_root,
+                found_versions,
+            },
+            Pending::MissingFramework { header, .. } => {
+                HostingDiagnostic::Other { message: header }
+            }
+            Pending::Complete(diagnostic) => diagnostic,
+        };
+        self.diagnostics.push(diagnostic);
+    }
+
+    /// Checks whether the line starts a new diagnostic.
+    fn start(&self, line: &str) -> Option<Pending> {
+        if line == "You must install or update .NET to run this application."
+            || line == "It was not possible to find any compatible framework version"
+        {
+            return Some(Pending::MissingFramework {
+                name: None,
+                version: None,
+                architecture: None,
+                app_path: None,
+                dotnet_root: None,
+                found_versions: Vec::new(),
+                in_found_versions: false,
+                header: line.to_owned(),
+            });
+        }
+
+        if let Some((name, version, architecture)) = parse_framework_not_found(line) {
+            // part of the "It was not possible to find any compatible framework version" error.
+            if let Some(Pending::MissingFramework { name: None, .. }) = self.current {
+                return None;
+            }
+            return Some(Pending::MissingFramework {
+                name: Some(name),
+                version: Some(version),
+                architecture,
+                app_path: None,
+                dotnet_root: None,
+                found_versions: Vec::new(),
+                in_found_versions: false,
+                header: line.to_owned(),
+            });
+        }
+
+        let line = line
+            .strip_prefix("A fatal error was encountered. ")
+            .unwrap_or(line);
+        parse_complete(line).map(Pending::Complete)
+    }
+
+    /// Adds the information in the line to the current diagnostic, returns `false` if the line does not belong to it.
+    fn continue_current(&mut self, line: &str) -> bool {
+        match &mut self.current {
+            Some(Pending::MissingFramework {
+                name,
+                version,
+                architecture,
+                app_path,
+                dotnet_root,
+                found_versions,
+                in_found_versions,
+                ..
+            }) => {
+                if let Some((fx_name, fx_version, fx_architecture)) =
+                    parse_framework_not_found(line).or_else(|| parse_framework_line(line))
+                {
+                    *name = Some(fx_name);
+                    *version = Some(fx_version);
+                    if fx_architecture.is_some() {
+                        *architecture = fx_architecture;
+                    }
+                } else if let Some(path) = line.strip_prefix("App: ") {
+                    *app_path = Some(PathBuf::from(path));
+                } else if let Some(arch) = line.strip_prefix("Architecture: ") {
+                    *architecture = Some(arch.to_owned());
+                } else if let Some(path) = line.strip_prefix(".NET location: ") {
+                    *dotnet_root = Some(PathBuf::from(path));
+                } else if line.trim_start_matches("- ") == "The following frameworks were found:" {
+                    *in_found_versions = true;
+                } else if let Some(location) = parse_version_location(line) {
+                    if *in_found_versions {
+                        found_versions.push(location);
+                    }
+                } else {
+                    // headers of other sections (e.g. frameworks for other architectures), links and advice.
+                    *in_found_versions = false;
+                }
+                true
+            }
+            Some(Pending::Complete(HostingDiagnostic::MissingHostpolicy {
+                runtime_config,
+                ..
+            })) => {
+                if let Some(rest) =
+                    line.strip_prefix("- The application was run as a self-contained app because '")
+                {
+                    if let Some((path, _)) = rest.split_once("' did not specify a framework") {
+                        *runtime_config = Some(PathBuf::from(path));
+                    }
+                    true
+                } else {
+                    // "Failed to run as a self-contained app." and the related advice.
+                    line.starts_with("Failed to run as a self-contained app")
+                        || line.starts_with("- ")
+                }
+            }
+            Some(Pending::Complete(HostingDiagnostic::MissingDependency {
+                package,
+                version,
+                path,
+                ..
+            })) => {
+                if let Some(rest) = line.strip_prefix("package: ") {
+                    let mut parts = rest.split(", ");
+                    *package = parts.next().and_then(unquote).map(str::to_owned);
+                    *version = parts
+                        .next()
+                        .and_then(|part| part.strip_prefix("version: "))
+                        .and_then(unquote)
+                        .map(str::to_owned);
+                    true
+                } else if let Some(rest) = line.strip_prefix("path: ") {
+                    *path = unquote(rest).map(str::to_owned);
+                    true
+                } else {
+                    false
+                }
+            }
+            Some(Pending::Complete(HostingDiagnostic::MissingSdk {
+                requested_version,
+                global_json,
+            })) => {
+                if let Some(version) = line.strip_prefix("Requested SDK version: ") {
+                    *requested_version = Some(version.to_owned());
+                } else if let Some(path) = line.strip_prefix("global.json file: ") {
+                    *global_json = Some(PathBuf::from(path));
+                }
+                // installed SDKs, links and advice.
+                true
+            }
+            Some(Pending::Complete(HostingDiagnostic::CoreClrNotFound)) => {
+                line.starts_with("For more details")
+            }
+            None | Some(Pending::Complete(_)) => false,
+        }
+    }
+}
+
+/// Parses diagnostics that consist of a single line.
+fn parse_complete(line: &str) -> Option<HostingDiagnostic> {
+    if let Some(rest) = line.strip_prefix("The library '") {
+        let (library, rest) = rest.split_once('\'')?;
+        let rest = rest.strip_prefix(" required to execute the application was not found")?;
+        let directory = rest
+            .strip_prefix(" in '")
+            .and_then(|rest| rest.rsplit_once('\''))
+            .map(|(dir, _)| PathBuf::from(dir));
+        return Some(HostingDiagnostic::MissingHostpolicy {
+            library: library.to_owned(),
+            directory,
+            runtime_config: None,
+        });
+    }
+
+    if let Some(rest) = line.strip_prefix("Invalid runtimeconfig.json ") {
+        let mut paths = bracketed(rest);
+        return Some(HostingDiagnostic::InvalidRuntimeConfig {
+            path: PathBuf::from(paths.next()?),
+            dev_path: paths.next().map(PathBuf::from),
+        });
+    }
+
+    if let Some(rest) = line.strip_prefix("A JSON parsing exception occurred in [") {
+        let (path, rest) = rest.split_once(']')?;
+        let (location, message) = rest.split_once(": ").unwrap_or((rest, ""));
+        let position = location
+            .split_once("(line ")
+            .and_then(|(_, position)| position.strip_suffix(')'))
+            .and_then(|position| position.split_once(", column "));
+        return Some(HostingDiagnostic::InvalidJson {
+            path: PathBuf::from(path),
+            line: position.and_then(|(line, _)| line.parse().ok()),
+            column: position.and_then(|(_, column)| column.parse().ok()),
+            message: message.to_owned(),
+        });
+    }
+
+    if let Some(rest) = line.strip_prefix("Cannot use file stream for [") {
+        let (path, reason) = rest.split_once("]: ")?;
+        return Some(HostingDiagnostic::UnreadableFile {
+            path: PathBuf::from(path),
+            reason: reason.to_owned(),
+        });
+    }
+
+    if let Some(rest) = line.strip_prefix("The specified framework '") {
+        return parse_incompatible_config(rest);
+    }
+
+    if line.starts_with("Initialization for self-contained components is not supported") {
+        return Some(HostingDiagnostic::SelfContainedComponent);
+    }
+
+    if let Some(rest) =
+        line.strip_prefix("An assembly specified in the application dependencies manifest (")
+    {
+        let (manifest, _) = rest.split_once(") was not found")?;
+        return Some(HostingDiagnostic::MissingDependency {
+            manifest: manifest.to_owned(),
+            package: None,
+            version: None,
+            path: None,
+        });
+    }
+
+    if let Some(rest) = line.strip_prefix("The application to execute does not exist: ") {
+        let path = rest.trim_end_matches('.');
+        return Some(HostingDiagnostic::AppNotFound {
+            path: PathBuf::from(unquote(path).unwrap_or(path)),
+        });
+    }
+
+    if line.starts_with("Could not resolve CoreCLR path") {
+        return Some(HostingDiagnostic::CoreClrNotFound);
+    }
+
+    if let Some(rest) = line.strip_prefix("Failed to create CoreCLR, HRESULT: ") {
+        let hresult = rest.trim().trim_start_matches("0x");
+        return Some(HostingDiagnostic::CoreClrInitFailure {
+            hresult: u32::from_str_radix(hresult, 16).ok()?,
+        });
+    }
+
+    if line.starts_with("A compatible .NET SDK was not found") {
+        return Some(HostingDiagnostic::MissingSdk {
+            requested_version: None,
+            global_json: None,
+        });
+    }
+
+    None
+}
+
+/// Parses the remainder of `The specified framework '{name}' is not present in the previously loaded runtime.`
+/// and `The specified framework '{name}', version '{version}', ... is incompatible with the previously loaded version '{version}'.`
+fn parse_incompatible_config(rest: &str) -> Option<HostingDiagnostic> {
+    let (framework, rest) = rest.split_once('\'')?;
+    if rest.starts_with(" is not present in the previously loaded runtime") {
+        return Some(HostingDiagnostic::IncompatibleConfig {
+            framework: framework.to_owned(),
+            requested_version: None,
+            loaded_version: None,
+        });
+    }
+    let requested_version = rest
+        .strip_prefix(", version '")
+        .and_then(|rest| rest.split_once('\''))
+        .map(|(version, _)| version.to_owned());
+    let loaded_version = rest
+        .split_once("incompatible with the previously loaded version '")
+        .and_then(|(_, rest)| rest.split_once('\''))
+        .map(|(version, _)| version.to_owned());
+    loaded_version.as_ref()?;
+    Some(HostingDiagnostic::IncompatibleConfig {
+        framework: framework.to_owned(),
+        requested_version,
+        loaded_version,
+    })
+}
+
+/// Parses `The framework '{name}', version '{version}' ({arch}) was not found.`
+fn parse_framework_not_found(line: &str) -> Option<(String, String, Option<String>)> {
+    let rest = line.strip_prefix("The framework ")?;
+    let (reference, _) = rest.split_once(" was not found")?;
+    parse_framework_reference(reference)
+}
+
+/// Parses `Framework: '{name}', version '{version}' ({arch})`.
+fn parse_framework_line(line: &str) -> Option<(String, String, Option<String>)> {
+    parse_framework_reference(line.strip_prefix("Framework: ")?)
+}
+
+/// Parses `'{name}', version '{version}' ({arch})` where the architecture is optional.
+fn parse_framework_reference(reference: &str) -> Option<(String, String, Option<String>)> {
+    let (name, rest) = reference.strip_prefix('\'')?.split_once('\'')?;
+    let (version, rest) = rest.strip_prefix(", version '")?.split_once('\'')?;
+    let architecture = rest
+        .trim()
+        .strip_prefix('(')
+        .and_then(|rest| rest.strip_suffix(')'))
+        .map(str::to_owned);
+    Some((name.to_owned(), version.to_owned(), architecture))
+}
+
+/// Parses `{version} at [{path}]`.
+fn parse_version_location(line: &str) -> Option<FrameworkVersionLocation> {
+    let (version, path) = line.split_once(" at [")?;
+    let path = path.strip_suffix(']')?;
+    if version.contains(char::is_whitespace) {
+        return None;
+    }
+    Some(FrameworkVersionLocation {
+        version: version.to_owned(),
+        path: PathBuf::from(path),
+    })
+}
+
+fn unquote(s: &str) -> Option<&str> {
+    s.trim().strip_prefix('\'')?.strip_suffix('\'')
+}
+
+/// Iterates over the values enclosed in square brackets in `s`.
+fn bracketed(mut s: &str) -> impl Iterator<Item = &str> {
+    std::iter::from_fn(move || {
+        let (_, rest) = s.split_once('[')?;
+        let (value, rest) = rest.split_once(']')?;
+        s = rest;
+        Some(value)
+    })
+}
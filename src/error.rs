@@ -0,0 +1,270 @@
+use std::{error, fmt, io};
+
+use coreclr_hosting_shared::StatusCode;
+
+use crate::diagnostics::HostingDiagnostic;
+
+/// A failure status code returned by the hosting components together with the errors reported while producing it.
+///
+/// See [`StatusCode`] for the known codes.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct HostingError {
+    code: u32,
+    messages: Vec<String>,
+}
+
+/// A success status code returned by the hosting components.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum HostingSuccess {
+    /// See [`StatusCode::Success`].
+    Success,
+    /// See [`StatusCode::Success_HostAlreadyInitialized`].
+    HostAlreadyInitialized,
+    /// See [`StatusCode::Success_DifferentRuntimeProperties`].
+    DifferentRuntimeProperties,
+    /// A success code not known to this crate.
+    Unknown(u32),
+}
+
+impl HostingError {
+    /// Splits a status code returned by the hosting components into success and failure.
+    #[allow(clippy::cast_sign_loss)]
+    pub fn check(code: i32) -> Result<HostingSuccess, Self> {
+        if code < 0 {
+            return Err(Self::new(code as u32));
+        }
+        Ok(match code as u32 {
+            c if c == StatusCode::Success as u32 => HostingSuccess::Success,
+            c if c == StatusCode::Success_HostAlreadyInitialized as u32 => {
+                HostingSuccess::HostAlreadyInitialized
+            }
+            c if c == StatusCode::Success_DifferentRuntimeProperties as u32 => {
+                HostingSuccess::DifferentRuntimeProperties
+            }
+            c => HostingSuccess::Unknown(c),
+        })
+    }
+
+    /// Splits the result of running an application (like [`hostfxr_run_app`](crate::wrapper::Hostfxr::hostfxr_run_app))
+    /// into the exit code of the application and a hosting failure.
+    ///
+    /// As both share the same `i32`, the result is only treated as a failure if it is in the range of hosting status codes
+    /// (`0x80008000` to `0x800080FF`) and the hosting components reported an error while running, which they always do when failing.
+    /// `messages` are the errors captured during the call, see [`capture_errors`](crate::error_writer::capture_errors).
+    #[allow(clippy::cast_sign_loss)]
+    pub fn check_exit_code(code: i32, messages: Vec<String>) -> Result<ExitCode, Self> {
+        if Self::is_hosting_status_code(code as u32) && !messages.is_empty() {
+            return Err(Self::new(code as u32).with_messages(messages));
+        }
+        Ok(ExitCode(code))
+    }
+
+    /// Returns whether the given code is in the range reserved for hosting status codes (`0x80008000` to `0x800080FF`).
+    #[must_use]
+    pub const fn is_hosting_status_code(code: u32) -> bool {
+        code & 0xFFFF_FF00 == 0x8000_8000
+    }
+
+    /// Creates a new error from the given status code.
+    #[must_use]
+    pub const fn new(code: u32) -> Self {
+        Self {
+            code,
+            messages: Vec::new(),
+        }
+    }
+
+    /// Attaches the messages reported through the error writer while the failing call was made.
+    #[must_use]
+    pub fn with_messages(mut self, messages: Vec<String>) -> Self {
+        self.messages = messages;
+        self
+    }
+
+    /// Returns the raw status code.
+    #[must_use]
+    pub const fn code(&self) -> u32 {
+        self.code
+    }
+
+    /// Returns whether this error has the given status code.
+    #[must_use]
+    pub const fn is(&self, code: StatusCode) -> bool {
+        self.code == code as u32
+    }
+
+    /// Returns the name of the status code, if it is known.
+    #[must_use]
+    #[rustfmt::skip]
+    pub const fn name(&self) -> Option<&'static str> {
+        macro_rules! names {
+            ($($code:ident),* $(,)?) => {
+                match self.code {
+                    $(c if c == StatusCode::$code as u32 => Some(stringify!($code)),)*
+                    _ => None,
+                }
+            };
+        }
+        names!(
+            InvalidArgFailure, CoreHostLibLoadFailure, CoreHostLibMissingFailure, CoreHostEntryPointFailure,
+            CoreHostCurHostFindFailure, CoreClrResolveFailure, CoreClrBindFailure, CoreClrInitFailure,
+            CoreClrExeFailure, ResolverInitFailure, ResolverResolveFailure, LibHostCurExeFindFailure,
+            LibHostInitFailure, LibHostExecModeFailure, LibHostSdkFindFailure, LibHostInvalidArgs,
+            InvalidConfigFile, AppArgNotRunnable, AppHostExeNotBoundFailure, FrameworkMissingFailure,
+            HostApiFailed, HostApiBufferTooSmall, LibHostUnknownCommand, LibHostAppRootFindFailure,
+            SdkResolverResolveFailure, FrameworkCompatFailure, FrameworkCompatRetry, AppHostExeNotBundle,
+            BundleExtractionFailure, BundleExtractionIOError, LibHostDuplicateProperty,
+            HostApiUnsupportedVersion, HostInvalidState, HostPropertyNotFound, CoreHostIncompatibleConfig,
+            HostApiUnsupportedScenario, HostFeatureDisabled,
+        )
+    }
+
+    /// Returns the messages reported through the error writer while the failing call was made.
+    #[must_use]
+    pub fn messages(&self) -> &[String] {
+        &self.messages
+    }
+
+    /// Classifies the reported messages, see [`HostingDiagnostic::parse`].
+    #[must_use]
+    pub fn diagnostics(&self) -> Vec<HostingDiagnostic> {
+        HostingDiagnostic::parse(&self.messages)
+    }
+}
+
+impl fmt::Display for HostingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.name() {
+            Some(name) => write!(f, "hosting call failed with {name} ({:#010x})", self.code)?,
+            None => write!(f, "hosting call failed with {:#010x}", self.code)?,
+        }
+        for message in &self.messages {
+            write!(f, "\n{message}")?;
+        }
+        Ok(())
+    }
+}
+
+impl error::Error for HostingError {}
+
+/// The exit code of a managed application.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ExitCode(i32);
+
+impl ExitCode {
+    /// The exit code of an application that completed successfully.
+    pub const SUCCESS: Self = Self(0);
+
+    /// Creates an exit code from its raw value.
+    #[must_use]
+    pub const fn new(code: i32) -> Self {
+        Self(code)
+    }
+
+    /// Returns the raw exit code.
+    #[must_use]
+    pub const fn code(self) -> i32 {
+        self.0
+    }
+
+    /// Returns whether the application completed successfully, i.e. the exit code is `0`.
+    #[must_use]
+    pub const fn is_success(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl fmt::Display for ExitCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl From<ExitCode> for i32 {
+    fn from(exit_code: ExitCode) -> Self {
+        exit_code.0
+    }
+}
+
+impl From<ExitCode> for std::process::ExitCode {
+    /// Converts the exit code for returning it from `main`, truncating it to its lowest byte
+    /// (as done by the operating system on Unix) while keeping failures non-zero.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn from(exit_code: ExitCode) -> Self {
+        match exit_code.0 as u8 {
+            0 if !exit_code.is_success() => Self::FAILURE,
+            code => Self::from(code),
+        }
+    }
+}
+
+/// Error returned when a string passed to the hosting components contains an interior nul character.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ContainsNulError {
+    pub(crate) position: usize,
+}
+
+impl ContainsNulError {
+    /// Returns the position of the nul character in [`char_t`](coreclr_hosting_shared::char_t) units.
+    #[must_use]
+    pub const fn nul_position(&self) -> usize {
+        self.position
+    }
+}
+
+impl fmt::Display for ContainsNulError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "string contains an interior nul character at position {}",
+            self.position
+        )
+    }
+}
+
+impl error::Error for ContainsNulError {}
+
+/// Error returned by the safe wrappers in this crate.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// A hosting call failed.
+    Hosting(HostingError),
+    /// An argument could not be converted into a platform string.
+    ContainsNul(ContainsNulError),
+    /// A file needed for the call could not be written.
+    Io(io::ErrorKind),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Hosting(err) => err.fmt(f),
+            Self::ContainsNul(err) => err.fmt(f),
+            Self::Io(kind) => write!(f, "failed to write file: {kind}"),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Hosting(err) => Some(err),
+            Self::ContainsNul(err) => Some(err),
+            Self::Io(_) => None,
+        }
+    }
+}
+
+impl From<HostingError> for Error {
+    fn from(err: HostingError) -> Self {
+        Self::Hosting(err)
+    }
+}
+
+impl From<ContainsNulError> for Error {
+    fn from(err: ContainsNulError) -> Self {
+        Self::ContainsNul(err)
+    }
+}
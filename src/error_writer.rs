@@ -0,0 +1,656 @@
+use std::{
+    cell::RefCell,
+    marker::PhantomData,
+    mem,
+    panic::{self, AssertUnwindSafe},
+    ptr,
+    sync::{Arc, PoisonError, RwLock},
+};
+
+use coreclr_hosting_shared::char_t;
+
+use crate::{hostfxr_error_writer_fn, pdcstr::PdCStr, wrapper::Hostfxr};
+
+type BoxedErrorWriter = Box<dyn Fn(&str)>;
+type SharedErrorWriter = Arc<dyn Fn(&str) + Send + Sync>;
+
+thread_local! {
+    static THREAD_ERROR_WRITER: RefCell<Option<BoxedErrorWriter>> = const { RefCell::new(None) };
+    static CAPTURED_ERRORS: RefCell<Vec<Vec<String>>> = const { RefCell::new(Vec::new()) };
+}
+
+static GLOBAL_ERROR_WRITER: RwLock<Option<SharedErrorWriter>> = RwLock::new(None);
+
+fn global_error_writer() -> Option<SharedErrorWriter> {
+    GLOBAL_ERROR_WRITER
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+/// The [`hostfxr_error_writer_fn`] registered with hostfxr, which forwards each message to [`dispatch`].
+extern "C" fn error_writer_trampoline(message: *const char_t) {
+    if message.is_null() {
+        return;
+    }
+    let message = unsafe { PdCStr::from_ptr(message) }.to_string_lossy();
+
+    // unwinding across the ffi boundary would abort the process.
+    let _ = panic::catch_unwind(AssertUnwindSafe(|| dispatch(&message)));
+}
+
+/// Routes an error message: it is collected by the innermost [`capture_errors`] call (if any) and passed to the writer
+/// of the current thread or, if there is none, to the global writer.
+/// Messages that are neither captured nor handled by a writer are written to stderr.
+fn dispatch(message: &str) {
+    let captured = CAPTURED_ERRORS.with(|captures| {
+        let mut captures = captures.borrow_mut();
+        let capture = captures.last_mut()?;
+        capture.push(message.to_owned());
+        Some(())
+    });
+
+    let handled = THREAD_ERROR_WRITER.with(|writer| match writer.try_borrow().as_deref() {
+        Ok(Some(writer)) => {
+            writer(message);
+            true
+        }
+        _ => false,
+    });
+    if !handled {
+        match global_error_writer() {
+            Some(writer) => writer(message),
+            None if captured.is_none() => eprintln!("{message}"),
+            None => {}
+        }
+    }
+}
+
+/// Abstraction over [`Hostfxr::hostfxr_set_error_writer`], so that the registration logic does not depend on a loaded library.
+trait SetErrorWriter {
+    unsafe fn set_error_writer(
+        &self,
+        error_writer: Option<hostfxr_error_writer_fn>,
+    ) -> Option<hostfxr_error_writer_fn>;
+}
+
+impl SetErrorWriter for Hostfxr {
+    unsafe fn set_error_writer(
+        &self,
+        error_writer: Option<hostfxr_error_writer_fn>,
+    ) -> Option<hostfxr_error_writer_fn> {
+        unsafe { self.hostfxr_set_error_writer(error_writer) }
+    }
+}
+
+/// Registers `writer` as the error writer of hostfxr (and hostpolicy) for the current thread.
+///
+/// The previously registered writer is restored once the returned guard is dropped.
+/// As the registration is thread-local, errors reported on other threads are not passed to `writer`.
+pub fn set_thread_error_writer(
+    hostfxr: &Hostfxr,
+    writer: impl Fn(&str) + 'static,
+) -> ErrorWriterGuard<'_> {
+    register_thread_error_writer(hostfxr, Box::new(writer))
+}
+
+fn register_thread_error_writer(
+    hostfxr: &dyn SetErrorWriter,
+    writer: BoxedErrorWriter,
+) -> ErrorWriterGuard<'_> {
+    let previous_writer = THREAD_ERROR_WRITER.with(|current| current.borrow_mut().replace(writer));
+    let previous_fn = unsafe { hostfxr.set_error_writer(Some(error_writer_trampoline)) };
+    ErrorWriterGuard {
+        hostfxr,
+        previous_fn,
+        previous_writer,
+        _not_send: PhantomData,
+    }
+}
+
+/// Guard returned by [`set_thread_error_writer`] which restores the previously registered error writer when dropped.
+///
+/// Guards should be dropped in the reverse order of their creation.
+#[must_use = "the error writer is unregistered when the guard is dropped"]
+pub struct ErrorWriterGuard<'lib> {
+    hostfxr: &'lib dyn SetErrorWriter,
+    previous_fn: Option<hostfxr_error_writer_fn>,
+    previous_writer: Option<BoxedErrorWriter>,
+    // the registration is bound to the thread it was made on.
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for ErrorWriterGuard<'_> {
+    fn drop(&mut self) {
+        unsafe { self.hostfxr.set_error_writer(self.previous_fn) };
+        let previous_writer = self.previous_writer.take();
+        THREAD_ERROR_WRITER.with(|current| *current.borrow_mut() = previous_writer);
+    }
+}
+
+/// Sets the process-wide error writer.
+///
+/// As error writer registrations in hostfxr are thread-local, the global writer only receives errors from calls
+/// made inside [`with_global_error_writer`] or [`capture_errors`] (which the safe wrappers in this crate use),
+/// regardless of the thread they are made on.
+/// Writers registered for a specific thread using [`set_thread_error_writer`] take precedence over the global one.
+pub fn set_global_error_writer(writer: impl Fn(&str) + Send + Sync + 'static) {
+    *GLOBAL_ERROR_WRITER
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(writer));
+}
+
+/// Removes the process-wide error writer set by [`set_global_error_writer`].
+pub fn clear_global_error_writer() {
+    *GLOBAL_ERROR_WRITER
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = None;
+}
+
+/// Runs `f` with the global error writer registered on the current thread and unregisters it afterwards.
+///
+/// If no global writer is set or another error writer has been registered directly with hostfxr on this thread,
+/// the registration is left untouched.
+pub fn with_global_error_writer<R>(hostfxr: &Hostfxr, f: impl FnOnce() -> R) -> R {
+    run_with_global_error_writer(hostfxr, f)
+}
+
+fn run_with_global_error_writer<R>(hostfxr: &dyn SetErrorWriter, f: impl FnOnce() -> R) -> R {
+    if GLOBAL_ERROR_WRITER
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .is_none()
+    {
+        return f();
+    }
+
+    let previous_fn = unsafe { hostfxr.set_error_writer(Some(error_writer_trampoline)) };
+    if let Some(previous) = previous_fn {
+        if !ptr::fn_addr_eq(previous, error_writer_trampoline as hostfxr_error_writer_fn) {
+            unsafe { hostfxr.set_error_writer(previous_fn) };
+            return f();
+        }
+    }
+
+    let _restore = RestoreErrorWriterFn {
+        hostfxr,
+        previous_fn,
+    };
+    f()
+}
+
+/// Runs `f` and collects all errors reported by hostfxr and hostpolicy on the current thread in the meantime.
+///
+/// The messages are still passed on to the writer of the current thread or the global writer, if any, but are no longer written to stderr.
+/// A writer registered directly with hostfxr (without using this module) does not receive messages during the call.
+/// The collected messages can be classified using [`HostingDiagnostic::parse`](crate::diagnostics::HostingDiagnostic::parse).
+pub fn capture_errors<R>(hostfxr: &Hostfxr, f: impl FnOnce() -> R) -> (R, Vec<String>) {
+    run_capturing_errors(hostfxr, f)
+}
+
+fn run_capturing_errors<R>(
+    hostfxr: &dyn SetErrorWriter,
+    f: impl FnOnce() -> R,
+) -> (R, Vec<String>) {
+    struct PopCapture;
+    impl Drop for PopCapture {
+        fn drop(&mut self) {
+            CAPTURED_ERRORS.with(|captures| captures.borrow_mut().pop());
+        }
+    }
+
+    CAPTURED_ERRORS.with(|captures| captures.borrow_mut().push(Vec::new()));
+    let pop_capture = PopCapture;
+    let previous_fn = unsafe { hostfxr.set_error_writer(Some(error_writer_trampoline)) };
+    let restore = RestoreErrorWriterFn {
+        hostfxr,
+        previous_fn,
+    };
+
+    let result = f();
+
+    drop(restore);
+    let messages = CAPTURED_ERRORS.with(|captures| {
+        captures
+            .borrow_mut()
+            .last_mut()
+            .map(mem::take)
+            .unwrap_or_default()
+    });
+    drop(pop_capture);
+    (result, messages)
+}
+
+/// Restores a previously registered [`hostfxr_error_writer_fn`] when dropped, even if the guarded call panics.
+struct RestoreErrorWriterFn<'lib> {
+    hostfxr: &'lib dyn SetErrorWriter,
+    previous_fn: Option<hostfxr_error_writer_fn>,
+}
+
+impl Drop for RestoreErrorWriterFn<'_> {
+    fn drop(&mut self) {
+        unsafe { self.hostfxr.set_error_writer(self.previous_fn) };
+    }
+}
+
+/// Error writer which forwards hostfxr errors to the [`log`] crate.
+#[cfg(feature = "log")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "log")))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogErrorWriter {
+    target: std::borrow::Cow<'static, str>,
+    level: ::log::Level,
+}
+
+#[cfg(feature = "log")]
+impl LogErrorWriter {
+    /// Creates a new writer which logs with the target `"hostfxr"` at [`Level::Error`](::log::Level::Error).
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            target: std::borrow::Cow::Borrowed("hostfxr"),
+            level: ::log::Level::Error,
+        }
+    }
+
+    /// Sets the target used for the log records.
+    #[must_use]
+    pub fn with_target(mut self, target: impl Into<std::borrow::Cow<'static, str>>) -> Self {
+        self.target = target.into();
+        self
+    }
+
+    /// Sets the level used for the log records.
+    #[must_use]
+    pub const fn with_level(mut self, level: ::log::Level) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Logs the given error message.
+    pub fn write(&self, message: &str) {
+        ::log::log!(target: &self.target, self.level, "{message}");
+    }
+
+    /// Registers this writer as the error writer for the current thread.
+    ///
+    /// See [`set_thread_error_writer`] for details.
+    pub fn install(self, hostfxr: &Hostfxr) -> ErrorWriterGuard<'_> {
+        set_thread_error_writer(hostfxr, move |message| self.write(message))
+    }
+
+    /// Sets this writer as the process-wide error writer.
+    ///
+    /// See [`set_global_error_writer`] for details.
+    pub fn install_global(self) {
+        set_global_error_writer(move |message| self.write(message));
+    }
+}
+
+#[cfg(feature = "log")]
+impl Default for LogErrorWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Error writer which forwards hostfxr errors as [`tracing`] events.
+#[cfg(feature = "tracing")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "tracing")))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TracingErrorWriter {
+    target: &'static str,
+    level: ::tracing::Level,
+}
+
+#[cfg(feature = "tracing")]
+impl TracingErrorWriter {
+    /// Creates a new writer which emits events with the target `"hostfxr"` at [`Level::ERROR`](::tracing::Level::ERROR).
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            target: "hostfxr",
+            level: ::tracing::Level::ERROR,
+        }
+    }
+
+    /// Sets the target used for the emitted events.
+    #[must_use]
+    pub const fn with_target(mut self, target: &'static str) -> Self {
+        self.target = target;
+        self
+    }
+
+    /// Sets the level used for the emitted events.
+    #[must_use]
+    pub const fn with_level(mut self, level: ::tracing::Level) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Emits an event for the given error message.
+    pub fn write(&self, message: &str) {
+        tracing_callsite::emit(tracing_callsite::get(self.target, self.level), message);
+    }
+
+    /// Registers this writer as the error writer for the current thread.
+    ///
+    /// See [`set_thread_error_writer`] for details.
+    pub fn install(self, hostfxr: &Hostfxr) -> ErrorWriterGuard<'_> {
+        let callsite = tracing_callsite::get(self.target, self.level);
+        set_thread_error_writer(hostfxr, move |message| {
+            tracing_callsite::emit(callsite, message);
+        })
+    }
+
+    /// Sets this writer as the process-wide error writer.
+    ///
+    /// See [`set_global_error_writer`] for details.
+    pub fn install_global(self) {
+        let callsite = tracing_callsite::get(self.target, self.level);
+        set_global_error_writer(move |message| tracing_callsite::emit(callsite, message));
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl Default for TracingErrorWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `tracing` requires the target and level of an event to be part of its static callsite metadata,
+/// so a callsite is created (and leaked) for each used combination.
+#[cfg(feature = "tracing")]
+mod tracing_callsite {
+    use std::{
+        collections::HashMap,
+        sync::{Mutex, OnceLock},
+    };
+
+    use tracing::{
+        callsite::{self, Callsite, Identifier},
+        field::FieldSet,
+        metadata::Kind,
+        subscriber::Interest,
+        Event, Level, Metadata,
+    };
+
+    pub(super) struct ErrorWriterCallsite {
+        metadata: OnceLock<Metadata<'static>>,
+    }
+
+    impl Callsite for ErrorWriterCallsite {
+        fn set_interest(&self, _interest: Interest) {}
+
+        fn metadata(&self) -> &Metadata<'_> {
+            self.metadata
+                .get()
+                .expect("callsite is initialized on creation")
+        }
+    }
+
+    pub(super) fn get(target: &'static str, level: Level) -> &'static ErrorWriterCallsite {
+        static CALLSITES: OnceLock<
+            Mutex<HashMap<(&'static str, Level), &'static ErrorWriterCallsite>>,
+        > = OnceLock::new();
+
+        let mut callsites = CALLSITES
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        callsites.entry((target, level)).or_insert_with(|| {
+            let callsite: &'static ErrorWriterCallsite = Box::leak(Box::new(ErrorWriterCallsite {
+                metadata: OnceLock::new(),
+            }));
+            let _ = callsite.metadata.set(Metadata::new(
+                "hostfxr error",
+                target,
+                level,
+                Some(file!()),
+                Some(line!()),
+                Some(module_path!()),
+                FieldSet::new(&["message"], Identifier(callsite)),
+                Kind::EVENT,
+            ));
+            callsite::register(callsite);
+            callsite
+        })
+    }
+
+    pub(super) fn emit(callsite: &'static ErrorWriterCallsite, message: &str) {
+        let metadata = callsite.metadata();
+        tracing::dispatcher::get_default(|dispatch| {
+            if !dispatch.enabled(metadata) {
+                return;
+            }
+            let fields = metadata.fields();
+            let Some(field) = fields.field("message") else {
+                return;
+            };
+            let message = format_args!("{message}");
+            let values = [(&field, Some(&message as &dyn tracing::Value))];
+            dispatch.event(&Event::new(metadata, &fields.value_set(&values)));
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::{Cell, RefCell},
+        ptr,
+        rc::Rc,
+        sync::{Arc, Mutex, MutexGuard, PoisonError},
+    };
+
+    use coreclr_hosting_shared::char_t;
+
+    use super::*;
+    use crate::pdcstr::PdCString;
+
+    /// Stands in for hostfxr, which keeps one registered error writer per thread.
+    #[derive(Default)]
+    struct FakeHostfxr {
+        error_writer: Cell<Option<hostfxr_error_writer_fn>>,
+    }
+
+    impl FakeHostfxr {
+        fn error_writer(&self) -> Option<hostfxr_error_writer_fn> {
+            self.error_writer.get()
+        }
+
+        fn report(&self, message: &str) {
+            let message = PdCString::from_os_str(message).unwrap();
+            if let Some(error_writer) = self.error_writer() {
+                error_writer(message.as_ptr());
+            }
+        }
+    }
+
+    impl SetErrorWriter for FakeHostfxr {
+        unsafe fn set_error_writer(
+            &self,
+            error_writer: Option<hostfxr_error_writer_fn>,
+        ) -> Option<hostfxr_error_writer_fn> {
+            self.error_writer.replace(error_writer)
+        }
+    }
+
+    extern "C" fn foreign_error_writer(_message: *const char_t) {}
+
+    fn is_trampoline(error_writer: Option<hostfxr_error_writer_fn>) -> bool {
+        error_writer.is_some_and(|error_writer| {
+            ptr::fn_addr_eq(
+                error_writer,
+                error_writer_trampoline as hostfxr_error_writer_fn,
+            )
+        })
+    }
+
+    fn is_foreign(error_writer: Option<hostfxr_error_writer_fn>) -> bool {
+        error_writer.is_some_and(|error_writer| {
+            ptr::fn_addr_eq(
+                error_writer,
+                foreign_error_writer as hostfxr_error_writer_fn,
+            )
+        })
+    }
+
+    type Messages = Rc<RefCell<Vec<String>>>;
+
+    fn recording_writer(messages: &Messages, name: &'static str) -> BoxedErrorWriter {
+        let messages = messages.clone();
+        Box::new(move |message| messages.borrow_mut().push(format!("{name}: {message}")))
+    }
+
+    /// Serializes the tests, as the global writer is shared between them, and sets it to record into the returned list.
+    fn lock_global_writer(set: bool) -> (MutexGuard<'static, ()>, Arc<Mutex<Vec<String>>>) {
+        static LOCK: Mutex<()> = Mutex::new(());
+        let lock = LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+        let messages = Arc::new(Mutex::new(Vec::new()));
+        if set {
+            let global_messages = messages.clone();
+            set_global_error_writer(move |message| {
+                global_messages
+                    .lock()
+                    .unwrap()
+                    .push(format!("global: {message}"));
+            });
+        } else {
+            clear_global_error_writer();
+        }
+        (lock, messages)
+    }
+
+    #[test]
+    fn dispatch_precedence() {
+        let (_lock, global_messages) = lock_global_writer(true);
+        let thread_messages = Messages::default();
+        let hostfxr = FakeHostfxr::default();
+
+        // the global writer receives messages if there is no thread writer.
+        dispatch("a");
+        assert_eq!(*global_messages.lock().unwrap(), ["global: a"]);
+
+        // the thread writer takes precedence over the global one.
+        let guard =
+            register_thread_error_writer(&hostfxr, recording_writer(&thread_messages, "thread"));
+        dispatch("b");
+        assert_eq!(*thread_messages.borrow(), ["thread: b"]);
+        assert_eq!(global_messages.lock().unwrap().len(), 1);
+
+        // captured messages are still passed on to the writers.
+        let ((), captured) = run_capturing_errors(&hostfxr, || dispatch("c"));
+        assert_eq!(captured, ["c"]);
+        assert_eq!(*thread_messages.borrow(), ["thread: b", "thread: c"]);
+        drop(guard);
+
+        let ((), captured) = run_capturing_errors(&hostfxr, || dispatch("d"));
+        assert_eq!(captured, ["d"]);
+        assert_eq!(*global_messages.lock().unwrap(), ["global: a", "global: d"]);
+
+        // without any writer, captured messages are not written anywhere else (and others go to stderr).
+        clear_global_error_writer();
+        let ((), captured) = run_capturing_errors(&hostfxr, || dispatch("e"));
+        assert_eq!(captured, ["e"]);
+        dispatch("f");
+        assert_eq!(global_messages.lock().unwrap().len(), 2);
+        assert_eq!(thread_messages.borrow().len(), 2);
+    }
+
+    #[test]
+    fn trampoline_dispatches_reported_messages() {
+        let (_lock, _) = lock_global_writer(false);
+        let hostfxr = FakeHostfxr::default();
+
+        let ((), captured) = run_capturing_errors(&hostfxr, || {
+            assert!(is_trampoline(hostfxr.error_writer()));
+            hostfxr.report("first");
+            hostfxr.report("second");
+        });
+        assert_eq!(captured, ["first", "second"]);
+        assert!(hostfxr.error_writer().is_none());
+
+        // a panicking writer does not unwind into hostfxr.
+        let _guard =
+            register_thread_error_writer(&hostfxr, Box::new(|_| panic!("writer panicked")));
+        hostfxr.report("panic");
+        error_writer_trampoline(ptr::null());
+    }
+
+    #[test]
+    fn guards_restore_previous_writers() {
+        let (_lock, _) = lock_global_writer(false);
+        let messages = Messages::default();
+        let hostfxr = FakeHostfxr::default();
+        hostfxr.error_writer.set(Some(foreign_error_writer));
+
+        let outer = register_thread_error_writer(&hostfxr, recording_writer(&messages, "outer"));
+        let inner = register_thread_error_writer(&hostfxr, recording_writer(&messages, "inner"));
+        assert!(is_trampoline(hostfxr.error_writer()));
+        hostfxr.report("a");
+
+        drop(inner);
+        assert!(is_trampoline(hostfxr.error_writer()));
+        hostfxr.report("b");
+
+        drop(outer);
+        assert!(is_foreign(hostfxr.error_writer()));
+        assert!(THREAD_ERROR_WRITER.with(|writer| writer.borrow().is_none()));
+        assert_eq!(*messages.borrow(), ["inner: a", "outer: b"]);
+    }
+
+    #[test]
+    fn global_writer_registration() {
+        let (_lock, global_messages) = lock_global_writer(true);
+        let hostfxr = FakeHostfxr::default();
+
+        run_with_global_error_writer(&hostfxr, || {
+            assert!(is_trampoline(hostfxr.error_writer()));
+            hostfxr.report("a");
+        });
+        assert!(hostfxr.error_writer().is_none());
+        assert_eq!(*global_messages.lock().unwrap(), ["global: a"]);
+
+        // a writer registered directly with hostfxr is left in place.
+        hostfxr.error_writer.set(Some(foreign_error_writer));
+        run_with_global_error_writer(&hostfxr, || {
+            assert!(is_foreign(hostfxr.error_writer()));
+            hostfxr.report("b");
+        });
+        assert!(is_foreign(hostfxr.error_writer()));
+        assert_eq!(global_messages.lock().unwrap().len(), 1);
+
+        // without a global writer, nothing is registered.
+        clear_global_error_writer();
+        hostfxr.error_writer.set(None);
+        run_with_global_error_writer(&hostfxr, || assert!(hostfxr.error_writer().is_none()));
+    }
+
+    #[test]
+    fn nested_capture_errors() {
+        let (_lock, _) = lock_global_writer(false);
+        let hostfxr = FakeHostfxr::default();
+
+        let (inner, outer) = run_capturing_errors(&hostfxr, || {
+            hostfxr.report("before");
+            let ((), inner) = run_capturing_errors(&hostfxr, || hostfxr.report("inner"));
+            assert!(is_trampoline(hostfxr.error_writer()));
+            hostfxr.report("after");
+            inner
+        });
+        assert_eq!(inner, ["inner"]);
+        assert_eq!(outer, ["before", "after"]);
+        assert!(hostfxr.error_writer().is_none());
+        assert!(CAPTURED_ERRORS.with(|captures| captures.borrow().is_empty()));
+
+        // the capture is popped even if the call panics.
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            run_capturing_errors(&hostfxr, || panic!("call panicked"))
+        }));
+        assert!(result.is_err());
+        assert!(hostfxr.error_writer().is_none());
+        assert!(CAPTURED_ERRORS.with(|captures| captures.borrow().is_empty()));
+    }
+}
@@ -0,0 +1,116 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+/// The architecture name used by the .NET installers, e.g. in `DOTNET_ROOT_X64`.
+pub(crate) const ARCH: Option<&str> = if cfg!(target_arch = "x86") {
+    Some("x86")
+} else if cfg!(target_arch = "x86_64") {
+    Some("x64")
+} else if cfg!(target_arch = "arm") {
+    Some("arm")
+} else if cfg!(target_arch = "aarch64") {
+    Some("arm64")
+} else if cfg!(target_arch = "loongarch64") {
+    Some("loongarch64")
+} else if cfg!(target_arch = "riscv64") {
+    Some("riscv64")
+} else if cfg!(target_arch = "s390x") {
+    Some("s390x")
+} else if cfg!(target_arch = "powerpc64") {
+    Some("ppc64le")
+} else {
+    None
+};
+
+/// Locates the root directory of the .NET installation for the architecture of the current process,
+/// the same way an apphost does.
+///
+/// The following locations are checked in order, and the first one containing a `host/fxr` directory is returned:
+/// 1. the `DOTNET_ROOT_<ARCH>` environment variable (like `DOTNET_ROOT_X64`),
+/// 2. the `DOTNET_ROOT(x86)` environment variable for 32-bit processes on Windows,
+/// 3. the `DOTNET_ROOT` environment variable,
+/// 4. the registered install location (`/etc/dotnet/install_location_<arch>` and `/etc/dotnet/install_location`,
+///    not supported on Windows where it is stored in the registry),
+/// 5. the default install location (`%ProgramFiles%\dotnet` on Windows, `/usr/local/share/dotnet` on macOS
+///    and `/usr/share/dotnet` and `/usr/lib/dotnet` on other Unix systems).
+#[must_use]
+pub fn dotnet_root() -> Option<PathBuf> {
+    candidates().into_iter().find(|root| is_dotnet_root(root))
+}
+
+/// Returns whether the given directory looks like the root of a .NET installation, i.e. contains `host/fxr`.
+#[must_use]
+pub fn is_dotnet_root(path: &Path) -> bool {
+    path.join("host").join("fxr").is_dir()
+}
+
+fn candidates() -> Vec<PathBuf> {
+    let env_path = |name: &str| {
+        env::var_os(name)
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+    };
+
+    let mut candidates = Vec::new();
+    if let Some(arch) = ARCH {
+        candidates.extend(env_path(&format!(
+            "DOTNET_ROOT_{}",
+            arch.to_ascii_uppercase()
+        )));
+    }
+    if cfg!(all(windows, target_pointer_width = "32")) {
+        candidates.extend(env_path("DOTNET_ROOT(x86)"));
+    }
+    candidates.extend(env_path("DOTNET_ROOT"));
+    candidates.extend(registered_install_location());
+    candidates.extend(default_install_locations());
+    candidates
+}
+
+#[cfg(not(windows))]
+fn registered_install_location() -> Option<PathBuf> {
+    let config_dir = Path::new("/etc/dotnet");
+    ARCH.map(|arch| config_dir.join(format!("install_location_{arch}")))
+        .into_iter()
+        .chain([config_dir.join("install_location")])
+        .find_map(|file| {
+            let contents = std::fs::read_to_string(file).ok()?;
+            let location = contents.lines().next()?.trim();
+            (!location.is_empty()).then(|| PathBuf::from(location))
+        })
+}
+
+/// The install location is stored in the registry under `HKLM\SOFTWARE\dotnet\Setup\InstalledVersions\<arch>`,
+/// which is not read to avoid depending on the Windows API.
+#[cfg(windows)]
+fn registered_install_location() -> Option<PathBuf> {
+    None
+}
+
+#[cfg(windows)]
+fn default_install_locations() -> Vec<PathBuf> {
+    let program_files = if cfg!(target_pointer_width = "32") {
+        env::var_os("ProgramFiles(x86)").or_else(|| env::var_os("ProgramFiles"))
+    } else {
+        env::var_os("ProgramFiles")
+    };
+    program_files
+        .map(|program_files| Path::new(&program_files).join("dotnet"))
+        .into_iter()
+        .collect()
+}
+
+#[cfg(target_os = "macos")]
+fn default_install_locations() -> Vec<PathBuf> {
+    vec![PathBuf::from("/usr/local/share/dotnet")]
+}
+
+#[cfg(all(not(windows), not(target_os = "macos")))]
+fn default_install_locations() -> Vec<PathBuf> {
+    vec![
+        PathBuf::from("/usr/share/dotnet"),
+        PathBuf::from("/usr/lib/dotnet"),
+    ]
+}
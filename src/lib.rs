@@ -335,6 +335,8 @@ pub struct hostfxr_initialize_parameters {
 impl hostfxr_initialize_parameters {
     /// Creates a new instance with the given `host_path`.
     /// The `size` field is set accordingly to the size of the struct and `dotnet_root` to [`core::ptr::null()`].
+    ///
+    /// See [`InitializeParameters`](crate::parameters::InitializeParameters) for parameters that own their strings.
     #[must_use]
     pub fn with_host_path(host_path: *const char_t) -> Self {
         Self {
@@ -345,6 +347,8 @@ impl hostfxr_initialize_parameters {
     }
     /// Creates a new instance with the given `dotnet_root`.
     /// The `size` field is set accordingly to the size of the struct and `host_path` to [`core::ptr::null()`].
+    ///
+    /// See [`InitializeParameters`](crate::parameters::InitializeParameters) for parameters that own their strings.
     #[must_use]
     pub fn with_dotnet_root(dotnet_root: *const char_t) -> Self {
         Self {
@@ -918,3 +922,85 @@ derive_apis! {
         ) -> i32,
     }
 }
+
+/// Module for owned and borrowed platform strings.
+pub mod pdcstr;
+
+/// Module for lists of paths joined by [`PATH_LIST_SEPARATOR`].
+pub mod path_list;
+
+/// Module for building the parameters of host context initialization.
+pub mod parameters;
+
+/// Module for classifying the error output of hostfxr and hostpolicy.
+pub mod diagnostics;
+
+/// Module for the errors returned by the safe wrappers.
+pub mod error;
+
+/// Module for building and parsing assembly qualified type names.
+pub mod type_name;
+
+/// Module for reading ECMA-335 metadata from managed assemblies.
+pub mod metadata;
+
+/// Module for generating Rust bindings for managed methods.
+pub mod bindgen;
+
+/// Module for locating .NET installations.
+pub mod install;
+
+/// Module for reading and writing single-file bundles.
+pub mod bundle;
+
+/// Module for creating apphosts from the apphost template of the .NET SDK.
+pub mod apphost;
+
+/// Module for reading and writing `.runtimeconfig.json` files.
+#[cfg(feature = "serde")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "serde")))]
+pub mod runtime_config;
+
+/// Module for reading and writing `.deps.json` files.
+#[cfg(feature = "serde")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "serde")))]
+pub mod deps_json;
+
+/// Module for predicting the assets the host resolves from `.deps.json` files.
+#[cfg(feature = "serde")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "serde")))]
+pub mod deps_resolver;
+
+/// Module for typed runtime delegates.
+#[cfg(feature = "netcore3_0")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "netcore3_0")))]
+pub mod delegate;
+
+/// Module for retrieving and calling managed methods.
+#[cfg(feature = "netcore3_0")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "netcore3_0")))]
+pub mod managed;
+
+/// Module for safe host contexts.
+#[cfg(all(feature = "wrapper", feature = "netcore3_0"))]
+#[cfg_attr(
+    feature = "doc-cfg",
+    doc(cfg(all(feature = "wrapper", feature = "netcore3_0")))
+)]
+pub mod context;
+
+/// Module for routing the error output of hostfxr and hostpolicy.
+#[cfg(all(feature = "wrapper", feature = "netcore3_0"))]
+#[cfg_attr(
+    feature = "doc-cfg",
+    doc(cfg(all(feature = "wrapper", feature = "netcore3_0")))
+)]
+pub mod error_writer;
+
+/// Module for running managed applications.
+#[cfg(all(feature = "wrapper", feature = "netcore3_0"))]
+#[cfg_attr(
+    feature = "doc-cfg",
+    doc(cfg(all(feature = "wrapper", feature = "netcore3_0")))
+)]
+pub mod app;
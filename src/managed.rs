@@ -0,0 +1,923 @@
+use std::{error, ffi::c_void, fmt, mem, ops::Deref, ptr};
+
+use coreclr_hosting_shared::{char_t, StatusCode};
+
+#[cfg(feature = "net5_0")]
+use crate::get_function_pointer_fn;
+#[cfg(feature = "net8_0")]
+use crate::metadata::{AssemblyIdentity, ValidationOptions};
+use crate::{
+    component_entry_point_fn,
+    error::ContainsNulError,
+    load_assembly_and_get_function_pointer_fn,
+    metadata::MetadataError,
+    pdcstr::{PdCStr, PdCString, ToPdCStr},
+};
+
+/// A function pointer type that can be returned for a managed method.
+///
+/// This trait is sealed and implemented for `extern "system"` function pointers (both safe and `unsafe`) with up to 12 arguments.
+pub trait FunctionPtr: Copy + sealed::Sealed {
+    /// Creates the function pointer from a raw pointer.
+    ///
+    /// # Safety
+    /// `ptr` must be a non-null pointer to a function with the signature of `Self`.
+    unsafe fn from_ptr(ptr: *const c_void) -> Self;
+
+    /// Returns the function pointer as a raw pointer.
+    fn as_ptr(self) -> *const c_void;
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+macro_rules! impl_function_ptr {
+    ($($arg:ident),*) => {
+        impl<R, $($arg),*> sealed::Sealed for extern "system" fn($($arg),*) -> R {}
+        impl<R, $($arg),*> FunctionPtr for extern "system" fn($($arg),*) -> R {
+            unsafe fn from_ptr(ptr: *const c_void) -> Self {
+                unsafe { core::mem::transmute::<*const c_void, Self>(ptr) }
+            }
+
+            fn as_ptr(self) -> *const c_void {
+                self as *const c_void
+            }
+        }
+
+        impl<R, $($arg),*> sealed::Sealed for unsafe extern "system" fn($($arg),*) -> R {}
+        impl<R, $($arg),*> FunctionPtr for unsafe extern "system" fn($($arg),*) -> R {
+            unsafe fn from_ptr(ptr: *const c_void) -> Self {
+                unsafe { core::mem::transmute::<*const c_void, Self>(ptr) }
+            }
+
+            fn as_ptr(self) -> *const c_void {
+                self as *const c_void
+            }
+        }
+    };
+}
+
+impl_function_ptr!();
+impl_function_ptr!(A);
+impl_function_ptr!(A, B);
+impl_function_ptr!(A, B, C);
+impl_function_ptr!(A, B, C, D);
+impl_function_ptr!(A, B, C, D, E);
+impl_function_ptr!(A, B, C, D, E, F);
+impl_function_ptr!(A, B, C, D, E, F, G);
+impl_function_ptr!(A, B, C, D, E, F, G, H);
+impl_function_ptr!(A, B, C, D, E, F, G, H, I);
+impl_function_ptr!(A, B, C, D, E, F, G, H, I, J);
+impl_function_ptr!(A, B, C, D, E, F, G, H, I, J, K);
+impl_function_ptr!(A, B, C, D, E, F, G, H, I, J, K, L);
+
+/// A function pointer to a managed method.
+///
+/// As the runtime cannot be unloaded, the pointer stays valid for the lifetime of the process.
+#[derive(Clone, Copy)]
+pub struct ManagedFunction<F: FunctionPtr>(F);
+
+impl<F: FunctionPtr> ManagedFunction<F> {
+    /// Creates a new instance from a raw function pointer.
+    ///
+    /// # Safety
+    /// `ptr` must be a non-null function pointer to a managed method with the signature of `F`.
+    #[must_use]
+    pub unsafe fn from_ptr(ptr: *const c_void) -> Self {
+        Self(unsafe { F::from_ptr(ptr) })
+    }
+
+    /// Returns the typed function pointer.
+    #[must_use]
+    pub fn into_inner(self) -> F {
+        self.0
+    }
+
+    /// Returns the function pointer as a raw pointer.
+    #[must_use]
+    pub fn as_ptr(&self) -> *const c_void {
+        self.0.as_ptr()
+    }
+}
+
+impl ManagedFunction<component_entry_point_fn> {
+    /// Invokes the method without an argument (passing a null pointer and a size of 0).
+    ///
+    /// Negative results are treated as failures (following the `HRESULT` convention), all other results are returned as is.
+    ///
+    /// # Safety
+    /// The managed method must not access the argument.
+    pub unsafe fn invoke(&self) -> Result<i32, InvokeError> {
+        unsafe { self.invoke_raw(ptr::null(), 0) }
+    }
+
+    /// Invokes the method with a pointer to the given bytes and their length.
+    ///
+    /// Negative results are treated as failures (following the `HRESULT` convention), all other results are returned as is.
+    ///
+    /// # Safety
+    /// The managed method must only read the argument and only within its length.
+    pub unsafe fn invoke_with_bytes(&self, args: &[u8]) -> Result<i32, InvokeError> {
+        unsafe { self.invoke_raw(args.as_ptr().cast(), args.len()) }
+    }
+
+    /// Invokes the method with a pointer to the given bytes and their length, allowing the method to write to them.
+    ///
+    /// Negative results are treated as failures (following the `HRESULT` convention), all other results are returned as is.
+    ///
+    /// # Safety
+    /// The managed method must only access the argument within its length.
+    pub unsafe fn invoke_with_bytes_mut(&self, args: &mut [u8]) -> Result<i32, InvokeError> {
+        unsafe { self.invoke_raw(args.as_mut_ptr().cast_const().cast(), args.len()) }
+    }
+
+    /// Invokes the method with a pointer to the given value and its size.
+    /// `T` should be `#[repr(C)]` (or another layout that the managed side can describe, e.g. using `[StructLayout(LayoutKind.Sequential)]`).
+    ///
+    /// Negative results are treated as failures (following the `HRESULT` convention), all other results are returned as is.
+    ///
+    /// # Safety
+    /// The managed method must only read the argument, only within its size and interpret it according to the layout of `T`.
+    pub unsafe fn invoke_with<T>(&self, arg: &T) -> Result<i32, InvokeError> {
+        unsafe { self.invoke_raw(ptr::from_ref(arg).cast(), mem::size_of::<T>()) }
+    }
+
+    /// Invokes the method with a pointer to the given value and its size, allowing the method to write to it
+    /// (e.g. to return results).
+    /// `T` should be `#[repr(C)]` (or another layout that the managed side can describe, e.g. using `[StructLayout(LayoutKind.Sequential)]`).
+    ///
+    /// Negative results are treated as failures (following the `HRESULT` convention), all other results are returned as is.
+    ///
+    /// # Safety
+    /// The managed method must only access the argument within its size, interpret it according to the layout of `T`
+    /// and leave it in a valid state for `T`.
+    pub unsafe fn invoke_with_mut<T>(&self, arg: &mut T) -> Result<i32, InvokeError> {
+        unsafe { self.invoke_raw(ptr::from_mut(arg).cast_const().cast(), mem::size_of::<T>()) }
+    }
+
+    unsafe fn invoke_raw(&self, arg: *const c_void, size: usize) -> Result<i32, InvokeError> {
+        let size = argument_size(size)?;
+        let result = unsafe { (self.0)(arg, size) };
+        if result < 0 {
+            return Err(InvokeError::Failed { result });
+        }
+        Ok(result)
+    }
+}
+
+/// Converts the size of an argument to the `sizeBytes` parameter of [`component_entry_point_fn`].
+fn argument_size(size: usize) -> Result<i32, InvokeError> {
+    i32::try_from(size).map_err(|_| InvokeError::ArgumentTooLarge { size })
+}
+
+/// Error returned when invoking a method with the signature of [`component_entry_point_fn`] fails.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum InvokeError {
+    /// The size of the argument does not fit into an [`i32`].
+    ArgumentTooLarge {
+        /// The size of the argument in bytes.
+        size: usize,
+    },
+    /// The method returned a negative result.
+    Failed {
+        /// The result returned by the method.
+        result: i32,
+    },
+}
+
+impl fmt::Display for InvokeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ArgumentTooLarge { size } => {
+                write!(
+                    f,
+                    "argument of {size} bytes is too large to be passed to a managed method"
+                )
+            }
+            Self::Failed { result } => {
+                write!(f, "managed method failed with {result:#010x}")
+            }
+        }
+    }
+}
+
+impl error::Error for InvokeError {}
+
+impl<F: FunctionPtr> Deref for ManagedFunction<F> {
+    type Target = F;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<F: FunctionPtr> fmt::Debug for ManagedFunction<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ManagedFunction")
+            .field(&self.as_ptr())
+            .finish()
+    }
+}
+
+/// Safe wrapper around a [`load_assembly_and_get_function_pointer_fn`] delegate, which loads an assembly
+/// (with its dependencies) into an isolated load context and returns function pointers for its static methods.
+///
+/// Obtained using [`HostfxrContext::get_delegate_loader`](crate::context::HostfxrContext::get_delegate_loader).
+#[derive(Clone, Copy, Debug)]
+pub struct AssemblyDelegateLoader {
+    load_assembly_and_get_function_pointer: load_assembly_and_get_function_pointer_fn,
+}
+
+impl AssemblyDelegateLoader {
+    /// Creates a new loader from a raw delegate.
+    ///
+    /// # Safety
+    /// `load_assembly_and_get_function_pointer` must be a delegate returned for
+    /// [`hdt_load_assembly_and_get_function_pointer`](crate::hostfxr_delegate_type::hdt_load_assembly_and_get_function_pointer).
+    #[must_use]
+    pub const unsafe fn from_delegate(
+        load_assembly_and_get_function_pointer: load_assembly_and_get_function_pointer_fn,
+    ) -> Self {
+        Self {
+            load_assembly_and_get_function_pointer,
+        }
+    }
+
+    /// Returns the raw delegate.
+    #[must_use]
+    pub const fn delegate(&self) -> load_assembly_and_get_function_pointer_fn {
+        self.load_assembly_and_get_function_pointer
+    }
+
+    /// Loads the given assembly and returns a function pointer to the given method, which has to have the
+    /// default signature of [`component_entry_point_fn`] (`public delegate int ComponentEntryPoint(IntPtr args, int sizeBytes)`).
+    ///
+    /// # Arguments
+    ///  * `assembly_path`:
+    ///    Path to the assembly to load.
+    ///  * `type_name`:
+    ///    Assembly qualified name of the type containing the method (e.g. `App.Program, App`).
+    ///  * `method_name`:
+    ///    Name of the public static method.
+    pub fn get_function_with_default_signature(
+        &self,
+        assembly_path: impl ToPdCStr,
+        type_name: impl ToPdCStr,
+        method_name: impl ToPdCStr,
+    ) -> Result<ManagedFunction<component_entry_point_fn>, GetFunctionError> {
+        unsafe {
+            self.get_function_with_signature(
+                assembly_path,
+                type_name,
+                method_name,
+                DelegateSignature::Default,
+            )
+        }
+    }
+
+    /// Loads the given assembly and returns a function pointer to the given method, whose signature is described
+    /// by the given delegate type.
+    ///
+    /// # Arguments
+    ///  * `assembly_path`:
+    ///    Path to the assembly to load.
+    ///  * `type_name`:
+    ///    Assembly qualified name of the type containing the method (e.g. `App.Program, App`).
+    ///  * `method_name`:
+    ///    Name of the public static method.
+    ///  * `delegate_type_name`:
+    ///    Assembly qualified name of the delegate type describing the signature of the method (e.g. `App.Program+CustomDelegate, App`).
+    ///
+    /// # Safety
+    /// `F` has to match the signature of the delegate type.
+    pub unsafe fn get_function<F: FunctionPtr>(
+        &self,
+        assembly_path: impl ToPdCStr,
+        type_name: impl ToPdCStr,
+        method_name: impl ToPdCStr,
+        delegate_type_name: &str,
+    ) -> Result<ManagedFunction<F>, GetFunctionError> {
+        unsafe {
+            self.get_function_with_signature(
+                assembly_path,
+                type_name,
+                method_name,
+                DelegateSignature::DelegateType(delegate_type_name),
+            )
+        }
+    }
+
+    /// Loads the given assembly and returns a function pointer to the given method, which has to be marked with the
+    /// [`UnmanagedCallersOnlyAttribute`].
+    ///
+    /// # Arguments
+    ///  * `assembly_path`:
+    ///    Path to the assembly to load.
+    ///  * `type_name`:
+    ///    Assembly qualified name of the type containing the method (e.g. `App.Program, App`).
+    ///  * `method_name`:
+    ///    Name of the public static method.
+    ///
+    /// # Safety
+    /// `F` has to match the signature of the method.
+    ///
+    /// [`UnmanagedCallersOnlyAttribute`]: https://docs.microsoft.com/en-us/dotnet/api/system.runtime.interopservices.unmanagedcallersonlyattribute
+    #[cfg(feature = "net5_0")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "net5_0")))]
+    pub unsafe fn get_function_with_unmanaged_callers_only<F: FunctionPtr>(
+        &self,
+        assembly_path: impl ToPdCStr,
+        type_name: impl ToPdCStr,
+        method_name: impl ToPdCStr,
+    ) -> Result<ManagedFunction<F>, GetFunctionError> {
+        unsafe {
+            self.get_function_with_signature(
+                assembly_path,
+                type_name,
+                method_name,
+                DelegateSignature::UnmanagedCallersOnly,
+            )
+        }
+    }
+
+    /// Loads the given assembly and returns a function pointer to the given method with the given signature.
+    ///
+    /// # Arguments
+    ///  * `assembly_path`:
+    ///    Path to the assembly to load.
+    ///  * `type_name`:
+    ///    Assembly qualified name of the type containing the method (e.g. `App.Program, App`).
+    ///  * `method_name`:
+    ///    Name of the public static method.
+    ///  * `signature`:
+    ///    How the signature of the method is specified.
+    ///
+    /// # Safety
+    /// `F` has to match the signature of the method.
+    pub unsafe fn get_function_with_signature<F: FunctionPtr>(
+        &self,
+        assembly_path: impl ToPdCStr,
+        type_name: impl ToPdCStr,
+        method_name: impl ToPdCStr,
+        signature: DelegateSignature<'_>,
+    ) -> Result<ManagedFunction<F>, GetFunctionError> {
+        let assembly_path = assembly_path.to_pdcstr()?;
+        let type_name = type_name.to_pdcstr()?;
+        let method_name = method_name.to_pdcstr()?;
+        let delegate_type_name = signature.to_pdcstring()?;
+
+        let mut delegate = ptr::null();
+        let result = unsafe {
+            (self.load_assembly_and_get_function_pointer)(
+                assembly_path.as_ptr(),
+                type_name.as_ptr(),
+                method_name.as_ptr(),
+                signature.as_ptr(delegate_type_name.as_deref()),
+                ptr::null(),
+                &raw mut delegate,
+            )
+        };
+        check_delegate_result(result, delegate, &type_name, &method_name)?;
+        Ok(unsafe { ManagedFunction::from_ptr(delegate) })
+    }
+}
+
+/// Safe wrapper around a [`get_function_pointer_fn`] delegate, which returns function pointers for static methods
+/// of types that are already loaded in the default load context.
+///
+/// Obtained using [`HostfxrContext::get_function_pointer_loader`](crate::context::HostfxrContext::get_function_pointer_loader).
+#[cfg(feature = "net5_0")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "net5_0")))]
+#[derive(Clone, Copy, Debug)]
+pub struct FunctionPointerLoader {
+    get_function_pointer: get_function_pointer_fn,
+}
+
+#[cfg(feature = "net5_0")]
+impl FunctionPointerLoader {
+    /// Creates a new loader from a raw delegate.
+    ///
+    /// # Safety
+    /// `get_function_pointer` must be a delegate returned for
+    /// [`hdt_get_function_pointer`](crate::hostfxr_delegate_type::hdt_get_function_pointer).
+    #[must_use]
+    pub const unsafe fn from_delegate(get_function_pointer: get_function_pointer_fn) -> Self {
+        Self {
+            get_function_pointer,
+        }
+    }
+
+    /// Returns the raw delegate.
+    #[must_use]
+    pub const fn delegate(&self) -> get_function_pointer_fn {
+        self.get_function_pointer
+    }
+
+    /// Returns a function pointer to the given method, which has to have the default signature of [`component_entry_point_fn`].
+    ///
+    /// # Arguments
+    ///  * `type_name`:
+    ///    Assembly qualified name of the type containing the method (e.g. `App.Program, App`).
+    ///  * `method_name`:
+    ///    Name of the public static method.
+    pub fn get_function_pointer_with_default_signature(
+        &self,
+        type_name: impl ToPdCStr,
+        method_name: impl ToPdCStr,
+    ) -> Result<ManagedFunction<component_entry_point_fn>, GetFunctionError> {
+        unsafe { self.get_function_pointer(type_name, method_name, DelegateSignature::Default) }
+    }
+
+    /// Returns a function pointer to the given method with the given signature.
+    ///
+    /// # Arguments
+    ///  * `type_name`:
+    ///    Assembly qualified name of the type containing the method (e.g. `App.Program, App`).
+    ///  * `method_name`:
+    ///    Name of the public static method.
+    ///  * `signature`:
+    ///    How the signature of the method is specified.
+    ///
+    /// # Safety
+    /// `F` has to match the signature of the method.
+    pub unsafe fn get_function_pointer<F: FunctionPtr>(
+        &self,
+        type_name: impl ToPdCStr,
+        method_name: impl ToPdCStr,
+        signature: DelegateSignature<'_>,
+    ) -> Result<ManagedFunction<F>, GetFunctionError> {
+        let type_name = type_name.to_pdcstr()?;
+        let method_name = method_name.to_pdcstr()?;
+        let delegate_type_name = signature.to_pdcstring()?;
+
+        let mut delegate = ptr::null();
+        let result = unsafe {
+            (self.get_function_pointer)(
+                type_name.as_ptr(),
+                method_name.as_ptr(),
+                signature.as_ptr(delegate_type_name.as_deref()),
+                ptr::null(),
+                ptr::null(),
+                &raw mut delegate,
+            )
+        };
+        check_delegate_result(result, delegate, &type_name, &method_name)?;
+        Ok(unsafe { ManagedFunction::from_ptr(delegate) })
+    }
+}
+
+/// Describes the signature of a managed method whose function pointer is requested.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DelegateSignature<'a> {
+    /// The method has the signature of [`component_entry_point_fn`] (`public delegate int ComponentEntryPoint(IntPtr args, int sizeBytes)`).
+    Default,
+    /// The signature is described by the delegate type with the given assembly qualified name (e.g. `App.Program+CustomDelegate, App`).
+    DelegateType(&'a str),
+    /// The method is marked with the [`UnmanagedCallersOnlyAttribute`].
+    ///
+    /// [`UnmanagedCallersOnlyAttribute`]: https://docs.microsoft.com/en-us/dotnet/api/system.runtime.interopservices.unmanagedcallersonlyattribute
+    #[cfg(feature = "net5_0")]
+    #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "net5_0")))]
+    UnmanagedCallersOnly,
+}
+
+impl DelegateSignature<'_> {
+    fn to_pdcstring(self) -> Result<Option<PdCString>, ContainsNulError> {
+        if let Self::DelegateType(name) = self {
+            PdCString::from_os_str(name).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn as_ptr(self, delegate_type_name: Option<&PdCStr>) -> *const char_t {
+        match self {
+            Self::Default => ptr::null(),
+            Self::DelegateType(_) => delegate_type_name.map_or(ptr::null(), PdCStr::as_ptr),
+            #[cfg(feature = "net5_0")]
+            Self::UnmanagedCallersOnly => crate::UNMANAGED_CALLERS_ONLY_METHOD,
+        }
+    }
+}
+
+fn check_delegate_result(
+    result: i32,
+    delegate: *const c_void,
+    type_name: &PdCStr,
+    method_name: &PdCStr,
+) -> Result<(), GetFunctionError> {
+    #[allow(clippy::cast_sign_loss)]
+    if result < 0 {
+        return Err(GetFunctionError::Failed {
+            type_name: type_name.to_string_lossy(),
+            method_name: method_name.to_string_lossy(),
+            kind: ManagedErrorKind::from_hresult(result as u32),
+            hresult: result as u32,
+        });
+    }
+    if delegate.is_null() {
+        return Err(GetFunctionError::NullPointer);
+    }
+    Ok(())
+}
+
+/// The kind of failure reported by a runtime delegate, derived from the returned `HRESULT`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ManagedErrorKind {
+    /// The assembly file could not be found (`FileNotFoundException`).
+    AssemblyNotFound,
+    /// The assembly was found but could not be loaded (`FileLoadException`).
+    AssemblyLoadFailed,
+    /// The file is not a valid assembly (`BadImageFormatException`).
+    BadImageFormat,
+    /// The type could not be found (`TypeLoadException`).
+    TypeNotFound,
+    /// The method could not be found (`MissingMethodException`).
+    MethodNotFound,
+    /// An argument was invalid, e.g. the method does not match the signature of the delegate type (`ArgumentException`).
+    InvalidArgument,
+    /// The operation is not valid in the current state of the runtime (`InvalidOperationException`).
+    InvalidOperation,
+    /// The operation is not supported (`NotSupportedException`).
+    NotSupported,
+    /// Loading managed components is disabled for this runtime (see [`HostFeatureDisabled`](coreclr_hosting_shared::StatusCode::HostFeatureDisabled)).
+    FeatureDisabled,
+    /// Any other failure.
+    Other,
+}
+
+impl ManagedErrorKind {
+    /// Classifies the given `HRESULT`.
+    #[must_use]
+    pub const fn from_hresult(hresult: u32) -> Self {
+        match hresult {
+            0x8007_0002 | 0x8007_0003 => Self::AssemblyNotFound,
+            0x8013_1621 | 0x8013_1040 => Self::AssemblyLoadFailed,
+            0x8007_000B => Self::BadImageFormat,
+            0x8013_1522 => Self::TypeNotFound,
+            0x8013_1513 => Self::MethodNotFound,
+            // FUSION_E_INVALID_NAME is reported for malformed assembly names.
+            0x8007_0057 | 0x8000_4003 | 0x8013_1047 => Self::InvalidArgument,
+            0x8013_1509 => Self::InvalidOperation,
+            0x8013_1515 => Self::NotSupported,
+            c if c == StatusCode::HostFeatureDisabled as u32 => Self::FeatureDisabled,
+            _ => Self::Other,
+        }
+    }
+
+    const fn description(self) -> &'static str {
+        match self {
+            Self::AssemblyNotFound => "assembly not found",
+            Self::AssemblyLoadFailed => "assembly could not be loaded",
+            Self::BadImageFormat => "invalid assembly image",
+            Self::TypeNotFound => "type not found",
+            Self::MethodNotFound => "method not found",
+            Self::InvalidArgument => "invalid argument or signature mismatch",
+            Self::InvalidOperation => "operation not valid in the current state",
+            Self::NotSupported => "operation not supported",
+            Self::FeatureDisabled => "loading managed components is disabled",
+            Self::Other => "unknown error",
+        }
+    }
+}
+
+/// Error returned when a function pointer for a managed method could not be retrieved.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum GetFunctionError {
+    /// An argument contained an interior nul character.
+    ContainsNul(ContainsNulError),
+    /// The runtime returned a failure `HRESULT` (or hosting status code).
+    Failed {
+        /// The requested type.
+        type_name: String,
+        /// The requested method.
+        method_name: String,
+        /// The kind of failure.
+        kind: ManagedErrorKind,
+        /// The raw result.
+        hresult: u32,
+    },
+    /// The runtime reported success but returned a null pointer.
+    NullPointer,
+}
+
+impl GetFunctionError {
+    /// Returns the kind of failure reported by the runtime, if any.
+    #[must_use]
+    pub const fn kind(&self) -> Option<ManagedErrorKind> {
+        match self {
+            Self::Failed { kind, .. } => Some(*kind),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for GetFunctionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ContainsNul(err) => err.fmt(f),
+            Self::Failed {
+                type_name,
+                method_name,
+                kind,
+                hresult,
+            } => write!(
+                f,
+                "failed to get function pointer for {method_name} on [{type_name}]: {} ({hresult:#010x})",
+                kind.description()
+            ),
+            Self::NullPointer => f.write_str("runtime returned a null function pointer"),
+        }
+    }
+}
+
+impl error::Error for GetFunctionError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::ContainsNul(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ContainsNulError> for GetFunctionError {
+    fn from(err: ContainsNulError) -> Self {
+        Self::ContainsNul(err)
+    }
+}
+
+/// The load context an assembly is loaded into.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum LoadContext {
+    /// The default load context.
+    #[default]
+    Default,
+    /// A handle to a custom load context.
+    ///
+    /// This is reserved for future versions of the runtime, which currently only support loading into the default
+    /// load context, so it is always rejected with [`LoadAssemblyError::UnsupportedLoadContext`].
+    Custom(*const c_void),
+}
+
+impl LoadContext {
+    fn as_ptr(self) -> Result<*const c_void, LoadAssemblyError> {
+        match self {
+            Self::Default => Ok(ptr::null()),
+            Self::Custom(_) => Err(LoadAssemblyError::UnsupportedLoadContext),
+        }
+    }
+}
+
+/// Safe wrapper around a [`load_assembly_fn`](crate::load_assembly_fn) delegate, which loads an assembly (and registers dependency resolution
+/// based on its `.deps.json`) into a load context.
+///
+/// Obtained using [`HostfxrContext::get_assembly_loader`](crate::context::HostfxrContext::get_assembly_loader).
+#[cfg(feature = "net8_0")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "net8_0")))]
+#[derive(Clone, Copy, Debug)]
+pub struct AssemblyLoader {
+    load_assembly: crate::load_assembly_fn,
+}
+
+#[cfg(feature = "net8_0")]
+impl AssemblyLoader {
+    /// Creates a new loader from a raw delegate.
+    ///
+    /// # Safety
+    /// `load_assembly` must be a delegate returned for [`hdt_load_assembly`](crate::hostfxr_delegate_type::hdt_load_assembly).
+    #[must_use]
+    pub const unsafe fn from_delegate(load_assembly: crate::load_assembly_fn) -> Self {
+        Self { load_assembly }
+    }
+
+    /// Returns the raw delegate.
+    #[must_use]
+    pub const fn delegate(&self) -> crate::load_assembly_fn {
+        self.load_assembly
+    }
+
+    /// Loads the assembly at the given path into the default load context.
+    pub fn load(&self, assembly_path: impl ToPdCStr) -> Result<(), LoadAssemblyError> {
+        self.load_into(assembly_path, LoadContext::Default)
+    }
+
+    /// Loads the assembly at the given path into the given load context.
+    pub fn load_into(
+        &self,
+        assembly_path: impl ToPdCStr,
+        load_context: LoadContext,
+    ) -> Result<(), LoadAssemblyError> {
+        let load_context = load_context.as_ptr()?;
+        let assembly_path = assembly_path.to_pdcstr()?;
+        let result =
+            unsafe { (self.load_assembly)(assembly_path.as_ptr(), load_context, ptr::null()) };
+        check_load_result(result)
+    }
+
+    /// Validates the assembly at the given path using the default [`ValidationOptions`] and loads it into the default load context,
+    /// returning its identity.
+    ///
+    /// See [`MetadataReader::validate`](crate::metadata::MetadataReader::validate) for the checks performed.
+    pub fn load_checked(
+        &self,
+        assembly_path: impl ToPdCStr,
+    ) -> Result<AssemblyIdentity, LoadAssemblyError> {
+        self.load_validated(assembly_path, &ValidationOptions::default())
+    }
+
+    /// Validates the assembly at the given path using the given options and loads it into the default load context,
+    /// returning its identity.
+    ///
+    /// See [`MetadataReader::validate`](crate::metadata::MetadataReader::validate) for the checks performed.
+    pub fn load_validated(
+        &self,
+        assembly_path: impl ToPdCStr,
+        options: &ValidationOptions,
+    ) -> Result<AssemblyIdentity, LoadAssemblyError> {
+        let assembly_path = assembly_path.to_pdcstr()?;
+        let identity = options.validate_file(assembly_path.to_os_string())?;
+        self.load(&*assembly_path)?;
+        Ok(identity)
+    }
+}
+
+/// Safe wrapper around a [`load_assembly_bytes_fn`](crate::load_assembly_bytes_fn) delegate, which loads an assembly from memory into a load context.
+///
+/// Obtained using [`HostfxrContext::get_assembly_bytes_loader`](crate::context::HostfxrContext::get_assembly_bytes_loader).
+#[cfg(feature = "net8_0")]
+#[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "net8_0")))]
+#[derive(Clone, Copy, Debug)]
+pub struct AssemblyBytesLoader {
+    load_assembly_bytes: crate::load_assembly_bytes_fn,
+}
+
+#[cfg(feature = "net8_0")]
+impl AssemblyBytesLoader {
+    /// Creates a new loader from a raw delegate.
+    ///
+    /// # Safety
+    /// `load_assembly_bytes` must be a delegate returned for [`hdt_load_assembly_bytes`](crate::hostfxr_delegate_type::hdt_load_assembly_bytes).
+    #[must_use]
+    pub const unsafe fn from_delegate(load_assembly_bytes: crate::load_assembly_bytes_fn) -> Self {
+        Self {
+            load_assembly_bytes,
+        }
+    }
+
+    /// Returns the raw delegate.
+    #[must_use]
+    pub const fn delegate(&self) -> crate::load_assembly_bytes_fn {
+        self.load_assembly_bytes
+    }
+
+    /// Loads the given assembly image, with optional symbols (the contents of a `.pdb` file), into the default load context.
+    ///
+    /// Dependencies of the assembly are not resolved automatically, see [`load_assembly_bytes_fn`](crate::load_assembly_bytes_fn) for details.
+    pub fn load(&self, assembly: &[u8], symbols: Option<&[u8]>) -> Result<(), LoadAssemblyError> {
+        self.load_into(assembly, symbols, LoadContext::Default)
+    }
+
+    /// Loads the given assembly image, with optional symbols (the contents of a `.pdb` file), into the given load context.
+    pub fn load_into(
+        &self,
+        assembly: &[u8],
+        symbols: Option<&[u8]>,
+        load_context: LoadContext,
+    ) -> Result<(), LoadAssemblyError> {
+        let load_context = load_context.as_ptr()?;
+        let symbols = symbols.unwrap_or_default();
+        let result = unsafe {
+            (self.load_assembly_bytes)(
+                assembly.as_ptr(),
+                assembly.len(),
+                if symbols.is_empty() {
+                    ptr::null()
+                } else {
+                    symbols.as_ptr()
+                },
+                symbols.len(),
+                load_context,
+                ptr::null(),
+            )
+        };
+        check_load_result(result)
+    }
+
+    /// Validates the given assembly image using the default [`ValidationOptions`] and loads it into the default load context,
+    /// returning its identity.
+    ///
+    /// See [`MetadataReader::validate`](crate::metadata::MetadataReader::validate) for the checks performed.
+    pub fn load_checked(
+        &self,
+        assembly: &[u8],
+        symbols: Option<&[u8]>,
+    ) -> Result<AssemblyIdentity, LoadAssemblyError> {
+        self.load_validated(assembly, symbols, &ValidationOptions::default())
+    }
+
+    /// Validates the given assembly image using the given options and loads it into the default load context,
+    /// returning its identity.
+    ///
+    /// See [`MetadataReader::validate`](crate::metadata::MetadataReader::validate) for the checks performed.
+    pub fn load_validated(
+        &self,
+        assembly: &[u8],
+        symbols: Option<&[u8]>,
+        options: &ValidationOptions,
+    ) -> Result<AssemblyIdentity, LoadAssemblyError> {
+        let identity = options.validate(assembly)?;
+        self.load(assembly, symbols)?;
+        Ok(identity)
+    }
+}
+
+fn check_load_result(result: i32) -> Result<(), LoadAssemblyError> {
+    #[allow(clippy::cast_sign_loss)]
+    if result < 0 {
+        return Err(LoadAssemblyError::Failed {
+            kind: ManagedErrorKind::from_hresult(result as u32),
+            hresult: result as u32,
+        });
+    }
+    Ok(())
+}
+
+/// Error returned when an assembly could not be loaded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum LoadAssemblyError {
+    /// The path contained an interior nul character.
+    ContainsNul(ContainsNulError),
+    /// A load context other than [`LoadContext::Default`] was requested, which the runtime does not support yet.
+    UnsupportedLoadContext,
+    /// The assembly is not a valid managed assembly.
+    InvalidAssembly(MetadataError),
+    /// The runtime returned a failure `HRESULT` (or hosting status code).
+    Failed {
+        /// The kind of failure.
+        kind: ManagedErrorKind,
+        /// The raw result.
+        hresult: u32,
+    },
+}
+
+impl fmt::Display for LoadAssemblyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ContainsNul(err) => err.fmt(f),
+            Self::UnsupportedLoadContext => {
+                f.write_str("only the default load context is supported by the runtime")
+            }
+            Self::InvalidAssembly(err) => err.fmt(f),
+            Self::Failed { kind, hresult } => write!(
+                f,
+                "failed to load assembly: {} ({hresult:#010x})",
+                kind.description()
+            ),
+        }
+    }
+}
+
+impl error::Error for LoadAssemblyError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::ContainsNul(err) => Some(err),
+            Self::InvalidAssembly(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ContainsNulError> for LoadAssemblyError {
+    fn from(err: ContainsNulError) -> Self {
+        Self::ContainsNul(err)
+    }
+}
+
+impl From<MetadataError> for LoadAssemblyError {
+    fn from(err: MetadataError) -> Self {
+        Self::InvalidAssembly(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn argument_size_fits_i32() {
+        assert_eq!(argument_size(0), Ok(0));
+        assert_eq!(argument_size(i32::MAX as usize), Ok(i32::MAX));
+        for size in [i32::MAX as usize + 1, usize::MAX] {
+            assert_eq!(
+                argument_size(size),
+                Err(InvokeError::ArgumentTooLarge { size })
+            );
+        }
+    }
+}
@@ -0,0 +1,727 @@
+use std::{error, fmt, fs, io, path::Path};
+
+use crate::type_name::{
+    AssemblyName, AssemblyQualifiedName, AssemblyVersion, PublicKeyToken, TypeName,
+};
+
+pub(crate) mod sha1;
+mod signature;
+mod tables;
+mod validation;
+
+use signature::SignatureDecoder;
+pub use signature::{MethodSignature, Parameter, ParameterType};
+pub(crate) use tables::{CodedIndex, TableId, Tables};
+use validation::{machine_name, ShortVersion};
+pub use validation::{TargetFramework, ValidationOptions};
+
+/// Index of the CLI header in the data directories of the optional header.
+const CLI_HEADER_DIRECTORY: usize = 14;
+/// Signature of the metadata root (`BSJB`).
+const METADATA_SIGNATURE: u32 = 0x424A_5342;
+/// `MethodAttributes.Static`
+const METHOD_STATIC: u32 = 0x0010;
+/// Maximum nesting depth of types, to guard against cycles in malformed metadata.
+const MAX_NESTING_DEPTH: usize = 64;
+
+/// The identity of an assembly as stored in its `Assembly` metadata table.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AssemblyIdentity {
+    /// The simple name of the assembly.
+    pub name: String,
+    /// The version of the assembly.
+    pub version: AssemblyVersion,
+    /// The culture of the assembly, or [`None`] if the assembly is culture-neutral.
+    pub culture: Option<String>,
+    /// The full public key of the assembly, or [`None`] if the assembly is not strong-named.
+    pub public_key: Option<Vec<u8>>,
+    /// The raw `AssemblyFlags`.
+    pub flags: u32,
+    /// The raw `AssemblyHashAlgorithm`.
+    pub hash_algorithm: u32,
+}
+
+impl AssemblyIdentity {
+    /// Reads the identity of the assembly at the given path.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, MetadataError> {
+        let image = fs::read(path).map_err(|err| MetadataError::Io(err.kind()))?;
+        Self::from_bytes(&image)
+    }
+
+    /// Reads the identity of the given assembly image.
+    pub fn from_bytes(image: &[u8]) -> Result<Self, MetadataError> {
+        MetadataReader::new(image)?.assembly_identity()
+    }
+
+    /// Returns the public key token, i.e. the last 8 bytes of the SHA-1 hash of the public key in reverse order.
+    #[must_use]
+    pub fn public_key_token(&self) -> PublicKeyToken {
+        match &self.public_key {
+            Some(public_key) => {
+                let hash = sha1::sha1(public_key);
+                let mut token = [0; 8];
+                for (byte, hash) in token.iter_mut().zip(hash.iter().rev()) {
+                    *byte = *hash;
+                }
+                PublicKeyToken::Token(token)
+            }
+            None => PublicKeyToken::Null,
+        }
+    }
+
+    /// Returns the full assembly name like `App, Version=1.0.0.0, Culture=neutral, PublicKeyToken=null`.
+    #[must_use]
+    pub fn assembly_name(&self) -> AssemblyName {
+        AssemblyName::new(self.name.clone())
+            .with_version(self.version)
+            .with_culture(self.culture.as_deref().unwrap_or("neutral"))
+            .with_public_key_token(self.public_key_token())
+    }
+
+    /// Qualifies the given type name with the full name of this assembly.
+    #[must_use]
+    pub fn qualify(&self, type_name: TypeName) -> AssemblyQualifiedName {
+        type_name.qualified(self.assembly_name())
+    }
+}
+
+impl fmt::Display for AssemblyIdentity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.assembly_name().fmt(f)
+    }
+}
+
+/// A static method marked with [`[UnmanagedCallersOnly]`](https://learn.microsoft.com/en-us/dotnet/api/system.runtime.interopservices.unmanagedcallersonlyattribute),
+/// which can be retrieved using [`DelegateSignature::UnmanagedCallersOnly`](crate::managed::DelegateSignature::UnmanagedCallersOnly).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct UnmanagedCallersOnlyMethod {
+    /// The assembly qualified name of the declaring type.
+    pub type_name: AssemblyQualifiedName,
+    /// The name of the method.
+    pub method_name: String,
+    /// The `EntryPoint` of the attribute, i.e. the name the method is exported as when compiled ahead-of-time.
+    pub entry_point: Option<String>,
+    /// The `CallConvs` of the attribute as (assembly qualified) type names.
+    pub calling_conventions: Vec<String>,
+    /// The signature of the method.
+    pub signature: MethodSignature,
+}
+
+/// A reader for the ECMA-335 metadata of a managed PE image.
+#[derive(Clone, Debug)]
+pub struct MetadataReader<'a> {
+    image: &'a [u8],
+    machine: u16,
+    cli_flags: u32,
+    runtime_version: &'a str,
+    strings: &'a [u8],
+    blobs: &'a [u8],
+    guids: &'a [u8],
+    tables: Tables<'a>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Section {
+    virtual_address: u32,
+    virtual_size: u32,
+    raw_data_pointer: u32,
+    raw_data_size: u32,
+}
+
+impl<'a> MetadataReader<'a> {
+    /// Parses the PE/COFF headers, the CLI header and the metadata streams of the given image.
+    pub fn new(image: &'a [u8]) -> Result<Self, MetadataError> {
+        if bytes::u16_at(image, 0).ok() != Some(u16::from_le_bytes(*b"MZ")) {
+            return Err(match bytes::u32_at(image, 0).map(u32::to_le_bytes) {
+                Ok([0x7F, b'E', b'L', b'F']) => MetadataError::NativeImage("ELF"),
+                Ok([0xCE | 0xCF, 0xFA, 0xED, 0xFE] | [0xFE, 0xED, 0xFA, 0xCE | 0xCF]) => {
+                    MetadataError::NativeImage("Mach-O")
+                }
+                _ => MetadataError::NotPortableExecutable,
+            });
+        }
+        let pe_offset = bytes::u32_at(image, 0x3C)? as usize;
+        if bytes::u32_at(image, pe_offset).ok() != Some(u32::from_le_bytes(*b"PE\0\0")) {
+            return Err(MetadataError::NotPortableExecutable);
+        }
+
+        let coff_header = pe_offset + 4;
+        let machine = bytes::u16_at(image, coff_header)?;
+        let section_count = bytes::u16_at(image, coff_header + 2)?;
+        let optional_header_size = bytes::u16_at(image, coff_header + 16)? as usize;
+
+        let optional_header = coff_header + 20;
+        let (directory_count_offset, directories_offset) =
+            match bytes::u16_at(image, optional_header)? {
+                0x10B => (92, 96),
+                0x20B => (108, 112),
+                _ => return Err(MetadataError::NotPortableExecutable),
+            };
+        let directory_count = bytes::u32_at(image, optional_header + directory_count_offset)?;
+        if directory_count as usize <= CLI_HEADER_DIRECTORY {
+            return Err(MetadataError::NotManaged);
+        }
+        let cli_directory = optional_header + directories_offset + CLI_HEADER_DIRECTORY * 8;
+        let cli_header_rva = bytes::u32_at(image, cli_directory)?;
+        let cli_header_size = bytes::u32_at(image, cli_directory + 4)?;
+        if cli_header_rva == 0 || cli_header_size == 0 {
+            return Err(MetadataError::NotManaged);
+        }
+
+        let sections_offset = optional_header + optional_header_size;
+        let sections = (0..section_count as usize)
+            .map(|i| {
+                let header = sections_offset + i * 40;
+                Ok(Section {
+                    virtual_size: bytes::u32_at(image, header + 8)?,
+                    virtual_address: bytes::u32_at(image, header + 12)?,
+                    raw_data_size: bytes::u32_at(image, header + 16)?,
+                    raw_data_pointer: bytes::u32_at(image, header + 20)?,
+                })
+            })
+            .collect::<Result<Vec<_>, MetadataError>>()?;
+
+        let cli_header = resolve_rva(image, &sections, cli_header_rva, 72)?;
+        let metadata_rva = bytes::u32_at(cli_header, 8)?;
+        let metadata_size = bytes::u32_at(cli_header, 12)?;
+        let cli_flags = bytes::u32_at(cli_header, 16)?;
+        let metadata = resolve_rva(image, &sections, metadata_rva, metadata_size)?;
+
+        Self::parse_metadata(image, machine, cli_flags, metadata)
+    }
+
+    fn parse_metadata(
+        image: &'a [u8],
+        machine: u16,
+        cli_flags: u32,
+        metadata: &'a [u8],
+    ) -> Result<Self, MetadataError> {
+        if bytes::u32_at(metadata, 0)? != METADATA_SIGNATURE {
+            return Err(MetadataError::InvalidMetadata("invalid metadata signature"));
+        }
+        let version_length = bytes::u32_at(metadata, 12)? as usize;
+        let runtime_version = bytes::slice(metadata, 16, version_length)?;
+        let runtime_version = runtime_version
+            .split(|b| *b == 0)
+            .next()
+            .and_then(|version| std::str::from_utf8(version).ok())
+            .ok_or(MetadataError::InvalidMetadata("invalid runtime version"))?;
+
+        let mut offset = 16 + version_length + 2;
+        let stream_count = bytes::u16_at(metadata, offset)?;
+        offset += 2;
+
+        let mut strings: &[u8] = &[];
+        let mut blobs: &[u8] = &[];
+        let mut guids: &[u8] = &[];
+        let mut tables = None;
+        for _ in 0..stream_count {
+            let stream_offset = bytes::u32_at(metadata, offset)? as usize;
+            let stream_size = bytes::u32_at(metadata, offset + 4)? as usize;
+            let name = bytes::null_terminated(metadata, offset + 8)?;
+            offset += 8 + (name.len() + 4) / 4 * 4;
+
+            let stream = bytes::slice(metadata, stream_offset, stream_size)?;
+            match name {
+                b"#Strings" => strings = stream,
+                b"#Blob" => blobs = stream,
+                b"#GUID" => guids = stream,
+                b"#~" | b"#-" => tables = Some(Tables::parse(stream)?),
+                _ => {}
+            }
+        }
+
+        Ok(Self {
+            image,
+            machine,
+            cli_flags,
+            runtime_version,
+            strings,
+            blobs,
+            guids,
+            tables: tables.ok_or(MetadataError::InvalidMetadata("missing #~ stream"))?,
+        })
+    }
+
+    /// Returns the version of the runtime the image was built against as stored in the metadata root, e.g. `v4.0.30319`.
+    #[must_use]
+    pub fn runtime_version(&self) -> &'a str {
+        self.runtime_version
+    }
+
+    /// Returns the identity of the assembly, or [`MetadataError::MissingAssemblyManifest`] if the image is a module without an assembly manifest.
+    pub fn assembly_identity(&self) -> Result<AssemblyIdentity, MetadataError> {
+        let table = TableId::Assembly;
+        if self.tables.rows(table) == 0 {
+            return Err(MetadataError::MissingAssemblyManifest);
+        }
+        let get = |column| self.tables.get(table, 1, column);
+        #[allow(clippy::cast_possible_truncation)]
+        let version_part = |column| get(column).map(|part| part as u16);
+
+        let public_key = self.blob(get(6)?)?;
+        let culture = self.string(get(8)?)?;
+        Ok(AssemblyIdentity {
+            name: self.string(get(7)?)?.to_owned(),
+            version: AssemblyVersion::new(
+                version_part(1)?,
+                version_part(2)?,
+                version_part(3)?,
+                version_part(4)?,
+            ),
+            culture: (!culture.is_empty()).then(|| culture.to_owned()),
+            public_key: (!public_key.is_empty()).then(|| public_key.to_vec()),
+            flags: get(5)?,
+            hash_algorithm: get(0)?,
+        })
+    }
+
+    /// Returns all static methods marked with `[UnmanagedCallersOnly]`, in declaration order.
+    pub fn unmanaged_callers_only_methods(
+        &self,
+    ) -> Result<Vec<UnmanagedCallersOnlyMethod>, MetadataError> {
+        let assembly = self.assembly_identity()?.assembly_name();
+        let tables = &self.tables;
+
+        let mut methods = Vec::new();
+        for row in 1..=tables.rows(TableId::CustomAttribute) {
+            let parent = tables.get(TableId::CustomAttribute, row, 0)?;
+            let Some((TableId::MethodDef, method)) =
+                CodedIndex::HasCustomAttribute.decode(parent)?
+            else {
+                continue;
+            };
+            if !self.attribute_type(row)?.is_some_and(|attribute_type| {
+                attribute_type.full_name()
+                    == "System.Runtime.InteropServices.UnmanagedCallersOnlyAttribute"
+            }) {
+                continue;
+            }
+            if tables.get(TableId::MethodDef, method, 2)? & METHOD_STATIC == 0 {
+                continue;
+            }
+
+            let signature = self.blob(tables.get(TableId::MethodDef, method, 4)?)?;
+            let parameter_names = self.parameter_names(method)?;
+            let Some(signature) =
+                SignatureDecoder::new(self, signature).static_method(&parameter_names)?
+            else {
+                continue;
+            };
+            let arguments = self.blob(tables.get(TableId::CustomAttribute, row, 2)?)?;
+            let (entry_point, calling_conventions) = parse_unmanaged_callers_only(arguments)?;
+            let declaring_type = self.type_name(TableId::TypeDef, self.declaring_type(method)?)?;
+
+            methods.push(UnmanagedCallersOnlyMethod {
+                type_name: declaring_type.qualified(assembly.clone()),
+                method_name: self
+                    .string(tables.get(TableId::MethodDef, method, 3)?)?
+                    .to_owned(),
+                entry_point,
+                calling_conventions,
+                signature,
+            });
+        }
+        Ok(methods)
+    }
+
+    /// Returns the full name of the type in the given `TypeDef` or `TypeRef` row.
+    pub(crate) fn type_name(&self, table: TableId, row: u32) -> Result<TypeName, MetadataError> {
+        self.type_name_with_depth(table, row, 0)
+    }
+
+    fn type_name_with_depth(
+        &self,
+        table: TableId,
+        row: u32,
+        depth: usize,
+    ) -> Result<TypeName, MetadataError> {
+        if depth > MAX_NESTING_DEPTH {
+            return Err(MetadataError::InvalidMetadata("type nesting too deep"));
+        }
+        let name = self.string(self.tables.get(table, row, 1)?)?;
+        let namespace = self.string(self.tables.get(table, row, 2)?)?;
+        let enclosing = match table {
+            TableId::TypeDef => self.enclosing_type(row)?.map(|row| (TableId::TypeDef, row)),
+            TableId::TypeRef => {
+                let scope = self.tables.get(TableId::TypeRef, row, 0)?;
+                CodedIndex::ResolutionScope
+                    .decode(scope)?
+                    .filter(|(table, _)| *table == TableId::TypeRef)
+            }
+            _ => {
+                return Err(MetadataError::InvalidMetadata(
+                    "unexpected type specification",
+                ))
+            }
+        };
+        match enclosing {
+            Some((table, row)) => Ok(self
+                .type_name_with_depth(table, row, depth + 1)?
+                .nested(name)),
+            None => Ok(TypeName {
+                namespace: (!namespace.is_empty()).then(|| namespace.to_owned()),
+                name: name.to_owned(),
+                nested: Vec::new(),
+                generic_arguments: Vec::new(),
+                modifiers: Vec::new(),
+            }),
+        }
+    }
+
+    /// Returns the type of the attribute in the given `CustomAttribute` row, or [`None`] if its constructor is not
+    /// declared on a `TypeDef` or `TypeRef`.
+    pub(crate) fn attribute_type(&self, row: u32) -> Result<Option<TypeName>, MetadataError> {
+        let constructor = self.tables.get(TableId::CustomAttribute, row, 1)?;
+        let Some((table, constructor)) = CodedIndex::CustomAttributeType.decode(constructor)?
+        else {
+            return Ok(None);
+        };
+        match table {
+            TableId::MemberRef => {
+                let parent = self.tables.get(TableId::MemberRef, constructor, 0)?;
+                match CodedIndex::MemberRefParent.decode(parent)? {
+                    Some((table @ (TableId::TypeRef | TableId::TypeDef), row)) => {
+                        self.type_name(table, row).map(Some)
+                    }
+                    _ => Ok(None),
+                }
+            }
+            _ => self
+                .type_name(TableId::TypeDef, self.declaring_type(constructor)?)
+                .map(Some),
+        }
+    }
+
+    fn enclosing_type(&self, type_def: u32) -> Result<Option<u32>, MetadataError> {
+        for row in 1..=self.tables.rows(TableId::NestedClass) {
+            if self.tables.get(TableId::NestedClass, row, 0)? == type_def {
+                return self.tables.get(TableId::NestedClass, row, 1).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the `TypeDef` row owning the given `MethodDef` row.
+    fn declaring_type(&self, method: u32) -> Result<u32, MetadataError> {
+        let type_count = self.tables.rows(TableId::TypeDef);
+        for row in 1..=type_count {
+            let start = self.tables.get(TableId::TypeDef, row, 5)?;
+            let end = if row == type_count {
+                self.tables.rows(TableId::MethodDef) + 1
+            } else {
+                self.tables.get(TableId::TypeDef, row + 1, 5)?
+            };
+            if (start..end).contains(&method) {
+                return Ok(row);
+            }
+        }
+        Err(MetadataError::InvalidMetadata(
+            "method without declaring type",
+        ))
+    }
+
+    /// Returns the names of the parameters of the given `MethodDef` row, indexed by position.
+    fn parameter_names(&self, method: u32) -> Result<Vec<Option<String>>, MetadataError> {
+        let start = self.tables.get(TableId::MethodDef, method, 5)?;
+        let end = if method == self.tables.rows(TableId::MethodDef) {
+            self.tables.rows(TableId::Param) + 1
+        } else {
+            self.tables.get(TableId::MethodDef, method + 1, 5)?
+        };
+        let mut names = Vec::new();
+        for row in start..end.min(self.tables.rows(TableId::Param) + 1) {
+            let sequence = self.tables.get(TableId::Param, row, 1)? as usize;
+            if sequence == 0 {
+                continue;
+            }
+            if names.len() < sequence {
+                names.resize(sequence, None);
+            }
+            names[sequence - 1] = Some(
+                self.string(self.tables.get(TableId::Param, row, 2)?)?
+                    .to_owned(),
+            );
+        }
+        Ok(names)
+    }
+
+    /// Returns the raw image.
+    pub(crate) fn image(&self) -> &'a [u8] {
+        self.image
+    }
+
+    /// Returns the target machine of the image.
+    pub(crate) fn machine(&self) -> u16 {
+        self.machine
+    }
+
+    /// Returns the flags of the CLI header.
+    pub(crate) fn cli_flags(&self) -> u32 {
+        self.cli_flags
+    }
+
+    /// Returns the metadata tables.
+    pub(crate) fn tables(&self) -> &Tables<'a> {
+        &self.tables
+    }
+
+    /// Reads a string from the `#Strings` heap.
+    pub(crate) fn string(&self, index: u32) -> Result<&'a str, MetadataError> {
+        let string = bytes::null_terminated(self.strings, index as usize)?;
+        std::str::from_utf8(string).map_err(|_| MetadataError::InvalidMetadata("invalid string"))
+    }
+
+    /// Reads a blob from the `#Blob` heap.
+    pub(crate) fn blob(&self, index: u32) -> Result<&'a [u8], MetadataError> {
+        let mut offset = index as usize;
+        let length = bytes::compressed_u32(self.blobs, &mut offset)?;
+        bytes::slice(self.blobs, offset, length as usize)
+    }
+
+    /// Reads a GUID from the `#GUID` heap.
+    pub(crate) fn guid(&self, index: u32) -> Result<Option<[u8; 16]>, MetadataError> {
+        if index == 0 {
+            return Ok(None);
+        }
+        let guid = bytes::slice(self.guids, (index as usize - 1) * 16, 16)?;
+        Ok(guid.try_into().ok())
+    }
+}
+
+/// Parses the named arguments of an `[UnmanagedCallersOnly]` attribute blob (ECMA-335 II.23.3)
+/// into the `EntryPoint` and `CallConvs`.
+fn parse_unmanaged_callers_only(
+    blob: &[u8],
+) -> Result<(Option<String>, Vec<String>), MetadataError> {
+    let mut entry_point = None;
+    let mut calling_conventions = Vec::new();
+    if bytes::u16_at(blob, 0)? != 1 {
+        return Err(MetadataError::InvalidMetadata(
+            "invalid custom attribute prolog",
+        ));
+    }
+    let named_count = bytes::u16_at(blob, 2)?;
+    let mut offset = 4;
+    for _ in 0..named_count {
+        // FIELD or PROPERTY
+        offset += 1;
+        let value_type = bytes::u8_at(blob, offset)?;
+        offset += 1;
+        let element_type = if value_type == 0x1D {
+            offset += 1;
+            Some(bytes::u8_at(blob, offset - 1)?)
+        } else {
+            None
+        };
+        let name = bytes::ser_string(blob, &mut offset)?;
+        match (value_type, element_type) {
+            // string
+            (0x0E, None) => {
+                let value = bytes::ser_string(blob, &mut offset)?;
+                if name.as_deref() == Some("EntryPoint") {
+                    entry_point = value;
+                }
+            }
+            // Type[]
+            (0x1D, Some(0x50)) => {
+                let count = bytes::u32_at(blob, offset)?;
+                offset += 4;
+                if count == u32::MAX {
+                    continue;
+                }
+                for _ in 0..count {
+                    let value = bytes::ser_string(blob, &mut offset)?;
+                    if name.as_deref() == Some("CallConvs") {
+                        calling_conventions.extend(value);
+                    }
+                }
+            }
+            // The attribute does not have any other members.
+            _ => break,
+        }
+    }
+    Ok((entry_point, calling_conventions))
+}
+
+fn resolve_rva<'a>(
+    image: &'a [u8],
+    sections: &[Section],
+    rva: u32,
+    size: u32,
+) -> Result<&'a [u8], MetadataError> {
+    let section = sections
+        .iter()
+        .find(|section| {
+            let section_size = section.virtual_size.max(section.raw_data_size);
+            rva >= section.virtual_address && rva - section.virtual_address < section_size
+        })
+        .ok_or(MetadataError::InvalidMetadata("RVA outside of any section"))?;
+    let offset = section.raw_data_pointer as usize + (rva - section.virtual_address) as usize;
+    bytes::slice(image, offset, size as usize)
+}
+
+/// Error returned when reading metadata from an image fails.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MetadataError {
+    /// The file could not be read.
+    Io(io::ErrorKind),
+    /// The image is not a PE/COFF image.
+    NotPortableExecutable,
+    /// The image is a native ELF or Mach-O image (like a `.so` or `.dylib`).
+    NativeImage(&'static str),
+    /// The image is a native PE/COFF image without a CLI header.
+    NotManaged,
+    /// The image ends before the headers or metadata it describes.
+    Truncated,
+    /// The image contains malformed metadata.
+    InvalidMetadata(&'static str),
+    /// The image is a module without an assembly manifest.
+    MissingAssemblyManifest,
+    /// The assembly is a reference assembly, which only contains metadata and cannot be executed.
+    ReferenceAssembly,
+    /// The assembly cannot run on the expected machine, see [`ValidationOptions::with_machine`].
+    MachineMismatch {
+        /// The raw machine of the image.
+        machine: u16,
+        /// The machine the assembly was expected to run on.
+        expected: u16,
+    },
+    /// The assembly targets a framework that is not supported by the runtime, see [`TargetFramework::is_supported_by`].
+    TargetFrameworkMismatch {
+        /// The target framework of the assembly.
+        target_framework: TargetFramework,
+        /// The version of the runtime.
+        runtime_version: AssemblyVersion,
+    },
+}
+
+impl fmt::Display for MetadataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(kind) => write!(f, "failed to read assembly: {kind}"),
+            Self::NotPortableExecutable => f.write_str("not a PE/COFF image"),
+            Self::NotManaged => f.write_str("not a managed assembly (missing CLI header)"),
+            Self::Truncated => f.write_str("image is truncated"),
+            Self::InvalidMetadata(reason) => write!(f, "invalid metadata: {reason}"),
+            Self::NativeImage(format) => {
+                write!(f, "not a managed assembly (native {format} image)")
+            }
+            Self::MissingAssemblyManifest => {
+                f.write_str("image is a module without an assembly manifest")
+            }
+            Self::ReferenceAssembly => {
+                f.write_str("assembly is a reference assembly and cannot be loaded for execution")
+            }
+            Self::MachineMismatch { machine, expected } => {
+                f.write_str("assembly cannot run on ")?;
+                match machine_name(*expected) {
+                    Some(name) => f.write_str(name)?,
+                    None => write!(f, "machine {expected:#06x}")?,
+                }
+                f.write_str(" (built for ")?;
+                match machine_name(*machine) {
+                    Some(name) => f.write_str(name)?,
+                    None => write!(f, "machine {machine:#06x}")?,
+                }
+                f.write_str(")")
+            }
+            Self::TargetFrameworkMismatch {
+                target_framework,
+                runtime_version,
+            } => write!(
+                f,
+                "assembly targets {target_framework}, which is not supported by runtime version {}",
+                ShortVersion(*runtime_version)
+            ),
+        }
+    }
+}
+
+impl error::Error for MetadataError {}
+
+/// Bounds-checked little-endian reads.
+mod bytes {
+    use super::MetadataError;
+
+    pub(crate) fn slice(data: &[u8], offset: usize, len: usize) -> Result<&[u8], MetadataError> {
+        offset
+            .checked_add(len)
+            .and_then(|end| data.get(offset..end))
+            .ok_or(MetadataError::Truncated)
+    }
+
+    fn array<const N: usize>(data: &[u8], offset: usize) -> Result<[u8; N], MetadataError> {
+        Ok(slice(data, offset, N)?
+            .try_into()
+            .expect("slice has length N"))
+    }
+
+    pub(crate) fn u8_at(data: &[u8], offset: usize) -> Result<u8, MetadataError> {
+        data.get(offset).copied().ok_or(MetadataError::Truncated)
+    }
+
+    pub(crate) fn u16_at(data: &[u8], offset: usize) -> Result<u16, MetadataError> {
+        array(data, offset).map(u16::from_le_bytes)
+    }
+
+    pub(crate) fn u32_at(data: &[u8], offset: usize) -> Result<u32, MetadataError> {
+        array(data, offset).map(u32::from_le_bytes)
+    }
+
+    pub(crate) fn u64_at(data: &[u8], offset: usize) -> Result<u64, MetadataError> {
+        array(data, offset).map(u64::from_le_bytes)
+    }
+
+    pub(crate) fn null_terminated(data: &[u8], offset: usize) -> Result<&[u8], MetadataError> {
+        let data = data.get(offset..).ok_or(MetadataError::Truncated)?;
+        let len = data
+            .iter()
+            .position(|b| *b == 0)
+            .ok_or(MetadataError::Truncated)?;
+        Ok(&data[..len])
+    }
+
+    /// Reads a `SerString` (ECMA-335 II.23.3) and advances `offset` past it.
+    pub(crate) fn ser_string(
+        data: &[u8],
+        offset: &mut usize,
+    ) -> Result<Option<String>, MetadataError> {
+        if u8_at(data, *offset)? == 0xFF {
+            *offset += 1;
+            return Ok(None);
+        }
+        let len = compressed_u32(data, offset)? as usize;
+        let value = slice(data, *offset, len)?;
+        *offset += len;
+        String::from_utf8(value.to_vec())
+            .map(Some)
+            .map_err(|_| MetadataError::InvalidMetadata("invalid serialized string"))
+    }
+
+    /// Reads a compressed unsigned integer (ECMA-335 II.23.2) and advances `offset` past it.
+    pub(crate) fn compressed_u32(data: &[u8], offset: &mut usize) -> Result<u32, MetadataError> {
+        let first = u8_at(data, *offset)?;
+        let (value, len) = match first {
+            0x00..=0x7F => (u32::from(first), 1),
+            0x80..=0xBF => {
+                let second = u8_at(data, *offset + 1)?;
+                ((u32::from(first & 0x3F) << 8) | u32::from(second), 2)
+            }
+            0xC0..=0xDF => {
+                let rest = slice(data, *offset + 1, 3)?;
+                (
+                    (u32::from(first & 0x1F) << 24)
+                        | (u32::from(rest[0]) << 16)
+                        | (u32::from(rest[1]) << 8)
+                        | u32::from(rest[2]),
+                    4,
+                )
+            }
+            _ => return Err(MetadataError::InvalidMetadata("invalid compressed integer")),
+        };
+        *offset += len;
+        Ok(value)
+    }
+}
@@ -0,0 +1,343 @@
+use std::fmt::Write as _;
+
+use super::{bytes, CodedIndex, MetadataError, MetadataReader};
+use crate::type_name::TypeName;
+
+/// Calling convention flag marking instance methods.
+const HAS_THIS: u8 = 0x20;
+/// Calling convention flag marking generic methods.
+const GENERIC: u8 = 0x10;
+/// Maximum nesting depth of types in a signature, to guard against stack overflows on malformed metadata.
+const MAX_TYPE_DEPTH: usize = 64;
+
+/// The signature of a managed method.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MethodSignature {
+    /// The return type.
+    pub return_type: ParameterType,
+    /// The parameters in order.
+    pub parameters: Vec<Parameter>,
+}
+
+/// A parameter of a managed method.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Parameter {
+    /// The name of the parameter, if it is stored in the metadata.
+    pub name: Option<String>,
+    /// The type of the parameter.
+    pub parameter_type: ParameterType,
+}
+
+/// The type of a parameter or return value of a managed method.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ParameterType {
+    /// `void`
+    Void,
+    /// `bool`
+    Bool,
+    /// `char`
+    Char,
+    /// `sbyte`
+    I8,
+    /// `byte`
+    U8,
+    /// `short`
+    I16,
+    /// `ushort`
+    U16,
+    /// `int`
+    I32,
+    /// `uint`
+    U32,
+    /// `long`
+    I64,
+    /// `ulong`
+    U64,
+    /// `float`
+    F32,
+    /// `double`
+    F64,
+    /// `nint`
+    IntPtr,
+    /// `nuint`
+    UIntPtr,
+    /// An unmanaged pointer to the given type.
+    Pointer(Box<ParameterType>),
+    /// An unmanaged function pointer (`delegate* unmanaged<...>`).
+    FunctionPointer,
+    /// A user-defined value type (a struct or an enum).
+    ValueType(TypeName),
+    /// A type that cannot be passed to or returned from unmanaged code, described by its C# spelling.
+    Other(String),
+}
+
+impl ParameterType {
+    /// Returns whether values of this type can be passed between managed and unmanaged code without marshalling,
+    /// which is required for methods marked with `[UnmanagedCallersOnly]`.
+    ///
+    /// User-defined value types are assumed to be blittable.
+    #[must_use]
+    pub fn is_blittable(&self) -> bool {
+        !matches!(self, Self::Bool | Self::Char | Self::Other(_))
+    }
+
+    /// Returns the Rust spelling of this type, or [`None`] if it is not blittable.
+    ///
+    /// User-defined value types are spelled by their simple name and have to be declared as `#[repr(C)]` types by the caller.
+    #[must_use]
+    pub fn rust_type(&self) -> Option<String> {
+        match self {
+            Self::Void => Some("()".to_owned()),
+            Self::Bool | Self::Char | Self::Other(_) => None,
+            _ => Some(self.pointee_rust_type()),
+        }
+    }
+
+    fn pointee_rust_type(&self) -> String {
+        match self {
+            Self::Void | Self::Other(_) => "::core::ffi::c_void",
+            Self::Bool => "bool",
+            Self::I8 => "i8",
+            Self::U8 => "u8",
+            Self::I16 => "i16",
+            Self::Char | Self::U16 => "u16",
+            Self::I32 => "i32",
+            Self::U32 => "u32",
+            Self::I64 => "i64",
+            Self::U64 => "u64",
+            Self::F32 => "f32",
+            Self::F64 => "f64",
+            Self::IntPtr => "isize",
+            Self::UIntPtr => "usize",
+            Self::Pointer(pointee) => return format!("*mut {}", pointee.pointee_rust_type()),
+            Self::FunctionPointer => "*const ::core::ffi::c_void",
+            Self::ValueType(type_name) => type_name.nested.last().unwrap_or(&type_name.name),
+        }
+        .to_owned()
+    }
+}
+
+impl MethodSignature {
+    /// Returns whether the return type and all parameter types are blittable, see [`ParameterType::is_blittable`].
+    #[must_use]
+    pub fn is_blittable(&self) -> bool {
+        self.return_type.is_blittable()
+            && self
+                .parameters
+                .iter()
+                .all(|parameter| parameter.parameter_type.is_blittable())
+    }
+
+    /// Returns the Rust function pointer type matching this signature, like `unsafe extern "system" fn(i32, i32) -> i32`,
+    /// or [`None`] if the signature is not blittable.
+    #[must_use]
+    pub fn rust_fn_type(&self) -> Option<String> {
+        let mut fn_type = "unsafe extern \"system\" fn(".to_owned();
+        for (i, parameter) in self.parameters.iter().enumerate() {
+            if i != 0 {
+                fn_type.push_str(", ");
+            }
+            fn_type.push_str(&parameter.parameter_type.rust_type()?);
+        }
+        fn_type.push(')');
+        if self.return_type != ParameterType::Void {
+            write!(fn_type, " -> {}", self.return_type.rust_type()?).unwrap();
+        }
+        Some(fn_type)
+    }
+}
+
+/// Decodes method signatures from the `#Blob` heap, see ECMA-335 II.23.2.
+pub(crate) struct SignatureDecoder<'r, 'a> {
+    reader: &'r MetadataReader<'a>,
+    blob: &'a [u8],
+    offset: usize,
+    depth: usize,
+}
+
+impl<'r, 'a> SignatureDecoder<'r, 'a> {
+    pub(crate) fn new(reader: &'r MetadataReader<'a>, blob: &'a [u8]) -> Self {
+        Self {
+            reader,
+            blob,
+            offset: 0,
+            depth: 0,
+        }
+    }
+
+    /// Decodes a `MethodDefSig`, returning [`None`] for instance and generic methods.
+    pub(crate) fn static_method(
+        mut self,
+        parameter_names: &[Option<String>],
+    ) -> Result<Option<MethodSignature>, MetadataError> {
+        let calling_convention = self.u8()?;
+        if calling_convention & (HAS_THIS | GENERIC) != 0 {
+            return Ok(None);
+        }
+        let (return_type, parameter_types) = self.method_types()?;
+        let parameters = parameter_types
+            .into_iter()
+            .enumerate()
+            .map(|(i, parameter_type)| Parameter {
+                name: parameter_names.get(i).cloned().flatten(),
+                parameter_type,
+            })
+            .collect();
+        Ok(Some(MethodSignature {
+            return_type,
+            parameters,
+        }))
+    }
+
+    fn method_types(&mut self) -> Result<(ParameterType, Vec<ParameterType>), MetadataError> {
+        let count = self.compressed()?;
+        let return_type = self.parameter_type()?;
+        let parameters = (0..count)
+            .map(|_| self.parameter_type())
+            .collect::<Result<_, _>>()?;
+        Ok((return_type, parameters))
+    }
+
+    fn u8(&mut self) -> Result<u8, MetadataError> {
+        let value = bytes::u8_at(self.blob, self.offset)?;
+        self.offset += 1;
+        Ok(value)
+    }
+
+    fn compressed(&mut self) -> Result<u32, MetadataError> {
+        bytes::compressed_u32(self.blob, &mut self.offset)
+    }
+
+    fn type_def_or_ref(&mut self) -> Result<TypeName, MetadataError> {
+        let index = self.compressed()?;
+        match CodedIndex::TypeDefOrRef.decode(index)? {
+            Some((table, row)) => self.reader.type_name(table, row),
+            None => Err(MetadataError::InvalidMetadata("null type reference")),
+        }
+    }
+
+    /// Decodes a `Param` or `RetType`, including custom modifiers and by-ref markers.
+    fn parameter_type(&mut self) -> Result<ParameterType, MetadataError> {
+        self.skip_custom_modifiers()?;
+        match bytes::u8_at(self.blob, self.offset)? {
+            // BYREF
+            0x10 => {
+                self.offset += 1;
+                let referenced = self.type_()?;
+                Ok(ParameterType::Other(format!(
+                    "ref {}",
+                    describe(&referenced)
+                )))
+            }
+            // TYPEDBYREF
+            0x16 => {
+                self.offset += 1;
+                Ok(ParameterType::Other("TypedReference".to_owned()))
+            }
+            _ => self.type_(),
+        }
+    }
+
+    fn skip_custom_modifiers(&mut self) -> Result<(), MetadataError> {
+        // CMOD_REQD and CMOD_OPT
+        while matches!(bytes::u8_at(self.blob, self.offset)?, 0x1F | 0x20) {
+            self.offset += 1;
+            self.compressed()?;
+        }
+        Ok(())
+    }
+
+    fn type_(&mut self) -> Result<ParameterType, MetadataError> {
+        if self.depth > MAX_TYPE_DEPTH {
+            return Err(MetadataError::InvalidMetadata("signature nesting too deep"));
+        }
+        self.depth += 1;
+        let result = self.type_inner();
+        self.depth -= 1;
+        result
+    }
+
+    fn type_inner(&mut self) -> Result<ParameterType, MetadataError> {
+        self.skip_custom_modifiers()?;
+        Ok(match self.u8()? {
+            0x01 => ParameterType::Void,
+            0x02 => ParameterType::Bool,
+            0x03 => ParameterType::Char,
+            0x04 => ParameterType::I8,
+            0x05 => ParameterType::U8,
+            0x06 => ParameterType::I16,
+            0x07 => ParameterType::U16,
+            0x08 => ParameterType::I32,
+            0x09 => ParameterType::U32,
+            0x0A => ParameterType::I64,
+            0x0B => ParameterType::U64,
+            0x0C => ParameterType::F32,
+            0x0D => ParameterType::F64,
+            0x0E => ParameterType::Other("string".to_owned()),
+            0x0F => ParameterType::Pointer(Box::new(self.type_()?)),
+            0x11 => ParameterType::ValueType(self.type_def_or_ref()?),
+            0x12 => ParameterType::Other(self.type_def_or_ref()?.full_name()),
+            0x13 => ParameterType::Other(format!("!{}", self.compressed()?)),
+            0x14 => {
+                let element = self.type_()?;
+                let rank = self.compressed()?;
+                let sizes = self.compressed()?;
+                for _ in 0..sizes {
+                    self.compressed()?;
+                }
+                let lower_bounds = self.compressed()?;
+                for _ in 0..lower_bounds {
+                    self.compressed()?;
+                }
+                let commas = ",".repeat(rank.saturating_sub(1) as usize);
+                ParameterType::Other(format!("{}[{commas}]", describe(&element)))
+            }
+            0x15 => {
+                self.u8()?;
+                let generic_type = self.type_def_or_ref()?;
+                let count = self.compressed()?;
+                for _ in 0..count {
+                    self.type_()?;
+                }
+                ParameterType::Other(generic_type.full_name())
+            }
+            0x18 => ParameterType::IntPtr,
+            0x19 => ParameterType::UIntPtr,
+            0x1B => {
+                self.u8()?;
+                self.method_types()?;
+                ParameterType::FunctionPointer
+            }
+            0x1C => ParameterType::Other("object".to_owned()),
+            0x1D => ParameterType::Other(format!("{}[]", describe(&self.type_()?))),
+            0x1E => ParameterType::Other(format!("!!{}", self.compressed()?)),
+            _ => return Err(MetadataError::InvalidMetadata("invalid element type")),
+        })
+    }
+}
+
+/// Returns the C# spelling of a type for use in [`ParameterType::Other`].
+fn describe(parameter_type: &ParameterType) -> String {
+    match parameter_type {
+        ParameterType::Void => "void".to_owned(),
+        ParameterType::Bool => "bool".to_owned(),
+        ParameterType::Char => "char".to_owned(),
+        ParameterType::I8 => "sbyte".to_owned(),
+        ParameterType::U8 => "byte".to_owned(),
+        ParameterType::I16 => "short".to_owned(),
+        ParameterType::U16 => "ushort".to_owned(),
+        ParameterType::I32 => "int".to_owned(),
+        ParameterType::U32 => "uint".to_owned(),
+        ParameterType::I64 => "long".to_owned(),
+        ParameterType::U64 => "ulong".to_owned(),
+        ParameterType::F32 => "float".to_owned(),
+        ParameterType::F64 => "double".to_owned(),
+        ParameterType::IntPtr => "nint".to_owned(),
+        ParameterType::UIntPtr => "nuint".to_owned(),
+        ParameterType::Pointer(pointee) => format!("{}*", describe(pointee)),
+        ParameterType::FunctionPointer => "delegate* unmanaged".to_owned(),
+        ParameterType::ValueType(type_name) => type_name.full_name(),
+        ParameterType::Other(name) => name.clone(),
+    }
+}
@@ -0,0 +1,349 @@
+use super::{bytes, MetadataError};
+
+/// The metadata tables, see ECMA-335 II.22.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum TableId {
+    Module = 0x00,
+    TypeRef = 0x01,
+    TypeDef = 0x02,
+    FieldPtr = 0x03,
+    Field = 0x04,
+    MethodPtr = 0x05,
+    MethodDef = 0x06,
+    ParamPtr = 0x07,
+    Param = 0x08,
+    InterfaceImpl = 0x09,
+    MemberRef = 0x0A,
+    Constant = 0x0B,
+    CustomAttribute = 0x0C,
+    FieldMarshal = 0x0D,
+    DeclSecurity = 0x0E,
+    ClassLayout = 0x0F,
+    FieldLayout = 0x10,
+    StandAloneSig = 0x11,
+    EventMap = 0x12,
+    EventPtr = 0x13,
+    Event = 0x14,
+    PropertyMap = 0x15,
+    PropertyPtr = 0x16,
+    Property = 0x17,
+    MethodSemantics = 0x18,
+    MethodImpl = 0x19,
+    ModuleRef = 0x1A,
+    TypeSpec = 0x1B,
+    ImplMap = 0x1C,
+    FieldRva = 0x1D,
+    EncLog = 0x1E,
+    EncMap = 0x1F,
+    Assembly = 0x20,
+    AssemblyProcessor = 0x21,
+    AssemblyOs = 0x22,
+    AssemblyRef = 0x23,
+    AssemblyRefProcessor = 0x24,
+    AssemblyRefOs = 0x25,
+    File = 0x26,
+    ExportedType = 0x27,
+    ManifestResource = 0x28,
+    NestedClass = 0x29,
+    GenericParam = 0x2A,
+    MethodSpec = 0x2B,
+    GenericParamConstraint = 0x2C,
+}
+
+const TABLE_COUNT: usize = TableId::GenericParamConstraint as usize + 1;
+
+/// The coded indices, see ECMA-335 II.24.2.6.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum CodedIndex {
+    TypeDefOrRef,
+    HasConstant,
+    HasCustomAttribute,
+    HasFieldMarshal,
+    HasDeclSecurity,
+    MemberRefParent,
+    HasSemantics,
+    MethodDefOrRef,
+    MemberForwarded,
+    Implementation,
+    CustomAttributeType,
+    ResolutionScope,
+    TypeOrMethodDef,
+}
+
+impl CodedIndex {
+    /// Returns the tables that can be referenced, in tag order. [`None`] marks unused tags.
+    const fn tables(self) -> &'static [Option<TableId>] {
+        use TableId as T;
+        match self {
+            Self::TypeDefOrRef => &[Some(T::TypeDef), Some(T::TypeRef), Some(T::TypeSpec)],
+            Self::HasConstant => &[Some(T::Field), Some(T::Param), Some(T::Property)],
+            Self::HasCustomAttribute => &[
+                Some(T::MethodDef),
+                Some(T::Field),
+                Some(T::TypeRef),
+                Some(T::TypeDef),
+                Some(T::Param),
+                Some(T::InterfaceImpl),
+                Some(T::MemberRef),
+                Some(T::Module),
+                Some(T::DeclSecurity),
+                Some(T::Property),
+                Some(T::Event),
+                Some(T::StandAloneSig),
+                Some(T::ModuleRef),
+                Some(T::TypeSpec),
+                Some(T::Assembly),
+                Some(T::AssemblyRef),
+                Some(T::File),
+                Some(T::ExportedType),
+                Some(T::ManifestResource),
+                Some(T::GenericParam),
+                Some(T::GenericParamConstraint),
+                Some(T::MethodSpec),
+            ],
+            Self::HasFieldMarshal => &[Some(T::Field), Some(T::Param)],
+            Self::HasDeclSecurity => &[Some(T::TypeDef), Some(T::MethodDef), Some(T::Assembly)],
+            Self::MemberRefParent => &[
+                Some(T::TypeDef),
+                Some(T::TypeRef),
+                Some(T::ModuleRef),
+                Some(T::MethodDef),
+                Some(T::TypeSpec),
+            ],
+            Self::HasSemantics => &[Some(T::Event), Some(T::Property)],
+            Self::MethodDefOrRef => &[Some(T::MethodDef), Some(T::MemberRef)],
+            Self::MemberForwarded => &[Some(T::Field), Some(T::MethodDef)],
+            Self::Implementation => &[Some(T::File), Some(T::AssemblyRef), Some(T::ExportedType)],
+            Self::CustomAttributeType => {
+                &[None, None, Some(T::MethodDef), Some(T::MemberRef), None]
+            }
+            Self::ResolutionScope => &[
+                Some(T::Module),
+                Some(T::ModuleRef),
+                Some(T::AssemblyRef),
+                Some(T::TypeRef),
+            ],
+            Self::TypeOrMethodDef => &[Some(T::TypeDef), Some(T::MethodDef)],
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    const fn tag_bits(self) -> u32 {
+        let count = self.tables().len() as u32;
+        u32::BITS - (count - 1).leading_zeros()
+    }
+
+    /// Splits a raw coded index into the referenced table and the 1-based row, or [`None`] for null indices.
+    pub(crate) fn decode(self, value: u32) -> Result<Option<(TableId, u32)>, MetadataError> {
+        let tag_bits = self.tag_bits();
+        let row = value >> tag_bits;
+        let tag = (value & ((1 << tag_bits) - 1)) as usize;
+        match self.tables().get(tag) {
+            Some(Some(table)) if row != 0 => Ok(Some((*table, row))),
+            Some(Some(_)) => Ok(None),
+            _ => Err(MetadataError::InvalidMetadata("invalid coded index tag")),
+        }
+    }
+}
+
+/// The type of a column in a metadata table.
+#[derive(Clone, Copy, Debug)]
+enum Column {
+    U8,
+    U16,
+    U32,
+    String,
+    Guid,
+    Blob,
+    Table(TableId),
+    Coded(CodedIndex),
+}
+
+const fn schema(table: usize) -> &'static [Column] {
+    use CodedIndex as C;
+    use Column::{Blob, Coded, Guid, String, Table, U16, U32, U8};
+    use TableId as T;
+    match table {
+        0x00 => &[U16, String, Guid, Guid, Guid],
+        0x01 => &[Coded(C::ResolutionScope), String, String],
+        0x02 => &[
+            U32,
+            String,
+            String,
+            Coded(C::TypeDefOrRef),
+            Table(T::Field),
+            Table(T::MethodDef),
+        ],
+        0x03 => &[Table(T::Field)],
+        0x04 | 0x17 => &[U16, String, Blob],
+        0x05 => &[Table(T::MethodDef)],
+        0x06 => &[U32, U16, U16, String, Blob, Table(T::Param)],
+        0x07 => &[Table(T::Param)],
+        0x08 => &[U16, U16, String],
+        0x09 => &[Table(T::TypeDef), Coded(C::TypeDefOrRef)],
+        0x0A => &[Coded(C::MemberRefParent), String, Blob],
+        0x0B => &[U8, U8, Coded(C::HasConstant), Blob],
+        0x0C => &[
+            Coded(C::HasCustomAttribute),
+            Coded(C::CustomAttributeType),
+            Blob,
+        ],
+        0x0D => &[Coded(C::HasFieldMarshal), Blob],
+        0x0E => &[U16, Coded(C::HasDeclSecurity), Blob],
+        0x0F => &[U16, U32, Table(T::TypeDef)],
+        0x10 | 0x1D => &[U32, Table(T::Field)],
+        0x11 | 0x1B => &[Blob],
+        0x12 => &[Table(T::TypeDef), Table(T::Event)],
+        0x13 => &[Table(T::Event)],
+        0x14 => &[U16, String, Coded(C::TypeDefOrRef)],
+        0x15 => &[Table(T::TypeDef), Table(T::Property)],
+        0x16 => &[Table(T::Property)],
+        0x18 => &[U16, Table(T::MethodDef), Coded(C::HasSemantics)],
+        0x19 => &[
+            Table(T::TypeDef),
+            Coded(C::MethodDefOrRef),
+            Coded(C::MethodDefOrRef),
+        ],
+        0x1A => &[String],
+        0x1C => &[U16, Coded(C::MemberForwarded), String, Table(T::ModuleRef)],
+        0x1E => &[U32, U32],
+        0x1F | 0x21 => &[U32],
+        0x20 => &[U32, U16, U16, U16, U16, U32, Blob, String, String],
+        0x22 => &[U32, U32, U32],
+        0x23 => &[U16, U16, U16, U16, U32, Blob, String, String, Blob],
+        0x24 => &[U32, Table(T::AssemblyRef)],
+        0x25 => &[U32, U32, U32, Table(T::AssemblyRef)],
+        0x26 => &[U32, String, Blob],
+        0x27 => &[U32, U32, String, String, Coded(C::Implementation)],
+        0x28 => &[U32, U32, String, Coded(C::Implementation)],
+        0x29 => &[Table(T::TypeDef), Table(T::TypeDef)],
+        0x2A => &[U16, U16, Coded(C::TypeOrMethodDef), String],
+        0x2B => &[Coded(C::MethodDefOrRef), Blob],
+        0x2C => &[Table(T::GenericParam), Coded(C::TypeDefOrRef)],
+        _ => &[],
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct Layout {
+    rows: u32,
+    offset: usize,
+    row_size: usize,
+}
+
+/// The tables of a `#~` stream, see ECMA-335 II.24.2.6.
+#[derive(Clone, Debug)]
+pub(crate) struct Tables<'a> {
+    data: &'a [u8],
+    layouts: [Layout; TABLE_COUNT],
+    string_index_size: usize,
+    guid_index_size: usize,
+    blob_index_size: usize,
+}
+
+impl<'a> Tables<'a> {
+    pub(crate) fn parse(stream: &'a [u8]) -> Result<Self, MetadataError> {
+        let heap_sizes = bytes::u8_at(stream, 6)?;
+        let valid = bytes::u64_at(stream, 8)?;
+        let mut offset = 24;
+
+        let mut layouts = [Layout::default(); TABLE_COUNT];
+        for i in (0..64).filter(|i| valid & (1 << i) != 0) {
+            let rows = bytes::u32_at(stream, offset)?;
+            offset += 4;
+            match layouts.get_mut(i) {
+                Some(table) => table.rows = rows,
+                None if rows == 0 => {}
+                None => return Err(MetadataError::InvalidMetadata("unknown metadata table")),
+            }
+        }
+        // Uncompressed (`#-`) streams may contain an extra 4 bytes of data after the row counts.
+        if heap_sizes & 0x40 != 0 {
+            offset += 4;
+        }
+
+        let mut this = Self {
+            data: stream,
+            layouts,
+            string_index_size: if heap_sizes & 0x01 != 0 { 4 } else { 2 },
+            guid_index_size: if heap_sizes & 0x02 != 0 { 4 } else { 2 },
+            blob_index_size: if heap_sizes & 0x04 != 0 { 4 } else { 2 },
+        };
+        for i in 0..TABLE_COUNT {
+            let row_size = schema(i)
+                .iter()
+                .map(|column| this.column_size(*column))
+                .sum();
+            let table = &mut this.layouts[i];
+            table.offset = offset;
+            table.row_size = row_size;
+            offset = (table.rows as usize)
+                .checked_mul(row_size)
+                .and_then(|size| offset.checked_add(size))
+                .ok_or(MetadataError::InvalidMetadata("metadata table too large"))?;
+        }
+        if offset > stream.len() {
+            return Err(MetadataError::Truncated);
+        }
+        Ok(this)
+    }
+
+    fn column_size(&self, column: Column) -> usize {
+        match column {
+            Column::U8 => 1,
+            Column::U16 => 2,
+            Column::U32 => 4,
+            Column::String => self.string_index_size,
+            Column::Guid => self.guid_index_size,
+            Column::Blob => self.blob_index_size,
+            Column::Table(table) => self.index_size(self.layouts[table as usize].rows, 0),
+            Column::Coded(coded) => {
+                let max_rows = coded
+                    .tables()
+                    .iter()
+                    .flatten()
+                    .map(|table| self.layouts[*table as usize].rows)
+                    .max()
+                    .unwrap_or(0);
+                self.index_size(max_rows, coded.tag_bits())
+            }
+        }
+    }
+
+    #[allow(clippy::unused_self)]
+    const fn index_size(&self, rows: u32, tag_bits: u32) -> usize {
+        if (rows as u64) < (1 << (16 - tag_bits)) {
+            2
+        } else {
+            4
+        }
+    }
+
+    /// Returns the number of rows in the given table.
+    pub(crate) fn rows(&self, table: TableId) -> u32 {
+        self.layouts[table as usize].rows
+    }
+
+    /// Reads a column of a row (1-based) as an unsigned integer or raw index.
+    pub(crate) fn get(
+        &self,
+        table: TableId,
+        row: u32,
+        column: usize,
+    ) -> Result<u32, MetadataError> {
+        let layout = &self.layouts[table as usize];
+        if row == 0 || row > layout.rows {
+            return Err(MetadataError::InvalidMetadata("row index out of range"));
+        }
+        let columns = schema(table as usize);
+        let mut offset = layout.offset + (row as usize - 1) * layout.row_size;
+        for column in &columns[..column] {
+            offset += self.column_size(*column);
+        }
+        match self.column_size(columns[column]) {
+            1 => bytes::u8_at(self.data, offset).map(u32::from),
+            2 => bytes::u16_at(self.data, offset).map(u32::from),
+            _ => bytes::u32_at(self.data, offset),
+        }
+    }
+}
@@ -0,0 +1,317 @@
+use std::{fmt, fs, path::Path};
+
+use super::{bytes, AssemblyIdentity, CodedIndex, MetadataError, MetadataReader, TableId};
+use crate::type_name::AssemblyVersion;
+
+/// `COMIMAGE_FLAGS_ILONLY`
+const CLI_FLAGS_IL_ONLY: u32 = 0x0000_0001;
+/// `COMIMAGE_FLAGS_32BITREQUIRED`
+const CLI_FLAGS_32BIT_REQUIRED: u32 = 0x0000_0002;
+
+/// `IMAGE_FILE_MACHINE_I386`
+const MACHINE_I386: u16 = 0x014C;
+/// `IMAGE_FILE_MACHINE_AMD64`
+const MACHINE_AMD64: u16 = 0x8664;
+/// `IMAGE_FILE_MACHINE_ARMNT`
+const MACHINE_ARMNT: u16 = 0x01C4;
+/// `IMAGE_FILE_MACHINE_ARM64`
+const MACHINE_ARM64: u16 = 0xAA64;
+/// `IMAGE_FILE_MACHINE_LOONGARCH64`
+const MACHINE_LOONGARCH64: u16 = 0x6264;
+/// `IMAGE_FILE_MACHINE_RISCV64`
+const MACHINE_RISCV64: u16 = 0x5064;
+
+/// Values the machine of ReadyToRun images is xor-ed with to encode the target operating system
+/// (Apple, FreeBSD, Linux, NetBSD and SunOS).
+const READY_TO_RUN_OS_OVERRIDES: [u16; 5] = [0x4644, 0xADC4, 0x7B79, 0x1993, 0x1992];
+
+/// The machine of the current process.
+const HOST_MACHINE: Option<u16> = if cfg!(target_arch = "x86") {
+    Some(MACHINE_I386)
+} else if cfg!(target_arch = "x86_64") {
+    Some(MACHINE_AMD64)
+} else if cfg!(target_arch = "arm") {
+    Some(MACHINE_ARMNT)
+} else if cfg!(target_arch = "aarch64") {
+    Some(MACHINE_ARM64)
+} else if cfg!(target_arch = "loongarch64") {
+    Some(MACHINE_LOONGARCH64)
+} else if cfg!(target_arch = "riscv64") {
+    Some(MACHINE_RISCV64)
+} else {
+    None
+};
+
+/// The framework an assembly was compiled against, as stored in its `[TargetFramework]` attribute,
+/// e.g. `.NETCoreApp,Version=v8.0`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TargetFramework {
+    /// The framework identifier like `.NETCoreApp`, `.NETStandard` or `.NETFramework`.
+    pub identifier: String,
+    /// The version of the framework.
+    pub version: AssemblyVersion,
+    /// The profile of the framework, if any.
+    pub profile: Option<String>,
+}
+
+impl TargetFramework {
+    /// Parses a framework name like `.NETCoreApp,Version=v8.0`.
+    #[must_use]
+    pub fn parse(name: &str) -> Option<Self> {
+        let mut parts = name.split(',');
+        let identifier = parts.next()?.trim();
+        let mut version = None;
+        let mut profile = None;
+        for part in parts {
+            let (key, value) = part.split_once('=')?;
+            let value = value.trim();
+            match key.trim() {
+                "Version" => {
+                    let value = value.strip_prefix(['v', 'V']).unwrap_or(value);
+                    version = Some(value.parse().ok()?);
+                }
+                "Profile" => profile = Some(value.to_owned()),
+                _ => return None,
+            }
+        }
+        if identifier.is_empty() {
+            return None;
+        }
+        Some(Self {
+            identifier: identifier.to_owned(),
+            version: version?,
+            profile,
+        })
+    }
+
+    /// Returns whether an assembly targeting this framework can be loaded by a .NET (Core) runtime of the given version.
+    ///
+    /// Only `.NETCoreApp` and `.NETStandard` targets are checked, other frameworks are assumed to be compatible.
+    #[must_use]
+    pub fn is_supported_by(&self, runtime_version: AssemblyVersion) -> bool {
+        let runtime = (runtime_version.major, runtime_version.minor);
+        match self.identifier.as_str() {
+            ".NETCoreApp" => (self.version.major, self.version.minor) <= runtime,
+            // .NET Standard 2.1 is the last version and is implemented by .NET Core 3.0 and later.
+            ".NETStandard" => match (self.version.major, self.version.minor) {
+                (2, 1) => runtime >= (3, 0),
+                version => version <= (2, 0),
+            },
+            _ => true,
+        }
+    }
+}
+
+impl fmt::Display for TargetFramework {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{},Version=v{}",
+            self.identifier,
+            ShortVersion(self.version)
+        )?;
+        if let Some(profile) = &self.profile {
+            write!(f, ",Profile={profile}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats a version without trailing zero components (but with at least major and minor).
+pub(super) struct ShortVersion(pub(super) AssemblyVersion);
+
+impl fmt::Display for ShortVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let AssemblyVersion {
+            major,
+            minor,
+            build,
+            revision,
+        } = self.0;
+        write!(f, "{major}.{minor}")?;
+        if build != 0 || revision != 0 {
+            write!(f, ".{build}")?;
+        }
+        if revision != 0 {
+            write!(f, ".{revision}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Options for checking that an assembly can be loaded by a runtime before handing it over,
+/// see [`MetadataReader::validate`].
+///
+/// By default, reference assemblies and assemblies that cannot run on the architecture of the current process are rejected.
+/// The target framework is only checked if a runtime version is specified.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidationOptions {
+    machine: Option<u16>,
+    runtime_version: Option<AssemblyVersion>,
+    allow_reference_assemblies: bool,
+}
+
+impl Default for ValidationOptions {
+    fn default() -> Self {
+        Self {
+            machine: HOST_MACHINE,
+            runtime_version: None,
+            allow_reference_assemblies: false,
+        }
+    }
+}
+
+impl ValidationOptions {
+    /// Creates the default options.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the machine (one of the `IMAGE_FILE_MACHINE_*` constants) the assembly has to be able to run on,
+    /// which defaults to the architecture of the current process.
+    #[must_use]
+    pub fn with_machine(mut self, machine: u16) -> Self {
+        self.machine = Some(machine);
+        self
+    }
+
+    /// Sets the version of the runtime the assembly is going to be loaded into, like `8.0`, enabling the target framework check.
+    #[must_use]
+    pub fn with_runtime_version(mut self, runtime_version: AssemblyVersion) -> Self {
+        self.runtime_version = Some(runtime_version);
+        self
+    }
+
+    /// Sets whether reference assemblies (which only contain metadata) are accepted.
+    #[must_use]
+    pub fn allow_reference_assemblies(mut self, allow: bool) -> Self {
+        self.allow_reference_assemblies = allow;
+        self
+    }
+
+    /// Validates the given assembly image, see [`MetadataReader::validate`].
+    pub fn validate(&self, image: &[u8]) -> Result<AssemblyIdentity, MetadataError> {
+        MetadataReader::new(image)?.validate(self)
+    }
+
+    /// Validates the assembly at the given path, see [`MetadataReader::validate`].
+    pub fn validate_file(&self, path: impl AsRef<Path>) -> Result<AssemblyIdentity, MetadataError> {
+        let image = fs::read(path).map_err(|err| MetadataError::Io(err.kind()))?;
+        self.validate(&image)
+    }
+}
+
+impl MetadataReader<'_> {
+    /// Checks that the assembly can be loaded with the given options and returns its identity.
+    ///
+    /// This detects
+    /// - modules without an assembly manifest ([`MetadataError::MissingAssemblyManifest`]),
+    /// - reference assemblies ([`MetadataError::ReferenceAssembly`]),
+    /// - mixed-mode assemblies built for another architecture and 32-bit only assemblies in 64-bit processes ([`MetadataError::MachineMismatch`]) and
+    /// - assemblies targeting a newer framework than the runtime ([`MetadataError::TargetFrameworkMismatch`]).
+    ///
+    /// Native images and images without a CLI header are already rejected by [`MetadataReader::new`].
+    pub fn validate(&self, options: &ValidationOptions) -> Result<AssemblyIdentity, MetadataError> {
+        let identity = self.assembly_identity()?;
+        if !options.allow_reference_assemblies && self.is_reference_assembly()? {
+            return Err(MetadataError::ReferenceAssembly);
+        }
+        if let Some(expected) = options.machine {
+            if !self.runs_on(expected) {
+                return Err(MetadataError::MachineMismatch {
+                    machine: self.machine(),
+                    expected,
+                });
+            }
+        }
+        if let Some(runtime_version) = options.runtime_version {
+            if let Some(target_framework) = self.target_framework()? {
+                if !target_framework.is_supported_by(runtime_version) {
+                    return Err(MetadataError::TargetFrameworkMismatch {
+                        target_framework,
+                        runtime_version,
+                    });
+                }
+            }
+        }
+        Ok(identity)
+    }
+
+    /// Returns whether the assembly is a reference assembly, i.e. marked with `[ReferenceAssembly]`.
+    pub fn is_reference_assembly(&self) -> Result<bool, MetadataError> {
+        Ok(self
+            .assembly_attribute("System.Runtime.CompilerServices.ReferenceAssemblyAttribute")?
+            .is_some())
+    }
+
+    /// Returns the framework the assembly was compiled against as stored in its `[TargetFramework]` attribute.
+    pub fn target_framework(&self) -> Result<Option<TargetFramework>, MetadataError> {
+        let Some(blob) =
+            self.assembly_attribute("System.Runtime.Versioning.TargetFrameworkAttribute")?
+        else {
+            return Ok(None);
+        };
+        if bytes::u16_at(blob, 0)? != 1 {
+            return Err(MetadataError::InvalidMetadata(
+                "invalid custom attribute prolog",
+            ));
+        }
+        let name = bytes::ser_string(blob, &mut 2)?;
+        Ok(name.as_deref().and_then(TargetFramework::parse))
+    }
+
+    /// Returns the value blob of the first attribute of the given type applied to the assembly.
+    fn assembly_attribute(&self, attribute_type: &str) -> Result<Option<&[u8]>, MetadataError> {
+        let tables = self.tables();
+        for row in 1..=tables.rows(TableId::CustomAttribute) {
+            let parent = tables.get(TableId::CustomAttribute, row, 0)?;
+            if CodedIndex::HasCustomAttribute.decode(parent)? != Some((TableId::Assembly, 1)) {
+                continue;
+            }
+            if self
+                .attribute_type(row)?
+                .is_some_and(|name| name.full_name() == attribute_type)
+            {
+                return self
+                    .blob(tables.get(TableId::CustomAttribute, row, 2)?)
+                    .map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns whether the image can run on the given machine.
+    fn runs_on(&self, expected: u16) -> bool {
+        if self.cli_flags() & CLI_FLAGS_IL_ONLY != 0 {
+            // IL-only images run everywhere (ReadyToRun code for other platforms is ignored),
+            // unless they are marked as requiring a 32-bit process.
+            return self.cli_flags() & CLI_FLAGS_32BIT_REQUIRED == 0 || !is_64_bit(expected);
+        }
+        let machine = self.machine();
+        machine == expected
+            || READY_TO_RUN_OS_OVERRIDES
+                .iter()
+                .any(|os| machine ^ os == expected)
+    }
+}
+
+fn is_64_bit(machine: u16) -> bool {
+    matches!(
+        machine,
+        MACHINE_AMD64 | MACHINE_ARM64 | MACHINE_LOONGARCH64 | MACHINE_RISCV64
+    )
+}
+
+/// Returns a readable name of the given `IMAGE_FILE_MACHINE_*` value.
+pub(super) fn machine_name(machine: u16) -> Option<&'static str> {
+    match machine {
+        MACHINE_I386 => Some("x86"),
+        MACHINE_AMD64 => Some("x64"),
+        MACHINE_ARMNT => Some("arm"),
+        MACHINE_ARM64 => Some("arm64"),
+        MACHINE_LOONGARCH64 => Some("loongarch64"),
+        MACHINE_RISCV64 => Some("riscv64"),
+        _ => None,
+    }
+}
@@ -0,0 +1,146 @@
+use std::{fmt, mem, path::PathBuf, ptr};
+
+use crate::{
+    error::ContainsNulError,
+    hostfxr_initialize_parameters,
+    pdcstr::{PdCStr, PdCString},
+};
+
+/// Owned [`hostfxr_initialize_parameters`], which keep the strings their pointers refer to alive.
+///
+/// The raw parameters can only be borrowed (see [`as_raw`](Self::as_raw)), so they cannot outlive the strings.
+///
+/// # Example
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use hostfxr_sys::parameters::InitializeParameters;
+///
+/// let parameters = InitializeParameters::builder()
+///     .with_host_path("/app/App")
+///     .with_dotnet_root("/usr/share/dotnet")
+///     .build()?;
+/// let raw = parameters.as_raw();
+/// # Ok(())
+/// # }
+/// ```
+pub struct InitializeParameters {
+    host_path: Option<PdCString>,
+    dotnet_root: Option<PdCString>,
+    raw: hostfxr_initialize_parameters,
+}
+
+impl InitializeParameters {
+    /// Returns a builder for parameters.
+    #[must_use]
+    pub fn builder() -> InitializeParametersBuilder {
+        InitializeParametersBuilder::new()
+    }
+
+    /// Creates parameters with both the host path and the .NET root set.
+    pub fn new(
+        host_path: impl Into<PathBuf>,
+        dotnet_root: impl Into<PathBuf>,
+    ) -> Result<Self, ContainsNulError> {
+        Self::builder()
+            .with_host_path(host_path)
+            .with_dotnet_root(dotnet_root)
+            .build()
+    }
+
+    fn from_strings(host_path: Option<PdCString>, dotnet_root: Option<PdCString>) -> Self {
+        // the strings are heap allocated, so the pointers stay valid when `Self` is moved.
+        let raw = hostfxr_initialize_parameters {
+            size: mem::size_of::<hostfxr_initialize_parameters>(),
+            host_path: host_path.as_deref().map_or(ptr::null(), PdCStr::as_ptr),
+            dotnet_root: dotnet_root.as_deref().map_or(ptr::null(), PdCStr::as_ptr),
+        };
+        Self {
+            host_path,
+            dotnet_root,
+            raw,
+        }
+    }
+
+    /// Returns the raw parameters, which are valid for as long as `self` is borrowed.
+    #[must_use]
+    pub const fn as_raw(&self) -> &hostfxr_initialize_parameters {
+        &self.raw
+    }
+
+    /// Returns the path of the host executable, if set.
+    #[must_use]
+    pub fn host_path(&self) -> Option<PathBuf> {
+        self.host_path.as_deref().map(PathBuf::from)
+    }
+
+    /// Returns the root directory of the .NET installation, if set.
+    #[must_use]
+    pub fn dotnet_root(&self) -> Option<PathBuf> {
+        self.dotnet_root.as_deref().map(PathBuf::from)
+    }
+}
+
+impl AsRef<hostfxr_initialize_parameters> for InitializeParameters {
+    fn as_ref(&self) -> &hostfxr_initialize_parameters {
+        self.as_raw()
+    }
+}
+
+impl Clone for InitializeParameters {
+    fn clone(&self) -> Self {
+        Self::from_strings(self.host_path.clone(), self.dotnet_root.clone())
+    }
+}
+
+impl fmt::Debug for InitializeParameters {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InitializeParameters")
+            .field("host_path", &self.host_path)
+            .field("dotnet_root", &self.dotnet_root)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Builder for [`InitializeParameters`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InitializeParametersBuilder {
+    host_path: Option<PathBuf>,
+    dotnet_root: Option<PathBuf>,
+}
+
+impl InitializeParametersBuilder {
+    /// Creates a builder with neither path set.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the path of the native host (typically the executable), see [`hostfxr_initialize_parameters::host_path`].
+    #[must_use]
+    pub fn with_host_path(mut self, host_path: impl Into<PathBuf>) -> Self {
+        self.host_path = Some(host_path.into());
+        self
+    }
+
+    /// Sets the root directory of the .NET installation to use, see [`hostfxr_initialize_parameters::dotnet_root`].
+    #[must_use]
+    pub fn with_dotnet_root(mut self, dotnet_root: impl Into<PathBuf>) -> Self {
+        self.dotnet_root = Some(dotnet_root.into());
+        self
+    }
+
+    /// Converts the paths into platform strings and builds the parameters.
+    pub fn build(&self) -> Result<InitializeParameters, ContainsNulError> {
+        let host_path = self
+            .host_path
+            .as_ref()
+            .map(PdCString::from_os_str)
+            .transpose()?;
+        let dotnet_root = self
+            .dotnet_root
+            .as_ref()
+            .map(PdCString::from_os_str)
+            .transpose()?;
+        Ok(InitializeParameters::from_strings(host_path, dotnet_root))
+    }
+}
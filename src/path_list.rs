@@ -0,0 +1,229 @@
+use std::{
+    collections::HashSet,
+    error,
+    ffi::{OsStr, OsString},
+    fmt,
+    path::{Path, PathBuf},
+    slice, vec,
+};
+
+use coreclr_hosting_shared::char_t;
+
+use crate::{
+    pdcstr::{self, PdCStr, PdCString},
+    PATH_LIST_SEPARATOR,
+};
+
+/// A list of paths as used by the runtime properties `TRUSTED_PLATFORM_ASSEMBLIES`, `NATIVE_DLL_SEARCH_DIRECTORIES`,
+/// `PLATFORM_RESOURCE_ROOTS` and `APP_PATHS` and returned by `hostfxr_get_native_search_directories`,
+/// which are joined by [`PATH_LIST_SEPARATOR`] (`;` on Windows and `:` otherwise).
+///
+/// The entries are never empty and never contain the separator or a nul character, so a list can always be joined
+/// and parsed again without changing it.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct PathList {
+    paths: Vec<PathBuf>,
+}
+
+impl PathList {
+    /// Creates an empty list.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { paths: Vec::new() }
+    }
+
+    /// Splits the given string at [`PATH_LIST_SEPARATOR`], skipping empty entries like the runtime does.
+    ///
+    /// Nul characters are treated as the end of the string.
+    #[must_use]
+    pub fn parse(s: impl AsRef<OsStr>) -> Self {
+        let s = pdcstr::os_str_to_vec(s.as_ref());
+        let len = s.iter().position(|&c| c == 0).unwrap_or(s.len());
+        Self::from_slice(&s[..len])
+    }
+
+    /// Splits the given platform string at [`PATH_LIST_SEPARATOR`], skipping empty entries like the runtime does.
+    #[must_use]
+    pub fn from_pdcstr(s: &PdCStr) -> Self {
+        Self::from_slice(s.as_slice())
+    }
+
+    fn from_slice(s: &[char_t]) -> Self {
+        let paths = s
+            .split(|&c| c == PATH_LIST_SEPARATOR)
+            .filter(|path| !path.is_empty())
+            .map(|path| pdcstr::slice_to_os_string(path).into())
+            .collect();
+        Self { paths }
+    }
+
+    /// Returns the number of entries.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    /// Returns whether the list has no entries.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    /// Returns the entries.
+    #[must_use]
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    /// Returns an iterator over the entries.
+    pub fn iter(&self) -> slice::Iter<'_, PathBuf> {
+        self.paths.iter()
+    }
+
+    /// Returns whether the list contains the given path.
+    #[must_use]
+    pub fn contains(&self, path: impl AsRef<Path>) -> bool {
+        let path = path.as_ref();
+        self.paths.iter().any(|entry| entry == path)
+    }
+
+    /// Appends the given path.
+    pub fn push(&mut self, path: impl Into<PathBuf>) -> Result<(), PathListError> {
+        let path = validate(path.into())?;
+        self.paths.push(path);
+        Ok(())
+    }
+
+    /// Inserts the given path at `index`.
+    ///
+    /// # Panics
+    /// Panics if `index` is greater than the number of entries.
+    pub fn insert(&mut self, index: usize, path: impl Into<PathBuf>) -> Result<(), PathListError> {
+        let path = validate(path.into())?;
+        self.paths.insert(index, path);
+        Ok(())
+    }
+
+    /// Removes all occurrences of the given path and returns whether there were any.
+    pub fn remove(&mut self, path: impl AsRef<Path>) -> bool {
+        let path = path.as_ref();
+        let len = self.paths.len();
+        self.paths.retain(|entry| entry != path);
+        self.paths.len() != len
+    }
+
+    /// Retains only the entries for which `f` returns `true`.
+    pub fn retain(&mut self, f: impl FnMut(&PathBuf) -> bool) {
+        self.paths.retain(f);
+    }
+
+    /// Removes all but the first occurrence of each path.
+    pub fn dedup(&mut self) {
+        let mut seen = HashSet::new();
+        self.paths.retain(|path| seen.insert(path.clone()));
+    }
+
+    /// Returns the entries of this list which are not contained in `other`, e.g. to compare lists ignoring their order.
+    pub fn difference<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = &'a PathBuf> {
+        self.paths.iter().filter(|path| !other.contains(path))
+    }
+
+    /// Joins the entries with [`PATH_LIST_SEPARATOR`].
+    #[must_use]
+    pub fn join(&self) -> OsString {
+        pdcstr::slice_to_os_string(&self.join_slice())
+    }
+
+    /// Joins the entries with [`PATH_LIST_SEPARATOR`] into a platform string, as expected by `hostfxr_set_runtime_property_value`.
+    #[must_use]
+    pub fn to_pdcstring(&self) -> PdCString {
+        PdCString::from_vec(self.join_slice()).expect("entries never contain a nul character")
+    }
+
+    fn join_slice(&self) -> Vec<char_t> {
+        let mut joined = Vec::new();
+        for (i, path) in self.paths.iter().enumerate() {
+            if i != 0 {
+                joined.push(PATH_LIST_SEPARATOR);
+            }
+            joined.extend(pdcstr::os_str_to_vec(path.as_os_str()));
+        }
+        joined
+    }
+}
+
+fn validate(path: PathBuf) -> Result<PathBuf, PathListError> {
+    let chars = pdcstr::os_str_to_vec(path.as_os_str());
+    if chars.is_empty() {
+        Err(PathListError::Empty)
+    } else if chars.contains(&PATH_LIST_SEPARATOR) {
+        Err(PathListError::ContainsSeparator(path))
+    } else if chars.contains(&0) {
+        Err(PathListError::ContainsNul(path))
+    } else {
+        Ok(path)
+    }
+}
+
+impl<'a> IntoIterator for &'a PathList {
+    type Item = &'a PathBuf;
+    type IntoIter = slice::Iter<'a, PathBuf>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for PathList {
+    type Item = PathBuf;
+    type IntoIter = vec::IntoIter<PathBuf>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.paths.into_iter()
+    }
+}
+
+impl From<&PdCStr> for PathList {
+    fn from(s: &PdCStr) -> Self {
+        Self::from_pdcstr(s)
+    }
+}
+
+impl TryFrom<Vec<PathBuf>> for PathList {
+    type Error = PathListError;
+
+    fn try_from(paths: Vec<PathBuf>) -> Result<Self, Self::Error> {
+        let paths = paths.into_iter().map(validate).collect::<Result<_, _>>()?;
+        Ok(Self { paths })
+    }
+}
+
+/// Error returned when adding an invalid entry to a [`PathList`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PathListError {
+    /// The path is empty.
+    Empty,
+    /// The path contains [`PATH_LIST_SEPARATOR`].
+    ContainsSeparator(PathBuf),
+    /// The path contains a nul character.
+    ContainsNul(PathBuf),
+}
+
+impl fmt::Display for PathListError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("path list entry is empty"),
+            Self::ContainsSeparator(path) => write!(
+                f,
+                "path '{}' contains the path list separator",
+                path.display()
+            ),
+            Self::ContainsNul(path) => {
+                write!(f, "path '{}' contains a nul character", path.display())
+            }
+        }
+    }
+}
+
+impl error::Error for PathListError {}
@@ -0,0 +1,327 @@
+use std::{
+    borrow::{Borrow, Cow},
+    ffi::{OsStr, OsString},
+    fmt, ops,
+    path::{Path, PathBuf},
+    ptr, slice,
+    str::FromStr,
+};
+
+use coreclr_hosting_shared::char_t;
+
+use crate::error::ContainsNulError;
+
+/// An owned, nul-terminated platform string, i.e. a string of [`char_t`] as expected by the hosting apis
+/// (UTF-8 on Unix and UTF-16 on Windows).
+///
+/// This is the owned counterpart of [`PdCStr`], like [`CString`](std::ffi::CString) is for [`CStr`](std::ffi::CStr).
+/// Conversions from and to [`OsStr`] and [`Path`] are lossless.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PdCString {
+    /// The characters of the string including the nul terminator.
+    inner: Box<[char_t]>,
+}
+
+/// A borrowed, nul-terminated platform string, see [`PdCString`].
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct PdCStr {
+    /// The characters of the string including the nul terminator.
+    inner: [char_t],
+}
+
+impl PdCString {
+    /// Converts the given string into a platform string, failing if it contains a nul character.
+    pub fn from_os_str(s: impl AsRef<OsStr>) -> Result<Self, ContainsNulError> {
+        Self::from_vec(os_str_to_vec(s.as_ref()))
+    }
+
+    /// Creates a platform string from the given characters (without a nul terminator), failing if they contain a nul character.
+    pub fn from_vec(mut vec: Vec<char_t>) -> Result<Self, ContainsNulError> {
+        if let Some(position) = vec.iter().position(|&c| c == 0) {
+            return Err(ContainsNulError { position });
+        }
+        vec.push(0);
+        Ok(Self {
+            inner: vec.into_boxed_slice(),
+        })
+    }
+
+    /// Returns the borrowed platform string.
+    #[must_use]
+    pub fn as_pdcstr(&self) -> &PdCStr {
+        // SAFETY: `inner` is nul-terminated and contains no interior nul.
+        unsafe { PdCStr::from_slice_with_nul_unchecked(&self.inner) }
+    }
+
+    /// Converts the string into its characters, without the nul terminator.
+    #[must_use]
+    pub fn into_vec(self) -> Vec<char_t> {
+        let mut vec = self.inner.into_vec();
+        vec.pop();
+        vec
+    }
+
+    /// Converts the string into its characters, including the nul terminator.
+    #[must_use]
+    pub fn into_vec_with_nul(self) -> Vec<char_t> {
+        self.inner.into_vec()
+    }
+}
+
+impl PdCStr {
+    /// Wraps a raw nul-terminated platform string.
+    ///
+    /// # Safety
+    /// `ptr` must be non-null and point to a nul-terminated [`char_t`] string that is valid and not mutated for the lifetime `'a`.
+    #[must_use]
+    pub unsafe fn from_ptr<'a>(ptr: *const char_t) -> &'a Self {
+        let mut len = 0;
+        while unsafe { *ptr.add(len) } != 0 {
+            len += 1;
+        }
+        unsafe { Self::from_slice_with_nul_unchecked(slice::from_raw_parts(ptr, len + 1)) }
+    }
+
+    /// Wraps the given characters, which have to end with the only nul character.
+    ///
+    /// # Safety
+    /// `slice` must end with a nul character and must not contain any other.
+    #[must_use]
+    pub const unsafe fn from_slice_with_nul_unchecked(slice: &[char_t]) -> &Self {
+        // SAFETY: `PdCStr` is a transparent wrapper around `[char_t]`.
+        unsafe { &*(ptr::from_ref(slice) as *const Self) }
+    }
+
+    /// Returns a pointer to the nul-terminated string, which is valid for as long as `self` is.
+    #[must_use]
+    pub const fn as_ptr(&self) -> *const char_t {
+        self.inner.as_ptr()
+    }
+
+    /// Returns the characters of the string, without the nul terminator.
+    #[must_use]
+    pub fn as_slice(&self) -> &[char_t] {
+        &self.inner[..self.len()]
+    }
+
+    /// Returns the characters of the string, including the nul terminator.
+    #[must_use]
+    pub const fn as_slice_with_nul(&self) -> &[char_t] {
+        &self.inner
+    }
+
+    /// Returns the length of the string in [`char_t`] units, without the nul terminator.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.inner.len() - 1
+    }
+
+    /// Returns whether the string is empty.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Converts the string into an [`OsString`] without loss.
+    #[must_use]
+    pub fn to_os_string(&self) -> OsString {
+        slice_to_os_string(self.as_slice())
+    }
+
+    /// Converts the string into a [`String`], replacing invalid data with [`U+FFFD REPLACEMENT CHARACTER`](char::REPLACEMENT_CHARACTER).
+    #[must_use]
+    pub fn to_string_lossy(&self) -> String {
+        slice_to_string_lossy(self.as_slice())
+    }
+}
+
+#[cfg(not(windows))]
+#[allow(clippy::cast_possible_wrap)]
+pub(crate) fn os_str_to_vec(s: &OsStr) -> Vec<char_t> {
+    use std::os::unix::ffi::OsStrExt;
+
+    s.as_bytes().iter().map(|&b| b as char_t).collect()
+}
+
+#[cfg(windows)]
+pub(crate) fn os_str_to_vec(s: &OsStr) -> Vec<char_t> {
+    use std::os::windows::ffi::OsStrExt;
+
+    s.encode_wide().collect()
+}
+
+#[cfg(not(windows))]
+#[allow(clippy::cast_sign_loss)]
+pub(crate) fn slice_to_os_string(s: &[char_t]) -> OsString {
+    use std::os::unix::ffi::OsStringExt;
+
+    OsString::from_vec(s.iter().map(|&c| c as u8).collect())
+}
+
+#[cfg(windows)]
+pub(crate) fn slice_to_os_string(s: &[char_t]) -> OsString {
+    use std::os::windows::ffi::OsStringExt;
+
+    OsString::from_wide(s)
+}
+
+#[cfg(not(windows))]
+fn slice_to_string_lossy(s: &[char_t]) -> String {
+    slice_to_os_string(s).to_string_lossy().into_owned()
+}
+
+#[cfg(windows)]
+fn slice_to_string_lossy(s: &[char_t]) -> String {
+    String::from_utf16_lossy(s)
+}
+
+impl ops::Deref for PdCString {
+    type Target = PdCStr;
+
+    fn deref(&self) -> &Self::Target {
+        self.as_pdcstr()
+    }
+}
+
+impl AsRef<PdCStr> for PdCString {
+    fn as_ref(&self) -> &PdCStr {
+        self
+    }
+}
+
+impl AsRef<PdCStr> for PdCStr {
+    fn as_ref(&self) -> &PdCStr {
+        self
+    }
+}
+
+impl Borrow<PdCStr> for PdCString {
+    fn borrow(&self) -> &PdCStr {
+        self
+    }
+}
+
+impl ToOwned for PdCStr {
+    type Owned = PdCString;
+
+    fn to_owned(&self) -> Self::Owned {
+        PdCString {
+            inner: self.inner.into(),
+        }
+    }
+}
+
+impl From<&PdCStr> for PdCString {
+    fn from(s: &PdCStr) -> Self {
+        s.to_owned()
+    }
+}
+
+impl From<&PdCStr> for OsString {
+    fn from(s: &PdCStr) -> Self {
+        s.to_os_string()
+    }
+}
+
+impl From<&PdCStr> for PathBuf {
+    fn from(s: &PdCStr) -> Self {
+        s.to_os_string().into()
+    }
+}
+
+impl From<PdCString> for OsString {
+    fn from(s: PdCString) -> Self {
+        s.to_os_string()
+    }
+}
+
+impl From<PdCString> for PathBuf {
+    fn from(s: PdCString) -> Self {
+        s.to_os_string().into()
+    }
+}
+
+impl TryFrom<&OsStr> for PdCString {
+    type Error = ContainsNulError;
+
+    fn try_from(s: &OsStr) -> Result<Self, Self::Error> {
+        Self::from_os_str(s)
+    }
+}
+
+impl TryFrom<&Path> for PdCString {
+    type Error = ContainsNulError;
+
+    fn try_from(s: &Path) -> Result<Self, Self::Error> {
+        Self::from_os_str(s)
+    }
+}
+
+impl TryFrom<&str> for PdCString {
+    type Error = ContainsNulError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        Self::from_os_str(s)
+    }
+}
+
+impl FromStr for PdCString {
+    type Err = ContainsNulError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_os_str(s)
+    }
+}
+
+impl fmt::Debug for PdCString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_pdcstr().fmt(f)
+    }
+}
+
+impl fmt::Debug for PdCStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.to_os_string().fmt(f)
+    }
+}
+
+/// Conversion into a borrowed or owned [`PdCStr`], which is how the safe wrappers in this crate accept strings and paths.
+///
+/// Platform strings are passed through as is, while strings and paths are converted, failing if they contain a nul character.
+pub trait ToPdCStr {
+    /// Converts `self` into a platform string.
+    fn to_pdcstr(&self) -> Result<Cow<'_, PdCStr>, ContainsNulError>;
+}
+
+impl ToPdCStr for PdCStr {
+    fn to_pdcstr(&self) -> Result<Cow<'_, PdCStr>, ContainsNulError> {
+        Ok(Cow::Borrowed(self))
+    }
+}
+
+impl ToPdCStr for PdCString {
+    fn to_pdcstr(&self) -> Result<Cow<'_, PdCStr>, ContainsNulError> {
+        Ok(Cow::Borrowed(self))
+    }
+}
+
+impl<T: ToPdCStr + ?Sized> ToPdCStr for &T {
+    fn to_pdcstr(&self) -> Result<Cow<'_, PdCStr>, ContainsNulError> {
+        (**self).to_pdcstr()
+    }
+}
+
+macro_rules! impl_to_pdcstr_for_os_str {
+    ($($ty:ty),*) => {
+        $(
+            impl ToPdCStr for $ty {
+                fn to_pdcstr(&self) -> Result<Cow<'_, PdCStr>, ContainsNulError> {
+                    PdCString::from_os_str(self).map(Cow::Owned)
+                }
+            }
+        )*
+    };
+}
+
+impl_to_pdcstr_for_os_str!(str, String, OsStr, OsString, Path, PathBuf);
@@ -0,0 +1,318 @@
+use std::{
+    error, fmt, fs, io,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+
+/// The contents of a `.runtimeconfig.json` file, which describes the runtime an application runs on.
+///
+/// Keys not modeled by this type are kept in [`extra`](Self::extra) (and the `extra` fields of the nested types),
+/// in their original order, so reading and writing a file does not lose any information.
+///
+/// See the [documentation of the file format](https://github.com/dotnet/runtime/blob/main/docs/design/features/host-runtime-information.md)
+/// for details.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeConfig {
+    /// The `runtimeOptions` object.
+    #[serde(default)]
+    pub runtime_options: RuntimeOptions,
+    /// Any other top-level keys.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// The `runtimeOptions` object of a [`RuntimeConfig`].
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeOptions {
+    /// The target framework moniker of the application, e.g. `net8.0`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tfm: Option<String>,
+    /// The shared framework the application runs on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub framework: Option<FrameworkReference>,
+    /// The shared frameworks the application runs on, if there is more than one.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub frameworks: Vec<FrameworkReference>,
+    /// The frameworks included in a self-contained application.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub included_frameworks: Vec<FrameworkReference>,
+    /// The roll forward policy for all frameworks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub roll_forward: Option<RollForward>,
+    /// The legacy roll forward policy (`0` disabled, `1` minor and `2` major), superseded by [`roll_forward`](Self::roll_forward).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub roll_forward_on_no_candidate_fx: Option<u32>,
+    /// Whether to roll forward to the latest patch version, superseded by [`roll_forward`](Self::roll_forward).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub apply_patches: Option<bool>,
+    /// Additional directories probed for dependencies, usually only set in `.runtimeconfig.dev.json`.
+    ///
+    /// Like the host, a single path given as a string instead of an array is accepted.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_probing_paths",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub additional_probing_paths: Vec<PathBuf>,
+    /// The runtime properties passed to the runtime, e.g. `System.GC.Server`.
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub config_properties: Map<String, Value>,
+    /// Any other keys.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// A reference to a shared framework in a [`RuntimeOptions`].
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FrameworkReference {
+    /// The name of the framework, e.g. `Microsoft.NETCore.App`.
+    pub name: String,
+    /// The minimum version of the framework, e.g. `8.0.0`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// The roll forward policy for this framework.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub roll_forward: Option<RollForward>,
+    /// The legacy roll forward policy for this framework, see [`RuntimeOptions::roll_forward_on_no_candidate_fx`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub roll_forward_on_no_candidate_fx: Option<u32>,
+    /// Whether to roll forward to the latest patch version of this framework.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub apply_patches: Option<bool>,
+    /// Any other keys.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl FrameworkReference {
+    /// Creates a reference to the given version of a framework.
+    #[must_use]
+    pub fn new(name: impl Into<String>, version: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            version: Some(version.into()),
+            ..Self::default()
+        }
+    }
+}
+
+/// The policy for selecting a framework version if the requested one is not installed.
+///
+/// See the [documentation of roll forward](https://learn.microsoft.com/en-us/dotnet/core/versions/selection#control-roll-forward-behavior) for details.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RollForward {
+    /// Roll forward to the highest patch version, disabling minor version roll forward.
+    LatestPatch,
+    /// Roll forward to the lowest higher minor version if the requested minor version is missing (the default).
+    Minor,
+    /// Roll forward to the lowest higher major version if the requested major version is missing.
+    Major,
+    /// Roll forward to the highest minor version, even if the requested minor version is present.
+    LatestMinor,
+    /// Roll forward to the highest major and minor version, even if the requested version is present.
+    LatestMajor,
+    /// Do not roll forward, only bind to the requested version.
+    Disable,
+}
+
+impl RollForward {
+    /// Returns the name of the policy as used in `.runtimeconfig.json` files and the `DOTNET_ROLL_FORWARD` environment variable.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::LatestPatch => "LatestPatch",
+            Self::Minor => "Minor",
+            Self::Major => "Major",
+            Self::LatestMinor => "LatestMinor",
+            Self::LatestMajor => "LatestMajor",
+            Self::Disable => "Disable",
+        }
+    }
+}
+
+impl fmt::Display for RollForward {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for RollForward {
+    type Err = RuntimeConfigError;
+
+    /// Parses the name of a policy, ignoring case like the host does.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [
+            Self::LatestPatch,
+            Self::Minor,
+            Self::Major,
+            Self::LatestMinor,
+            Self::LatestMajor,
+            Self::Disable,
+        ]
+        .into_iter()
+        .find(|policy| policy.as_str().eq_ignore_ascii_case(s))
+        .ok_or_else(|| RuntimeConfigError::Json(format!("unknown roll forward policy '{s}'")))
+    }
+}
+
+impl Serialize for RollForward {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for RollForward {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Deserializes [`RuntimeOptions::additional_probing_paths`] from either an array or a single path.
+fn deserialize_probing_paths<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<PathBuf>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum ProbingPaths {
+        One(PathBuf),
+        Many(Vec<PathBuf>),
+    }
+
+    Ok(match ProbingPaths::deserialize(deserializer)? {
+        ProbingPaths::One(path) => vec![path],
+        ProbingPaths::Many(paths) => paths,
+    })
+}
+
+impl RuntimeConfig {
+    /// Creates a config for an application running on the given framework.
+    #[must_use]
+    pub fn for_framework(tfm: impl Into<String>, framework: FrameworkReference) -> Self {
+        Self {
+            runtime_options: RuntimeOptions {
+                tfm: Some(tfm.into()),
+                framework: Some(framework),
+                ..RuntimeOptions::default()
+            },
+            extra: Map::new(),
+        }
+    }
+
+    /// Parses the contents of a `.runtimeconfig.json` file.
+    pub fn from_json(json: &str) -> Result<Self, RuntimeConfigError> {
+        serde_json::from_str(json).map_err(RuntimeConfigError::from)
+    }
+
+    /// Reads the `.runtimeconfig.json` file at the given path.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, RuntimeConfigError> {
+        let json = fs::read_to_string(path).map_err(|err| RuntimeConfigError::Io(err.kind()))?;
+        Self::from_json(&json)
+    }
+
+    /// Reads the `.runtimeconfig.json` file at the given path and merges the `.runtimeconfig.dev.json` file next to it
+    /// into it if there is one, like the host does (see [`merge_dev_config`](Self::merge_dev_config)).
+    ///
+    /// Like the host (which only logs this case), a dev config that cannot be read or parsed is ignored.
+    pub fn read_with_dev_config(path: impl AsRef<Path>) -> Result<Self, RuntimeConfigError> {
+        let path = path.as_ref();
+        let mut config = Self::read(path)?;
+        if let Ok(dev_config) = Self::read(dev_config_path(path)) {
+            config.merge_dev_config(&dev_config);
+        }
+        Ok(config)
+    }
+
+    /// Formats the config as pretty-printed JSON.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("runtime config is always serializable")
+    }
+
+    /// Writes the config as pretty-printed JSON to the given path.
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), RuntimeConfigError> {
+        fs::write(path, self.to_json()).map_err(|err| RuntimeConfigError::Io(err.kind()))
+    }
+
+    /// Merges the options of a `.runtimeconfig.dev.json` file into this config, following the rules of the host:
+    /// - the options of this config take precedence over the ones of the dev config,
+    /// - `additionalProbingPaths` of the dev config are appended after the ones of this config,
+    /// - `configProperties` of the dev config are added unless this config sets them as well,
+    /// - the frameworks and the target framework are only read from this config.
+    pub fn merge_dev_config(&mut self, dev_config: &Self) {
+        let options = &mut self.runtime_options;
+        let dev_options = &dev_config.runtime_options;
+        options.roll_forward = options.roll_forward.or(dev_options.roll_forward);
+        options.roll_forward_on_no_candidate_fx = options
+            .roll_forward_on_no_candidate_fx
+            .or(dev_options.roll_forward_on_no_candidate_fx);
+        options.apply_patches = options.apply_patches.or(dev_options.apply_patches);
+        options
+            .additional_probing_paths
+            .extend(dev_options.additional_probing_paths.iter().cloned());
+        for (name, value) in &dev_options.config_properties {
+            if !options.config_properties.contains_key(name) {
+                options
+                    .config_properties
+                    .insert(name.clone(), value.clone());
+            }
+        }
+    }
+
+    /// Returns all frameworks referenced by this config, i.e. [`framework`](RuntimeOptions::framework) followed by
+    /// [`frameworks`](RuntimeOptions::frameworks).
+    pub fn frameworks(&self) -> impl Iterator<Item = &FrameworkReference> {
+        self.runtime_options
+            .framework
+            .iter()
+            .chain(&self.runtime_options.frameworks)
+    }
+
+    /// Returns whether the application is self-contained, i.e. its config lists included frameworks instead of
+    /// referencing shared ones.
+    #[must_use]
+    pub fn is_self_contained(&self) -> bool {
+        self.frameworks().next().is_none() && !self.runtime_options.included_frameworks.is_empty()
+    }
+}
+
+/// Returns the path of the `.runtimeconfig.dev.json` file belonging to the given `.runtimeconfig.json` file.
+#[must_use]
+pub fn dev_config_path(path: &Path) -> PathBuf {
+    let mut dev_path = path.with_extension("").into_os_string();
+    dev_path.push(".dev.json");
+    dev_path.into()
+}
+
+/// Error returned when reading a runtime config fails.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RuntimeConfigError {
+    /// The file could not be read or written.
+    Io(io::ErrorKind),
+    /// The file is not valid JSON or does not match the format.
+    Json(String),
+}
+
+impl From<serde_json::Error> for RuntimeConfigError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Json(err.to_string())
+    }
+}
+
+impl fmt::Display for RuntimeConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(kind) => write!(f, "failed to access runtime config: {kind}"),
+            Self::Json(message) => write!(f, "invalid runtime config: {message}"),
+        }
+    }
+}
+
+impl error::Error for RuntimeConfigError {}
@@ -0,0 +1,878 @@
+use std::{
+    borrow::Cow,
+    error,
+    fmt::{self, Write as _},
+    str::FromStr,
+};
+
+use coreclr_hosting_shared::char_t;
+
+use crate::{
+    error::ContainsNulError,
+    pdcstr::{PdCStr, PdCString, ToPdCStr},
+};
+
+/// Characters that have to be escaped in type and namespace names.
+const TYPE_NAME_SPECIAL_CHARS: &[char] = &['\\', ',', '+', '&', '*', '[', ']'];
+/// Characters that have to be escaped in assembly names.
+const ASSEMBLY_NAME_SPECIAL_CHARS: &[char] = &['\\', ',', '=', '"', '\'', '[', ']'];
+
+/// An assembly qualified type name like `App.Program+Nested, App, Version=1.0.0.0, Culture=neutral, PublicKeyToken=null`,
+/// as expected by [`load_assembly_and_get_function_pointer_fn`](crate::load_assembly_and_get_function_pointer_fn)
+/// and [`get_function_pointer_fn`](crate::get_function_pointer_fn).
+///
+/// Follows the [grammar for type names](https://learn.microsoft.com/en-us/dotnet/framework/reflection-and-codedom/specifying-fully-qualified-type-names)
+/// used by the runtime, including nested types, generic arguments, pointer/by-ref/array modifiers and escaping of special characters.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AssemblyQualifiedName {
+    /// The type.
+    pub type_name: TypeName,
+    /// The assembly containing the type, or [`None`] if the name is not assembly qualified.
+    pub assembly: Option<AssemblyName>,
+}
+
+/// A (not assembly qualified) type name like ``System.Collections.Generic.List`1+Enumerator[[System.Int32, System.Private.CoreLib]]``.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TypeName {
+    /// The namespace of the (outermost) type, if any.
+    pub namespace: Option<String>,
+    /// The name of the (outermost) type, including its generic arity (e.g. ``List`1``).
+    pub name: String,
+    /// The names of the nested types, from outermost to innermost.
+    pub nested: Vec<String>,
+    /// The generic arguments, if the type is a constructed generic type.
+    pub generic_arguments: Vec<AssemblyQualifiedName>,
+    /// The modifiers (pointers, references and arrays) applied to the type, in order.
+    pub modifiers: Vec<TypeModifier>,
+}
+
+/// A modifier applied to a type name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TypeModifier {
+    /// A pointer (`*`).
+    Pointer,
+    /// A managed reference (`&`), which may only be the last modifier.
+    ByRef,
+    /// A single-dimensional, zero-based array (`[]`).
+    SzArray,
+    /// A multi-dimensional array with the given rank (`[*]` for rank 1, `[,]` for rank 2 and so on).
+    Array {
+        /// The number of dimensions.
+        rank: u32,
+    },
+}
+
+/// The display name of an assembly like `App, Version=1.0.0.0, Culture=neutral, PublicKeyToken=null`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct AssemblyName {
+    /// The simple name of the assembly.
+    pub name: String,
+    /// The version of the assembly.
+    pub version: Option<AssemblyVersion>,
+    /// The culture of the assembly (`neutral` for culture-invariant assemblies).
+    pub culture: Option<String>,
+    /// The public key token of the assembly.
+    pub public_key_token: Option<PublicKeyToken>,
+    /// Any other properties (like `ProcessorArchitecture` or `Retargetable`) in order.
+    pub properties: Vec<(String, String)>,
+}
+
+/// The version of an assembly.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AssemblyVersion {
+    /// The major version.
+    pub major: u16,
+    /// The minor version.
+    pub minor: u16,
+    /// The build number.
+    pub build: u16,
+    /// The revision number.
+    pub revision: u16,
+}
+
+/// The public key token of an assembly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PublicKeyToken {
+    /// The assembly is not strong-named (`PublicKeyToken=null`).
+    Null,
+    /// The last 8 bytes of the SHA-1 hash of the public key of the assembly.
+    Token([u8; 8]),
+}
+
+impl AssemblyQualifiedName {
+    /// Creates a new assembly qualified name.
+    #[must_use]
+    pub fn new(type_name: TypeName, assembly: AssemblyName) -> Self {
+        Self {
+            type_name,
+            assembly: Some(assembly),
+        }
+    }
+
+    /// Parses an (optionally) assembly qualified type name.
+    pub fn parse(s: &str) -> Result<Self, TypeNameError> {
+        let mut parser = Parser::new(s);
+        let name = parser.parse_assembly_qualified_name(false)?;
+        parser.skip_whitespace();
+        if let Some(c) = parser.peek() {
+            return Err(TypeNameError::UnexpectedChar {
+                position: parser.position,
+                found: c,
+            });
+        }
+        Ok(name)
+    }
+
+    /// Checks that all parts of the name are well-formed, see [`TypeName::validate`] and [`AssemblyName::validate`].
+    pub fn validate(&self) -> Result<(), TypeNameError> {
+        self.type_name.validate()?;
+        if let Some(assembly) = &self.assembly {
+            assembly.validate()?;
+        }
+        Ok(())
+    }
+
+    /// Converts the formatted name into a nul-terminated platform string.
+    pub fn to_char_t_vec(&self) -> Result<Vec<char_t>, ContainsNulError> {
+        PdCString::from_os_str(self.to_string()).map(PdCString::into_vec_with_nul)
+    }
+}
+
+impl ToPdCStr for AssemblyQualifiedName {
+    fn to_pdcstr(&self) -> Result<Cow<'_, PdCStr>, ContainsNulError> {
+        PdCString::from_os_str(self.to_string()).map(Cow::Owned)
+    }
+}
+
+impl From<TypeName> for AssemblyQualifiedName {
+    fn from(type_name: TypeName) -> Self {
+        Self {
+            type_name,
+            assembly: None,
+        }
+    }
+}
+
+impl FromStr for AssemblyQualifiedName {
+    type Err = TypeNameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for AssemblyQualifiedName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.type_name.fmt(f)?;
+        if let Some(assembly) = &self.assembly {
+            write!(f, ", {assembly}")?;
+        }
+        Ok(())
+    }
+}
+
+impl TypeName {
+    /// Creates a new type name from a full name like `App.Program`, splitting off the namespace at the last `.`.
+    #[must_use]
+    pub fn new(full_name: &str) -> Self {
+        let (namespace, name) = split_namespace(full_name);
+        Self {
+            namespace,
+            name,
+            nested: Vec::new(),
+            generic_arguments: Vec::new(),
+            modifiers: Vec::new(),
+        }
+    }
+
+    /// Appends a nested type.
+    #[must_use]
+    pub fn nested(mut self, name: impl Into<String>) -> Self {
+        self.nested.push(name.into());
+        self
+    }
+
+    /// Appends a generic argument.
+    ///
+    /// If none of the names declare a generic arity (like ``List`1``), the arity is appended to the outermost name when formatting,
+    /// as nested types inherit the generic parameters of their declaring type (e.g. ``List`1+Enumerator``).
+    /// Parsing the formatted name therefore yields the name with the arity, while generic arguments without a declared arity are rejected.
+    #[must_use]
+    pub fn generic_argument(mut self, argument: impl Into<AssemblyQualifiedName>) -> Self {
+        self.generic_arguments.push(argument.into());
+        self
+    }
+
+    /// Appends a modifier.
+    #[must_use]
+    pub fn modifier(mut self, modifier: TypeModifier) -> Self {
+        self.modifiers.push(modifier);
+        self
+    }
+
+    /// Qualifies the type name with the given assembly.
+    #[must_use]
+    pub fn qualified(self, assembly: AssemblyName) -> AssemblyQualifiedName {
+        AssemblyQualifiedName::new(self, assembly)
+    }
+
+    /// Returns the full name of the (innermost) type without generic arguments and modifiers, e.g. `App.Program+Nested`.
+    #[must_use]
+    pub fn full_name(&self) -> String {
+        let mut full_name = String::new();
+        self.write_full_name(&mut full_name, false)
+            .expect("writing to a string cannot fail");
+        full_name
+    }
+
+    /// Returns the sum of the generic arities declared by the outer and nested type names.
+    #[must_use]
+    pub fn declared_arity(&self) -> usize {
+        std::iter::once(&self.name)
+            .chain(&self.nested)
+            .map(|name| declared_arity(name))
+            .sum()
+    }
+
+    /// Checks that the name is well-formed:
+    ///  * namespaces and names are non-empty and do not contain nul characters,
+    ///  * the declared generic arity matches the number of generic arguments,
+    ///  * a by-ref modifier is only used last and arrays have a rank between 1 and 32,
+    ///  * all generic arguments are valid.
+    pub fn validate(&self) -> Result<(), TypeNameError> {
+        if let Some(namespace) = &self.namespace {
+            for part in namespace.split('.') {
+                validate_identifier(part)?;
+            }
+        }
+        validate_identifier(&self.name)?;
+        for nested in &self.nested {
+            validate_identifier(nested)?;
+        }
+
+        let declared = self.declared_arity();
+        if declared != 0 && declared != self.generic_arguments.len() {
+            return Err(TypeNameError::ArityMismatch {
+                declared,
+                arguments: self.generic_arguments.len(),
+            });
+        }
+
+        for (i, modifier) in self.modifiers.iter().enumerate() {
+            match modifier {
+                TypeModifier::ByRef if i + 1 != self.modifiers.len() => {
+                    return Err(TypeNameError::ByRefNotLast);
+                }
+                TypeModifier::Array { rank } if !(1..=32).contains(rank) => {
+                    return Err(TypeNameError::InvalidArrayRank(*rank));
+                }
+                _ => {}
+            }
+        }
+
+        for argument in &self.generic_arguments {
+            argument.validate()?;
+        }
+        Ok(())
+    }
+
+    fn write_full_name(&self, f: &mut impl fmt::Write, append_arity: bool) -> fmt::Result {
+        if let Some(namespace) = &self.namespace {
+            write_escaped(f, namespace, TYPE_NAME_SPECIAL_CHARS)?;
+            f.write_char('.')?;
+        }
+        write_escaped(f, &self.name, TYPE_NAME_SPECIAL_CHARS)?;
+        if append_arity {
+            write!(f, "`{}", self.generic_arguments.len())?;
+        }
+        for nested in &self.nested {
+            f.write_char('+')?;
+            write_escaped(f, nested, TYPE_NAME_SPECIAL_CHARS)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for TypeName {
+    type Err = TypeNameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = AssemblyQualifiedName::parse(s)?;
+        if name.assembly.is_some() {
+            return Err(TypeNameError::UnexpectedAssemblyName);
+        }
+        Ok(name.type_name)
+    }
+}
+
+impl fmt::Display for TypeName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let append_arity = !self.generic_arguments.is_empty() && self.declared_arity() == 0;
+        self.write_full_name(f, append_arity)?;
+
+        if !self.generic_arguments.is_empty() {
+            f.write_char('[')?;
+            for (i, argument) in self.generic_arguments.iter().enumerate() {
+                if i != 0 {
+                    f.write_char(',')?;
+                }
+                if argument.assembly.is_some() {
+                    write!(f, "[{argument}]")?;
+                } else {
+                    argument.fmt(f)?;
+                }
+            }
+            f.write_char(']')?;
+        }
+
+        for modifier in &self.modifiers {
+            modifier.fmt(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for TypeModifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pointer => f.write_str("*"),
+            Self::ByRef => f.write_str("&"),
+            Self::SzArray => f.write_str("[]"),
+            Self::Array { rank: 1 } => f.write_str("[*]"),
+            Self::Array { rank } => {
+                f.write_char('[')?;
+                for _ in 1..*rank {
+                    f.write_char(',')?;
+                }
+                f.write_char(']')
+            }
+        }
+    }
+}
+
+impl AssemblyName {
+    /// Creates a new assembly name with the given simple name.
+    #[must_use]
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Sets the version.
+    #[must_use]
+    pub fn with_version(mut self, version: AssemblyVersion) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    /// Sets the culture.
+    #[must_use]
+    pub fn with_culture(mut self, culture: impl Into<String>) -> Self {
+        self.culture = Some(culture.into());
+        self
+    }
+
+    /// Sets the public key token.
+    #[must_use]
+    pub fn with_public_key_token(mut self, public_key_token: PublicKeyToken) -> Self {
+        self.public_key_token = Some(public_key_token);
+        self
+    }
+
+    /// Parses an assembly display name like `App, Version=1.0.0.0, Culture=neutral, PublicKeyToken=null`.
+    pub fn parse(s: &str) -> Result<Self, TypeNameError> {
+        let mut parser = Parser::new(s);
+        let name = parser.parse_assembly_name(false)?;
+        if let Some(c) = parser.peek() {
+            return Err(TypeNameError::UnexpectedChar {
+                position: parser.position,
+                found: c,
+            });
+        }
+        Ok(name)
+    }
+
+    /// Checks that the simple name, culture and properties are non-empty and do not contain nul characters.
+    pub fn validate(&self) -> Result<(), TypeNameError> {
+        validate_identifier(&self.name)?;
+        if let Some(culture) = &self.culture {
+            validate_identifier(culture)?;
+        }
+        for (key, value) in &self.properties {
+            validate_identifier(key)?;
+            validate_identifier(value)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for AssemblyName {
+    type Err = TypeNameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for AssemblyName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_escaped(f, &self.name, ASSEMBLY_NAME_SPECIAL_CHARS)?;
+        if let Some(version) = &self.version {
+            write!(f, ", Version={version}")?;
+        }
+        if let Some(culture) = &self.culture {
+            f.write_str(", Culture=")?;
+            write_property_value(f, culture)?;
+        }
+        if let Some(public_key_token) = &self.public_key_token {
+            write!(f, ", PublicKeyToken={public_key_token}")?;
+        }
+        for (key, value) in &self.properties {
+            f.write_str(", ")?;
+            write_escaped(f, key, ASSEMBLY_NAME_SPECIAL_CHARS)?;
+            f.write_char('=')?;
+            write_property_value(f, value)?;
+        }
+        Ok(())
+    }
+}
+
+impl AssemblyVersion {
+    /// Creates a new version.
+    #[must_use]
+    pub const fn new(major: u16, minor: u16, build: u16, revision: u16) -> Self {
+        Self {
+            major,
+            minor,
+            build,
+            revision,
+        }
+    }
+}
+
+impl FromStr for AssemblyVersion {
+    type Err = TypeNameError;
+
+    /// Parses a version with 2 to 4 components, missing components are set to 0.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || TypeNameError::InvalidVersion(s.to_owned());
+        let parts = s
+            .split('.')
+            .map(|part| part.trim().parse::<u16>().map_err(|_| invalid()))
+            .collect::<Result<Vec<_>, _>>()?;
+        match *parts.as_slice() {
+            [major, minor] => Ok(Self::new(major, minor, 0, 0)),
+            [major, minor, build] => Ok(Self::new(major, minor, build, 0)),
+            [major, minor, build, revision] => Ok(Self::new(major, minor, build, revision)),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl fmt::Display for AssemblyVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}.{}.{}.{}",
+            self.major, self.minor, self.build, self.revision
+        )
+    }
+}
+
+impl FromStr for PublicKeyToken {
+    type Err = TypeNameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("null") {
+            return Ok(Self::Null);
+        }
+        let invalid = || TypeNameError::InvalidPublicKeyToken(s.to_owned());
+        if s.len() != 16 || !s.is_ascii() {
+            return Err(invalid());
+        }
+        let mut token = [0; 8];
+        for (i, byte) in token.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&s[i * 2..i * 2 + 2], 16).map_err(|_| invalid())?;
+        }
+        Ok(Self::Token(token))
+    }
+}
+
+impl fmt::Display for PublicKeyToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Null => f.write_str("null"),
+            Self::Token(token) => token.iter().try_for_each(|b| write!(f, "{b:02x}")),
+        }
+    }
+}
+
+/// Error returned when a type or assembly name is malformed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TypeNameError {
+    /// The input ended unexpectedly.
+    UnexpectedEnd,
+    /// An unexpected character was found at the given position (in bytes).
+    UnexpectedChar {
+        /// The byte offset of the character.
+        position: usize,
+        /// The unexpected character.
+        found: char,
+    },
+    /// A namespace, type or assembly name (or property) is empty.
+    EmptyName,
+    /// A name contains a nul character.
+    ContainsNul,
+    /// The generic arity declared by the names does not match the number of generic arguments.
+    ArityMismatch {
+        /// The declared arity.
+        declared: usize,
+        /// The number of generic arguments.
+        arguments: usize,
+    },
+    /// A by-ref modifier (`&`) is followed by other modifiers.
+    ByRefNotLast,
+    /// An array rank outside of 1 to 32.
+    InvalidArrayRank(u32),
+    /// An invalid assembly version.
+    InvalidVersion(String),
+    /// An invalid public key token.
+    InvalidPublicKeyToken(String),
+    /// An assembly property without a value.
+    InvalidAssemblyProperty(String),
+    /// A type name was expected, but an assembly qualified name was found.
+    UnexpectedAssemblyName,
+}
+
+impl fmt::Display for TypeNameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedEnd => f.write_str("unexpected end of type name"),
+            Self::UnexpectedChar { position, found } => {
+                write!(f, "unexpected character '{found}' at position {position}")
+            }
+            Self::EmptyName => f.write_str("empty name"),
+            Self::ContainsNul => f.write_str("name contains a nul character"),
+            Self::ArityMismatch {
+                declared,
+                arguments,
+            } => write!(
+                f,
+                "type declares {declared} generic parameters, but {arguments} arguments were given"
+            ),
+            Self::ByRefNotLast => f.write_str("by-ref modifier has to be the last modifier"),
+            Self::InvalidArrayRank(rank) => write!(f, "invalid array rank {rank}"),
+            Self::InvalidVersion(version) => write!(f, "invalid assembly version '{version}'"),
+            Self::InvalidPublicKeyToken(token) => write!(f, "invalid public key token '{token}'"),
+            Self::InvalidAssemblyProperty(property) => {
+                write!(f, "invalid assembly property '{property}'")
+            }
+            Self::UnexpectedAssemblyName => {
+                f.write_str("expected a type name without an assembly name")
+            }
+        }
+    }
+}
+
+impl error::Error for TypeNameError {}
+
+fn validate_identifier(s: &str) -> Result<(), TypeNameError> {
+    if s.trim().is_empty() {
+        return Err(TypeNameError::EmptyName);
+    }
+    if s.contains('\0') {
+        return Err(TypeNameError::ContainsNul);
+    }
+    Ok(())
+}
+
+/// Returns the generic arity declared by a name like ``List`1``.
+fn declared_arity(name: &str) -> usize {
+    name.rsplit_once('`')
+        .and_then(|(_, arity)| arity.parse().ok())
+        .unwrap_or(0)
+}
+
+fn write_escaped(f: &mut impl fmt::Write, s: &str, special_chars: &[char]) -> fmt::Result {
+    // leading and trailing whitespace is trimmed by the parser unless escaped.
+    let start = s.len() - s.trim_start().len();
+    let end = s.trim_end().len();
+    for (i, c) in s.char_indices() {
+        if special_chars.contains(&c) || (c.is_whitespace() && (i < start || i >= end)) {
+            f.write_char('\\')?;
+        }
+        f.write_char(c)?;
+    }
+    Ok(())
+}
+
+/// Writes an assembly property value, quoting it if empty as an unquoted value cannot be empty.
+fn write_property_value(f: &mut impl fmt::Write, value: &str) -> fmt::Result {
+    if value.is_empty() {
+        f.write_str("\"\"")
+    } else {
+        write_escaped(f, value, ASSEMBLY_NAME_SPECIAL_CHARS)
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self { input, position: 0 }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.position..].chars().next()
+    }
+
+    fn peek_second(&self) -> Option<char> {
+        self.input[self.position..].chars().nth(1)
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += c.len_utf8();
+        Some(c)
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), TypeNameError> {
+        match self.next() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => Err(TypeNameError::UnexpectedChar {
+                position: self.position - c.len_utf8(),
+                found: c,
+            }),
+            None => Err(TypeNameError::UnexpectedEnd),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.next();
+        }
+    }
+
+    /// Parses a type name followed by an optional assembly name.
+    /// If `bracketed` is set, the name is a generic argument enclosed in brackets and the assembly name ends at the closing bracket.
+    fn parse_assembly_qualified_name(
+        &mut self,
+        bracketed: bool,
+    ) -> Result<AssemblyQualifiedName, TypeNameError> {
+        let type_name = self.parse_type_name()?;
+        self.skip_whitespace();
+        let assembly = if self.peek() == Some(',') {
+            self.next();
+            Some(self.parse_assembly_name(bracketed)?)
+        } else {
+            None
+        };
+        Ok(AssemblyQualifiedName {
+            type_name,
+            assembly,
+        })
+    }
+
+    fn parse_type_name(&mut self) -> Result<TypeName, TypeNameError> {
+        self.skip_whitespace();
+        let full_name = self.parse_identifier(TYPE_NAME_SPECIAL_CHARS)?;
+        let (namespace, name) = split_namespace(&full_name);
+        let mut type_name = TypeName {
+            namespace,
+            name,
+            nested: Vec::new(),
+            generic_arguments: Vec::new(),
+            modifiers: Vec::new(),
+        };
+
+        while self.peek() == Some('+') {
+            self.next();
+            type_name
+                .nested
+                .push(self.parse_identifier(TYPE_NAME_SPECIAL_CHARS)?);
+        }
+
+        if self.peek() == Some('[') && !matches!(self.peek_second(), Some(']' | ',' | '*')) {
+            self.next();
+            loop {
+                self.skip_whitespace();
+                let argument = if self.peek() == Some('[') {
+                    self.next();
+                    let argument = self.parse_assembly_qualified_name(true)?;
+                    self.skip_whitespace();
+                    self.expect(']')?;
+                    argument
+                } else {
+                    AssemblyQualifiedName::from(self.parse_type_name()?)
+                };
+                type_name.generic_arguments.push(argument);
+                self.skip_whitespace();
+                match self.next() {
+                    Some(',') => {}
+                    Some(']') => break,
+                    Some(c) => {
+                        return Err(TypeNameError::UnexpectedChar {
+                            position: self.position - c.len_utf8(),
+                            found: c,
+                        })
+                    }
+                    None => return Err(TypeNameError::UnexpectedEnd),
+                }
+            }
+
+            // formatting appends the arity to names without one, which would change the name.
+            if type_name.declared_arity() == 0 {
+                return Err(TypeNameError::ArityMismatch {
+                    declared: 0,
+                    arguments: type_name.generic_arguments.len(),
+                });
+            }
+        }
+
+        loop {
+            match self.peek() {
+                Some('*') => {
+                    self.next();
+                    type_name.modifiers.push(TypeModifier::Pointer);
+                }
+                Some('&') => {
+                    self.next();
+                    type_name.modifiers.push(TypeModifier::ByRef);
+                }
+                Some('[') => {
+                    self.next();
+                    let modifier = match self.peek() {
+                        Some(']') => TypeModifier::SzArray,
+                        Some('*') => {
+                            self.next();
+                            TypeModifier::Array { rank: 1 }
+                        }
+                        _ => {
+                            let mut rank = 1;
+                            while self.peek() == Some(',') {
+                                self.next();
+                                rank += 1;
+                            }
+                            TypeModifier::Array { rank }
+                        }
+                    };
+                    self.expect(']')?;
+                    type_name.modifiers.push(modifier);
+                }
+                _ => break,
+            }
+        }
+
+        Ok(type_name)
+    }
+
+    fn parse_assembly_name(&mut self, bracketed: bool) -> Result<AssemblyName, TypeNameError> {
+        self.skip_whitespace();
+        let mut assembly = AssemblyName::new(self.parse_identifier(ASSEMBLY_NAME_SPECIAL_CHARS)?);
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => {
+                    self.next();
+                }
+                Some(']') if bracketed => break,
+                None => break,
+                Some(c) => {
+                    return Err(TypeNameError::UnexpectedChar {
+                        position: self.position,
+                        found: c,
+                    })
+                }
+            }
+
+            self.skip_whitespace();
+            let key = self.parse_identifier(ASSEMBLY_NAME_SPECIAL_CHARS)?;
+            self.skip_whitespace();
+            if self.peek() != Some('=') {
+                return Err(TypeNameError::InvalidAssemblyProperty(key));
+            }
+            self.next();
+            self.skip_whitespace();
+            let value = self.parse_assembly_property_value()?;
+
+            if key.eq_ignore_ascii_case("Version") {
+                assembly.version = Some(value.parse()?);
+            } else if key.eq_ignore_ascii_case("Culture") {
+                assembly.culture = Some(value);
+            } else if key.eq_ignore_ascii_case("PublicKeyToken") {
+                assembly.public_key_token = Some(value.parse()?);
+            } else {
+                assembly.properties.push((key, value));
+            }
+        }
+        Ok(assembly)
+    }
+
+    fn parse_assembly_property_value(&mut self) -> Result<String, TypeNameError> {
+        match self.peek() {
+            Some(quote @ ('"' | '\'')) => {
+                self.next();
+                let mut value = String::new();
+                loop {
+                    match self.next() {
+                        Some('\\') => value.push(self.next().ok_or(TypeNameError::UnexpectedEnd)?),
+                        Some(c) if c == quote => break,
+                        Some(c) => value.push(c),
+                        None => return Err(TypeNameError::UnexpectedEnd),
+                    }
+                }
+                Ok(value)
+            }
+            _ => self.parse_identifier(ASSEMBLY_NAME_SPECIAL_CHARS),
+        }
+    }
+
+    /// Parses a (possibly escaped) identifier up to the next unescaped special character, trimming trailing whitespace.
+    fn parse_identifier(&mut self, special_chars: &[char]) -> Result<String, TypeNameError> {
+        let start = self.position;
+        let mut identifier = String::new();
+        let mut trimmed_len = 0;
+        while let Some(c) = self.peek() {
+            if c == '\\' {
+                self.next();
+                identifier.push(self.next().ok_or(TypeNameError::UnexpectedEnd)?);
+                trimmed_len = identifier.len();
+            } else if special_chars.contains(&c) || c == '[' || c == ']' {
+                break;
+            } else {
+                self.next();
+                identifier.push(c);
+                if !c.is_whitespace() {
+                    trimmed_len = identifier.len();
+                }
+            }
+        }
+        identifier.truncate(trimmed_len);
+        if identifier.is_empty() {
+            return match self.peek() {
+                Some(c) => Err(TypeNameError::UnexpectedChar {
+                    position: start.max(self.position),
+                    found: c,
+                }),
+                None => Err(TypeNameError::UnexpectedEnd),
+            };
+        }
+        Ok(identifier)
+    }
+}
+
+/// Splits a full type name at the last `.` into namespace and name.
+fn split_namespace(full_name: &str) -> (Option<String>, String) {
+    match full_name.rsplit_once('.') {
+        Some((namespace, name)) if !namespace.is_empty() && !name.is_empty() => {
+            (Some(namespace.to_owned()), name.to_owned())
+        }
+        _ => (None, full_name.to_owned()),
+    }
+}
@@ -0,0 +1,55 @@
+#![cfg(all(feature = "wrapper", feature = "netcore3_0"))]
+
+use std::{env, fs, path::PathBuf};
+
+use hostfxr_sys::{
+    app::{Argv, StartupInfo},
+    install,
+};
+
+#[test]
+fn argv_layout() {
+    let argv = Argv::new(["host", "app.dll", "--flag"]).unwrap();
+    assert_eq!(argv.argc(), 3);
+    assert_eq!(format!("{argv:?}"), r#"["host", "app.dll", "--flag"]"#);
+
+    let pointers = unsafe { std::slice::from_raw_parts(argv.as_ptr(), 4) };
+    assert!(pointers[..3].iter().all(|arg| !arg.is_null()));
+    assert!(pointers[3].is_null());
+    assert_eq!(
+        unsafe { *pointers[1] },
+        b'a' as coreclr_hosting_shared::char_t
+    );
+
+    let clone = argv.clone();
+    assert_ne!(clone.as_ptr(), argv.as_ptr());
+    assert_eq!(format!("{clone:?}"), format!("{argv:?}"));
+
+    assert_eq!(Argv::new(["a\0b"]).unwrap_err().nul_position(), 1);
+    assert!(Argv::new(Vec::<String>::new()).unwrap().is_empty());
+}
+
+#[test]
+fn startup_info_defaults() {
+    let startup_info = StartupInfo::new();
+    assert_eq!(startup_info.host_path(), env::current_exe().unwrap());
+    assert_eq!(
+        startup_info.app_path(),
+        env::current_exe().unwrap().with_extension("dll")
+    );
+
+    let startup_info = StartupInfo::new()
+        .with_host_path("/opt/app/app")
+        .with_dotnet_root("/opt/dotnet");
+    assert_eq!(startup_info.app_path(), PathBuf::from("/opt/app/app.dll"));
+    assert_eq!(startup_info.dotnet_root(), PathBuf::from("/opt/dotnet"));
+}
+
+#[test]
+fn dotnet_root_detection() {
+    let root = env::temp_dir().join(format!("hostfxr-sys-dotnet-root-{}", std::process::id()));
+    assert!(!install::is_dotnet_root(&root));
+    fs::create_dir_all(root.join("host").join("fxr")).unwrap();
+    assert!(install::is_dotnet_root(&root));
+    fs::remove_dir_all(root).unwrap();
+}
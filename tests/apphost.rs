@@ -0,0 +1,119 @@
+use std::{env, fs};
+
+use hostfxr_sys::{
+    apphost::{self, AppHostError, AppHostWriter, APP_PATH_PLACEHOLDER, MAX_APP_PATH_LENGTH},
+    bundle::{self, Bundle, BundleWriter},
+};
+
+const TEMPLATE: &[u8] = include_bytes!("fixtures/apphost/apphost");
+
+#[test]
+fn patch_app_path() {
+    let placeholder = apphost::find_placeholder(TEMPLATE).unwrap();
+    assert_eq!(placeholder, 0x40);
+
+    let image = AppHostWriter::new(TEMPLATE.to_vec())
+        .write("App.dll")
+        .unwrap();
+    assert_eq!(image.len(), TEMPLATE.len());
+    assert_eq!(apphost::find_placeholder(&image), None);
+    assert_eq!(&image[placeholder..placeholder + 8], b"App.dll\0");
+    assert!(image[placeholder..placeholder + APP_PATH_PLACEHOLDER.len()]
+        .iter()
+        .skip(7)
+        .all(|byte| *byte == 0));
+    assert_eq!(bundle::find_header_offset(&image), None);
+
+    let long_path = "a/".repeat(MAX_APP_PATH_LENGTH / 2);
+    let image = AppHostWriter::new(TEMPLATE.to_vec())
+        .write(&long_path)
+        .unwrap();
+    assert_eq!(
+        &image[placeholder..=placeholder + MAX_APP_PATH_LENGTH],
+        format!("{long_path}\0").as_bytes()
+    );
+}
+
+#[test]
+fn patch_errors() {
+    let writer = AppHostWriter::new(TEMPLATE.to_vec());
+    let too_long = "a".repeat(MAX_APP_PATH_LENGTH + 1);
+    assert_eq!(
+        writer.write(&too_long),
+        Err(AppHostError::AppPathTooLong {
+            length: MAX_APP_PATH_LENGTH + 1
+        })
+    );
+    assert_eq!(writer.write("App\0.dll"), Err(AppHostError::InvalidAppPath));
+
+    let patched = writer.write("App.dll").unwrap();
+    assert_eq!(
+        AppHostWriter::new(patched).write("App.dll"),
+        Err(AppHostError::MissingPlaceholder)
+    );
+    let mut pe = b"MZ".to_vec();
+    pe.extend_from_slice(APP_PATH_PLACEHOLDER);
+    assert_eq!(
+        AppHostWriter::new(pe).write("App.dll"),
+        Err(AppHostError::UnsupportedFormat)
+    );
+}
+
+#[test]
+fn bundle_marker() {
+    let image = AppHostWriter::new(TEMPLATE.to_vec())
+        .with_bundle_header_offset(0x1234)
+        .write("App.dll")
+        .unwrap();
+    assert_eq!(bundle::find_header_offset(&image), Some(0x1234));
+    assert_eq!(
+        AppHostWriter::new(image)
+            .with_bundle_header_offset(0x1234)
+            .write("App.dll"),
+        Err(AppHostError::MissingPlaceholder)
+    );
+    assert_eq!(
+        AppHostWriter::new(TEMPLATE.to_vec())
+            .with_bundle_header_offset(0)
+            .write("App.dll"),
+        Err(AppHostError::InvalidBundleHeaderOffset)
+    );
+
+    let apphost = AppHostWriter::new(TEMPLATE.to_vec())
+        .write("App.dll")
+        .unwrap();
+    let mut writer = BundleWriter::new(apphost);
+    writer.add_file("App.deps.json", b"{}".to_vec());
+    let bundle = Bundle::from_bytes(&writer.write().unwrap()).unwrap();
+    assert_eq!(bundle.manifest.files.len(), 1);
+}
+
+#[cfg(unix)]
+#[test]
+fn preserve_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = env::temp_dir().join(format!("hostfxr-sys-apphost-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let template = dir.join("apphost");
+    fs::write(&template, TEMPLATE).unwrap();
+    fs::set_permissions(&template, fs::Permissions::from_mode(0o750)).unwrap();
+
+    let output = dir.join("App");
+    AppHostWriter::from_file(&template)
+        .unwrap()
+        .write_to_file("App.dll", &output)
+        .unwrap();
+    let mode = fs::metadata(&output).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o750);
+
+    let output = dir.join("Other");
+    AppHostWriter::new(TEMPLATE.to_vec())
+        .write_to_file("Other.dll", &output)
+        .unwrap();
+    let mode = fs::metadata(&output).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o755);
+    assert!(fs::read(&output).unwrap().starts_with(b"\x7fELF"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
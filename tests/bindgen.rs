@@ -0,0 +1,53 @@
+use hostfxr_sys::bindgen::Bindings;
+
+#[test]
+fn generate_exports() {
+    let bindings =
+        Bindings::from_bytes(include_bytes!("fixtures/assemblies/Fixture.Exports.dll")).unwrap();
+    assert_eq!(bindings.methods().len(), 5);
+    assert_eq!(
+        bindings.generate(),
+        include_str!("fixtures/bindgen/Fixture.Exports.rs")
+    );
+}
+
+#[test]
+fn custom_crate_path() {
+    let bindings = Bindings::from_bytes(include_bytes!("fixtures/assemblies/Fixture.Exports.dll"))
+        .unwrap()
+        .with_crate_path("crate::hostfxr");
+    let source = bindings.generate();
+    assert!(source.contains("loader: &crate::hostfxr::managed::FunctionPointerLoader,"));
+    assert!(!source.contains("::hostfxr_sys"));
+}
+
+#[test]
+fn write_to_file() {
+    let bindings =
+        Bindings::from_bytes(include_bytes!("fixtures/assemblies/Fixture.Exports.dll")).unwrap();
+    let path = std::env::temp_dir().join(format!("hostfxr-bindgen-{}.rs", std::process::id()));
+    bindings.write_to_file(&path).unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), bindings.generate());
+    std::fs::remove_file(path).unwrap();
+}
+
+/// Checks that the generated bindings compile.
+#[cfg(feature = "net5_0")]
+#[allow(dead_code, clippy::pedantic)]
+mod generated {
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub struct Point {
+        pub x: f32,
+        pub y: f32,
+    }
+
+    include!("fixtures/bindgen/Fixture.Exports.rs");
+
+    #[test]
+    fn fn_types() {
+        let _: Option<fixture_exports::AddFn> = None;
+        let _: Option<fixture_exports_nested::GetVersionFn> = None;
+        assert!(fixture_exports::TYPE_NAME.starts_with("Fixture.Exports, Fixture.Exports"));
+    }
+}
@@ -0,0 +1,215 @@
+use hostfxr_sys::bundle::{
+    self, Bundle, BundleError, BundleWriter, FileLocation, FileType, Manifest,
+};
+
+macro_rules! fixture {
+    ($name:literal) => {
+        include_bytes!(concat!("fixtures/bundles/", $name))
+    };
+}
+
+#[test]
+fn header_offset() {
+    let image = fixture!("app.bundle");
+    assert_eq!(bundle::find_marker(image), Some(0x40));
+    assert_eq!(bundle::find_header_offset(image), Some(0x159));
+
+    assert_eq!(bundle::find_marker(fixture!("unbound.host")), Some(0x40));
+    assert_eq!(bundle::find_header_offset(fixture!("unbound.host")), None);
+    assert_eq!(
+        Bundle::from_bytes(fixture!("unbound.host")),
+        Err(BundleError::NotABundle)
+    );
+    assert_eq!(bundle::find_header_offset(b"\x7fELF"), None);
+}
+
+#[test]
+fn manifest() {
+    let image = fixture!("app.bundle");
+    let bundle = Bundle::from_bytes(image).unwrap();
+    let manifest = &bundle.manifest;
+    assert_eq!((manifest.major_version, manifest.minor_version), (6, 0));
+    assert_eq!(manifest.bundle_id, "fixture-bundle-id");
+    assert!(!manifest.is_netcoreapp3_compat_mode());
+
+    let files = manifest
+        .files
+        .iter()
+        .map(|file| (file.relative_path.as_str(), file.file_type))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        files,
+        [
+            ("App.dll", FileType::Assembly),
+            ("App.deps.json", FileType::DepsJson),
+            ("App.runtimeconfig.json", FileType::RuntimeConfigJson),
+            (
+                "runtimes/linux-x64/native/libnative.so",
+                FileType::NativeBinary
+            ),
+            ("App.pdb", FileType::Symbols),
+        ]
+    );
+
+    let assembly = manifest.file("App.dll").unwrap();
+    assert_eq!(assembly.offset % 16, 0);
+    assert!(!assembly.is_compressed());
+    assert_eq!(assembly.data(image).unwrap(), b"MZ app assembly");
+
+    let runtime_config = manifest.runtime_config_json_file().unwrap();
+    assert_eq!(
+        manifest.runtime_config_json,
+        Some(FileLocation {
+            offset: runtime_config.offset,
+            size: runtime_config.size
+        })
+    );
+    assert!(runtime_config
+        .data(image)
+        .unwrap()
+        .starts_with(b"{\"runtimeOptions\""));
+    assert_eq!(
+        manifest.deps_json.unwrap().offset,
+        manifest.deps_json_file().unwrap().offset
+    );
+}
+
+#[test]
+fn manifest_v1() {
+    let bundle = Bundle::from_bytes(fixture!("app-v1.bundle")).unwrap();
+    assert_eq!(bundle.manifest.major_version, 1);
+    assert_eq!(bundle.manifest.deps_json, None);
+    assert_eq!(bundle.manifest.files.len(), 1);
+    assert_eq!(bundle.manifest.files[0].compressed_size, 0);
+}
+
+#[test]
+fn invalid_manifests() {
+    let image = fixture!("app.bundle");
+    let header_offset = bundle::find_header_offset(image).unwrap();
+    for len in 0..image.len() {
+        assert!(Manifest::parse(&image[..len], header_offset).is_err());
+    }
+
+    let mut image = image.to_vec();
+    image[header_offset as usize] = 7;
+    assert_eq!(
+        Bundle::from_bytes(&image),
+        Err(BundleError::UnsupportedVersion { major: 7, minor: 0 })
+    );
+    assert_eq!(
+        Bundle::read("tests/fixtures/bundles/missing.bundle"),
+        Err(BundleError::Io(std::io::ErrorKind::NotFound))
+    );
+}
+
+#[test]
+fn round_trip() {
+    let assembly = include_bytes!("fixtures/assemblies/Fixture.Library.dll");
+    let mut writer = BundleWriter::new(fixture!("unbound.host").to_vec());
+    writer
+        .add_file(
+            "App.runtimeconfig.json",
+            b"{\"runtimeOptions\":{}}".to_vec(),
+        )
+        .add_file("Fixture.Library.dll", assembly.to_vec())
+        .add_file("App.deps.json", b"{}".to_vec())
+        .add_file(
+            r"runtimes\linux-x64\native\libnative.so",
+            b"\x7fELF".to_vec(),
+        )
+        .add_file("App.pdb", b"BSJB".to_vec())
+        .add_file("appsettings.json", b"{}".to_vec());
+    let image = writer.write().unwrap();
+
+    let bundle = Bundle::from_bytes(&image).unwrap();
+    let manifest = &bundle.manifest;
+    assert_eq!((manifest.major_version, manifest.minor_version), (6, 0));
+    assert_eq!(manifest.bundle_id.len(), 12);
+    let files = manifest
+        .files
+        .iter()
+        .map(|file| (file.relative_path.as_str(), file.file_type))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        files,
+        [
+            ("App.runtimeconfig.json", FileType::RuntimeConfigJson),
+            ("Fixture.Library.dll", FileType::Assembly),
+            ("App.deps.json", FileType::DepsJson),
+            (
+                "runtimes/linux-x64/native/libnative.so",
+                FileType::NativeBinary
+            ),
+            ("App.pdb", FileType::Symbols),
+            ("appsettings.json", FileType::Unknown),
+        ]
+    );
+
+    let file = manifest.file("Fixture.Library.dll").unwrap();
+    assert_eq!(file.offset % 16, 0);
+    assert_eq!(file.data(&image).unwrap(), assembly);
+    assert_eq!(
+        manifest.runtime_config_json_file().unwrap().data(&image),
+        Ok(&b"{\"runtimeOptions\":{}}"[..])
+    );
+    assert_eq!(
+        manifest.deps_json.unwrap().offset,
+        manifest.deps_json_file().unwrap().offset
+    );
+
+    // the bundle id is stable for the same contents
+    assert_eq!(
+        Bundle::from_bytes(&writer.write().unwrap())
+            .unwrap()
+            .manifest
+            .bundle_id,
+        manifest.bundle_id
+    );
+}
+
+#[test]
+fn round_trip_versions() {
+    for major_version in [1, 2] {
+        let mut writer = BundleWriter::new(fixture!("unbound.host").to_vec())
+            .with_version(major_version, 0)
+            .with_bundle_id("bundle")
+            .with_netcoreapp3_compat_mode(true)
+            .with_assembly_alignment(4096);
+        writer.add_file(
+            "App.dll",
+            include_bytes!("fixtures/assemblies/Fixture.Library.dll").to_vec(),
+        );
+        writer.add_file("App.deps.json", b"{}".to_vec());
+        let image = writer.write().unwrap();
+
+        let manifest = Bundle::from_bytes(&image).unwrap().manifest;
+        assert_eq!(manifest.major_version, major_version);
+        assert_eq!(manifest.bundle_id, "bundle");
+        assert_eq!(manifest.files[0].offset % 4096, 0);
+        assert_eq!(manifest.is_netcoreapp3_compat_mode(), major_version == 2);
+        assert_eq!(manifest.deps_json.is_some(), major_version == 2);
+    }
+}
+
+#[test]
+fn write_errors() {
+    let writer = BundleWriter::new(fixture!("app.bundle").to_vec());
+    assert_eq!(writer.write(), Err(BundleError::AlreadyBundled));
+    let writer = BundleWriter::new(b"\x7fELF".to_vec());
+    assert_eq!(writer.write(), Err(BundleError::MissingMarker));
+    let writer = BundleWriter::new(fixture!("unbound.host").to_vec()).with_version(3, 0);
+    assert_eq!(
+        writer.write(),
+        Err(BundleError::UnsupportedVersion { major: 3, minor: 0 })
+    );
+
+    let mut writer = BundleWriter::new(fixture!("unbound.host").to_vec());
+    writer
+        .add_file("lib/App.dll", b"MZ".to_vec())
+        .add_file(r"lib\App.dll", b"MZ".to_vec());
+    assert_eq!(
+        writer.write(),
+        Err(BundleError::DuplicateFile("lib/App.dll".to_owned()))
+    );
+}
@@ -0,0 +1,149 @@
+#![cfg(feature = "serde")]
+
+use hostfxr_sys::deps_json::{
+    self, AssetType, DepsJson, DepsJsonError, Library, RuntimeTarget, TargetLibrary,
+};
+use serde_json::{json, Value};
+
+const DEPS_PATH: &str = "tests/fixtures/depsjson/App.deps.json";
+
+#[test]
+fn read() {
+    let deps = DepsJson::read(DEPS_PATH).unwrap();
+    assert_eq!(deps.runtime_target.framework(), ".NETCoreApp,Version=v8.0");
+    assert_eq!(deps.runtime_target.rid(), None);
+    assert_eq!(
+        deps.target_libraries()
+            .map(|library| (library.name, library.version))
+            .collect::<Vec<_>>(),
+        [
+            ("App", "1.0.0"),
+            ("Newtonsoft.Json", "13.0.3"),
+            ("SQLitePCLRaw.lib.e_sqlite3", "2.1.6")
+        ]
+    );
+
+    let app = deps.find_library("app").unwrap();
+    assert!(app.library.unwrap().is_project());
+    assert_eq!(app.target.dependencies["Newtonsoft.Json"], "13.0.3");
+    assert_eq!(app.target.resources["de/App.resources.dll"].locale, "de");
+
+    let json = deps.find_library("Newtonsoft.Json").unwrap();
+    let library = json.library.unwrap();
+    assert!(library.is_package());
+    assert_eq!(library.path.as_deref(), Some("newtonsoft.json/13.0.3"));
+    let asset = &json.target.runtime["lib/net6.0/Newtonsoft.Json.dll"];
+    assert_eq!(asset.assembly_version.as_deref(), Some("13.0.0.0"));
+
+    let sqlite = deps.find_library("SQLitePCLRaw.lib.e_sqlite3").unwrap();
+    let asset = &sqlite.target.runtime_targets["runtimes/linux-x64/native/libe_sqlite3.so"];
+    assert_eq!(asset.rid, "linux-x64");
+    assert_eq!(asset.asset_type, AssetType::Native);
+    assert_eq!(asset.info.file_version.as_deref(), Some("0.0.0.0"));
+
+    assert!(deps.find_library("Analyzers").is_none());
+    assert_eq!(
+        deps.libraries["Analyzers/1.0.0"].extra["customKey"],
+        "unknown library key"
+    );
+}
+
+#[test]
+fn round_trip() {
+    let json = std::fs::read_to_string(DEPS_PATH).unwrap();
+    let deps = DepsJson::from_json(&json).unwrap();
+    let written = deps.to_json();
+    assert_eq!(DepsJson::from_json(&written).unwrap(), deps);
+
+    let original: Value = serde_json::from_str(&json).unwrap();
+    let written: Value = serde_json::from_str(&written).unwrap();
+    assert_eq!(written, original);
+}
+
+#[test]
+fn minimal_component() {
+    let mut deps = DepsJson::new(".NETCoreApp,Version=v8.0");
+    deps.add_library(
+        "Component",
+        "1.0.0",
+        Library::project(),
+        TargetLibrary::new()
+            .with_dependency("Native", "2.0.0")
+            .with_runtime_asset("Component.dll"),
+    );
+    deps.add_library(
+        "Native",
+        "2.0.0",
+        Library::package("sha512-AAAA", "native/2.0.0"),
+        TargetLibrary::new().with_native_asset("runtimes/linux-x64/native/libnative.so"),
+    );
+
+    let written: Value = serde_json::from_str(&deps.to_json()).unwrap();
+    assert_eq!(
+        written,
+        json!({
+            "runtimeTarget": { "name": ".NETCoreApp,Version=v8.0", "signature": "" },
+            "compilationOptions": {},
+            "targets": {
+                ".NETCoreApp,Version=v8.0": {
+                    "Component/1.0.0": {
+                        "dependencies": { "Native": "2.0.0" },
+                        "runtime": { "Component.dll": {} }
+                    },
+                    "Native/2.0.0": {
+                        "native": { "runtimes/linux-x64/native/libnative.so": {} }
+                    }
+                }
+            },
+            "libraries": {
+                "Component/1.0.0": { "type": "project", "serviceable": false, "sha512": "" },
+                "Native/2.0.0": {
+                    "type": "package",
+                    "serviceable": true,
+                    "sha512": "sha512-AAAA",
+                    "path": "native/2.0.0"
+                }
+            }
+        })
+    );
+}
+
+#[test]
+fn runtime_target_forms() {
+    // older files only contain the name of the runtime target.
+    let deps = DepsJson::from_json(
+        r#"{ "runtimeTarget": ".NETCoreApp,Version=v2.0/linux-x64", "targets": {}, "libraries": {} }"#,
+    )
+    .unwrap();
+    assert_eq!(
+        deps.runtime_target.name,
+        ".NETCoreApp,Version=v2.0/linux-x64"
+    );
+    assert_eq!(deps.runtime_target.framework(), ".NETCoreApp,Version=v2.0");
+    assert_eq!(deps.runtime_target.rid(), Some("linux-x64"));
+    assert_eq!(deps.runtime_target.signature, "");
+
+    // the runtime target may also be missing entirely.
+    let deps = DepsJson::from_json(r#"{ "targets": {}, "libraries": {} }"#).unwrap();
+    assert_eq!(deps.runtime_target, RuntimeTarget::default());
+    assert_eq!(deps.target_libraries().count(), 0);
+    let json: Value = serde_json::from_str(&deps.to_json()).unwrap();
+    assert_eq!(json.get("runtimeTarget"), None);
+}
+
+#[test]
+fn errors() {
+    assert_eq!(
+        DepsJson::read("tests/fixtures/depsjson/Missing.deps.json"),
+        Err(DepsJsonError::Io(std::io::ErrorKind::NotFound))
+    );
+    assert!(matches!(
+        DepsJson::from_json(r#"{ "runtimeTarget": 1 }"#),
+        Err(DepsJsonError::Json(_))
+    ));
+    assert_eq!(
+        deps_json::split_library_key("A/1.0/extra"),
+        ("A", "1.0/extra")
+    );
+    assert_eq!(deps_json::split_library_key("A"), ("A", ""));
+}
@@ -0,0 +1,312 @@
+#![cfg(feature = "serde")]
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use hostfxr_sys::{
+    deps_resolver::{self, DepsResolver, MissingAsset},
+    path_list::PathList,
+};
+use serde_json::{json, Value};
+
+fn touch(path: &Path) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, []).unwrap();
+}
+
+fn write_json(path: &Path, json: &Value) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, serde_json::to_string_pretty(json).unwrap()).unwrap();
+}
+
+fn paths(list: &PathList) -> Vec<PathBuf> {
+    list.paths().to_vec()
+}
+
+#[test]
+fn resolve() {
+    let root = env::temp_dir().join(format!("hostfxr-sys-resolver-{}", std::process::id()));
+    let app = root.join("app");
+    let packages = root.join("packages");
+    let fx = root.join("shared/Microsoft.NETCore.App/8.0.0");
+    let json_package = packages.join("newtonsoft.json/13.0.3");
+    let sqlite_package = packages.join("sqlitepclraw.lib.e_sqlite3/2.1.6");
+
+    write_json(
+        &app.join("App.deps.json"),
+        &json!({
+            "runtimeTarget": { "name": ".NETCoreApp,Version=v8.0", "signature": "" },
+            "targets": {
+                ".NETCoreApp,Version=v8.0": {
+                    "App/1.0.0": {
+                        "runtime": {
+                            "App.dll": {},
+                            "System.Runtime.dll": { "assemblyVersion": "7.0.0.0" },
+                            "System.Text.Json.dll": { "assemblyVersion": "9.0.0.0" }
+                        },
+                        "resources": { "de/App.resources.dll": { "locale": "de" } }
+                    },
+                    "Newtonsoft.Json/13.0.3": {
+                        "runtime": { "lib/net6.0/Newtonsoft.Json.dll": {} }
+                    },
+                    "SQLitePCLRaw.lib.e_sqlite3/2.1.6": {
+                        "runtimeTargets": {
+                            "runtimes/linux-x64/native/libe_sqlite3.so": { "rid": "linux-x64", "assetType": "native" },
+                            "runtimes/win-x64/native/e_sqlite3.dll": { "rid": "win-x64", "assetType": "native" }
+                        }
+                    },
+                    "Missing/1.0.0": {
+                        "runtime": { "lib/net8.0/Missing.dll": {} }
+                    }
+                }
+            },
+            "libraries": {
+                "App/1.0.0": { "type": "project", "serviceable": false, "sha512": "" },
+                "Newtonsoft.Json/13.0.3": { "type": "package", "serviceable": true, "sha512": "", "path": "newtonsoft.json/13.0.3" },
+                "SQLitePCLRaw.lib.e_sqlite3/2.1.6": { "type": "package", "serviceable": true, "sha512": "" },
+                "Missing/1.0.0": { "type": "package", "serviceable": true, "sha512": "" }
+            }
+        }),
+    );
+    for file in [
+        "App.dll",
+        "System.Runtime.dll",
+        "System.Text.Json.dll",
+        "de/App.resources.dll",
+    ] {
+        touch(&app.join(file));
+    }
+    touch(&json_package.join("lib/net6.0/Newtonsoft.Json.dll"));
+    touch(&sqlite_package.join("runtimes/linux-x64/native/libe_sqlite3.so"));
+    touch(&sqlite_package.join("runtimes/win-x64/native/e_sqlite3.dll"));
+
+    write_json(
+        &fx.join("Microsoft.NETCore.App.deps.json"),
+        &json!({
+            "runtimeTarget": { "name": ".NETCoreApp,Version=v8.0", "signature": "" },
+            "targets": {
+                ".NETCoreApp,Version=v8.0": {
+                    "Microsoft.NETCore.App.Runtime.linux-x64/8.0.0": {
+                        "runtime": {
+                            "System.Private.CoreLib.dll": { "assemblyVersion": "8.0.0.0" },
+                            "System.Runtime.dll": { "assemblyVersion": "8.0.0.0" },
+                            "System.Text.Json.dll": { "assemblyVersion": "8.0.0.0" }
+                        },
+                        "native": { "libSystem.Native.so": {} }
+                    }
+                }
+            },
+            "libraries": {
+                "Microsoft.NETCore.App.Runtime.linux-x64/8.0.0": { "type": "runtimepack", "serviceable": false, "sha512": "" }
+            }
+        }),
+    );
+    for file in [
+        "System.Private.CoreLib.dll",
+        "System.Runtime.dll",
+        "System.Text.Json.dll",
+        "libSystem.Native.so",
+    ] {
+        touch(&fx.join(file));
+    }
+
+    let assets = DepsResolver::new(app.join("App.dll"))
+        .with_framework(&fx)
+        .with_probing_path(&packages)
+        .with_rid("linux-x64")
+        .resolve()
+        .unwrap();
+
+    assert_eq!(
+        paths(&assets.trusted_platform_assemblies),
+        [
+            app.join("App.dll"),
+            // the framework wins unless the app has a higher version.
+            fx.join("System.Runtime.dll"),
+            app.join("System.Text.Json.dll"),
+            json_package.join("lib/net6.0/Newtonsoft.Json.dll"),
+            fx.join("System.Private.CoreLib.dll"),
+        ]
+    );
+    assert_eq!(
+        paths(&assets.native_dll_search_directories),
+        [
+            sqlite_package.join("runtimes/linux-x64/native"),
+            fx.clone(),
+            app.clone()
+        ]
+    );
+    assert_eq!(paths(&assets.platform_resource_roots), [app.clone(), fx]);
+    assert_eq!(
+        assets.missing_assets,
+        [MissingAsset {
+            library: "Missing/1.0.0".to_owned(),
+            path: "lib/net8.0/Missing.dll".to_owned(),
+            deps_file: app.join("App.deps.json"),
+        }]
+    );
+
+    // the host separates directories with a trailing separator.
+    let native_dirs = assets.native_dll_search_directories.join();
+    assert!(native_dirs
+        .to_string_lossy()
+        .ends_with(&format!("app{}", std::path::MAIN_SEPARATOR)));
+
+    let win_assets = DepsResolver::new(app.join("App.dll"))
+        .with_framework(root.join("shared/Microsoft.NETCore.App/8.0.0"))
+        .with_probing_path(&packages)
+        .with_rid("win-x64")
+        .resolve()
+        .unwrap();
+    let expected =
+        PathList::try_from(vec![sqlite_package.join("runtimes/win-x64/native")]).unwrap();
+    assert_eq!(
+        expected
+            .difference(&win_assets.native_dll_search_directories)
+            .count(),
+        0
+    );
+    assert_eq!(
+        win_assets
+            .native_dll_search_directories
+            .difference(&assets.native_dll_search_directories)
+            .collect::<Vec<_>>(),
+        [&sqlite_package.join("runtimes/win-x64/native/")]
+    );
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn resolve_app_local_rid_specific_assets() {
+    // a portable app published with `dotnet publish` keeps the `runtimes/<rid>/` layout next to the app.
+    let app = env::temp_dir().join(format!(
+        "hostfxr-sys-resolver-rid-local-{}",
+        std::process::id()
+    ));
+    write_json(
+        &app.join("App.deps.json"),
+        &json!({
+            "runtimeTarget": { "name": ".NETCoreApp,Version=v8.0", "signature": "" },
+            "targets": {
+                ".NETCoreApp,Version=v8.0": {
+                    "App/1.0.0": { "runtime": { "App.dll": {} } },
+                    "SQLitePCLRaw.lib.e_sqlite3/2.1.6": {
+                        "runtimeTargets": {
+                            "runtimes/linux-x64/native/libe_sqlite3.so": { "rid": "linux-x64", "assetType": "native" },
+                            "runtimes/win-x64/native/e_sqlite3.dll": { "rid": "win-x64", "assetType": "native" }
+                        }
+                    },
+                    "System.IO.Ports/8.0.0": {
+                        "runtime": { "lib/net8.0/System.IO.Ports.dll": {} },
+                        "runtimeTargets": {
+                            "runtimes/unix/lib/net8.0/System.IO.Ports.dll": { "rid": "unix", "assetType": "runtime" },
+                            "runtimes/win/lib/net8.0/System.IO.Ports.dll": { "rid": "win", "assetType": "runtime" }
+                        }
+                    }
+                }
+            },
+            "libraries": {
+                "App/1.0.0": { "type": "project", "serviceable": false, "sha512": "" },
+                "SQLitePCLRaw.lib.e_sqlite3/2.1.6": { "type": "package", "serviceable": true, "sha512": "" },
+                "System.IO.Ports/8.0.0": { "type": "package", "serviceable": true, "sha512": "" }
+            }
+        }),
+    );
+    for file in [
+        "App.dll",
+        "System.IO.Ports.dll",
+        "runtimes/linux-x64/native/libe_sqlite3.so",
+        "runtimes/win-x64/native/e_sqlite3.dll",
+        "runtimes/unix/lib/net8.0/System.IO.Ports.dll",
+        "runtimes/win/lib/net8.0/System.IO.Ports.dll",
+    ] {
+        touch(&app.join(file));
+    }
+
+    let assets = DepsResolver::new(app.join("App.dll"))
+        .with_rid("linux-x64")
+        .resolve()
+        .unwrap();
+    assert_eq!(
+        paths(&assets.trusted_platform_assemblies),
+        [
+            app.join("App.dll"),
+            app.join("runtimes/unix/lib/net8.0/System.IO.Ports.dll")
+        ]
+    );
+    assert_eq!(
+        paths(&assets.native_dll_search_directories),
+        [app.join("runtimes/linux-x64/native"), app.clone()]
+    );
+    assert!(assets.missing_assets.is_empty());
+
+    // RID-specific assets are not looked up by their file name.
+    fs::remove_file(app.join("runtimes/linux-x64/native/libe_sqlite3.so")).unwrap();
+    touch(&app.join("libe_sqlite3.so"));
+    let assets = DepsResolver::new(app.join("App.dll"))
+        .with_rid("linux-x64")
+        .resolve()
+        .unwrap();
+    assert_eq!(
+        assets.missing_assets,
+        [MissingAsset {
+            library: "SQLitePCLRaw.lib.e_sqlite3/2.1.6".to_owned(),
+            path: "runtimes/linux-x64/native/libe_sqlite3.so".to_owned(),
+            deps_file: app.join("App.deps.json"),
+        }]
+    );
+
+    fs::remove_dir_all(app).unwrap();
+}
+
+#[test]
+fn resolve_without_deps_file() {
+    let app = env::temp_dir().join(format!(
+        "hostfxr-sys-resolver-nodeps-{}",
+        std::process::id()
+    ));
+    for file in ["App.dll", "Library.dll", "libnative.so"] {
+        touch(&app.join(file));
+    }
+
+    let assets = DepsResolver::new(app.join("App.dll")).resolve().unwrap();
+    assert_eq!(
+        paths(&assets.trusted_platform_assemblies),
+        [app.join("App.dll"), app.join("Library.dll")]
+    );
+    assert_eq!(
+        paths(&assets.native_dll_search_directories),
+        [app.as_path()]
+    );
+    assert!(assets.missing_assets.is_empty());
+
+    fs::remove_dir_all(app).unwrap();
+}
+
+#[test]
+fn rid_fallbacks() {
+    assert_eq!(
+        deps_resolver::portable_rid_fallbacks("linux-x64"),
+        ["linux-x64", "linux", "unix-x64", "unix", "any", "base"]
+    );
+    assert_eq!(
+        deps_resolver::portable_rid_fallbacks("linux-musl-arm64"),
+        [
+            "linux-musl-arm64",
+            "linux-musl",
+            "linux-arm64",
+            "linux",
+            "unix-arm64",
+            "unix",
+            "any",
+            "base"
+        ]
+    );
+    assert_eq!(
+        deps_resolver::portable_rid_fallbacks("win-x86"),
+        ["win-x86", "win", "any", "base"]
+    );
+}
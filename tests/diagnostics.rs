@@ -0,0 +1,201 @@
+use std::path::PathBuf;
+
+use hostfxr_sys::diagnostics::{FrameworkVersionLocation, HostingDiagnostic};
+
+macro_rules! sample {
+    ($name:literal) => {
+        include_str!(concat!("fixtures/diagnostics/", $name, ".txt"))
+    };
+}
+
+/// Simulates the hosting components reporting each line through a separate error writer call.
+fn parse_lines(sample: &str) -> Vec<HostingDiagnostic> {
+    HostingDiagnostic::parse(sample.lines())
+}
+
+#[test]
+fn missing_framework() {
+    let diagnostics = parse_lines(sample!("missing_framework_net8"));
+    assert_eq!(
+        diagnostics,
+        [HostingDiagnostic::MissingFramework {
+            name: "Microsoft.NETCore.App".to_owned(),
+            version: "8.0.0".to_owned(),
+            architecture: Some("x64".to_owned()),
+            app_path: Some(PathBuf::from("/home/user/app/app.dll")),
+            dotnet_root: Some(PathBuf::from("/usr/share/dotnet")),
+            found_versions: vec![
+                FrameworkVersionLocation {
+                    version: "6.0.25".to_owned(),
+                    path: PathBuf::from("/usr/share/dotnet/shared/Microsoft.NETCore.App"),
+                },
+                FrameworkVersionLocation {
+                    version: "7.0.14".to_owned(),
+                    path: PathBuf::from("/usr/share/dotnet/shared/Microsoft.NETCore.App"),
+                },
+            ],
+        }]
+    );
+}
+
+#[test]
+fn missing_framework_legacy_format() {
+    let diagnostics = HostingDiagnostic::parse([sample!("missing_framework_netcore3")]);
+    assert_eq!(
+        diagnostics,
+        [HostingDiagnostic::MissingFramework {
+            name: "Microsoft.AspNetCore.App".to_owned(),
+            version: "3.1.0".to_owned(),
+            architecture: Some("x64".to_owned()),
+            app_path: None,
+            dotnet_root: None,
+            found_versions: vec![
+                FrameworkVersionLocation {
+                    version: "2.1.30".to_owned(),
+                    path: PathBuf::from(r"C:\Program Files\dotnet\shared\Microsoft.AspNetCore.App"),
+                },
+                FrameworkVersionLocation {
+                    version: "2.2.8".to_owned(),
+                    path: PathBuf::from(r"C:\Program Files\dotnet\shared\Microsoft.AspNetCore.App"),
+                },
+            ],
+        }]
+    );
+}
+
+#[test]
+fn missing_hostpolicy() {
+    let diagnostics = parse_lines(sample!("missing_hostpolicy"));
+    assert_eq!(
+        diagnostics,
+        [HostingDiagnostic::MissingHostpolicy {
+            library: "libhostpolicy.so".to_owned(),
+            directory: Some(PathBuf::from("/home/user/app/")),
+            runtime_config: Some(PathBuf::from("/home/user/app/app.runtimeconfig.json")),
+        }]
+    );
+}
+
+#[test]
+fn invalid_runtime_config() {
+    let diagnostics = parse_lines(sample!("invalid_runtimeconfig"));
+    assert_eq!(
+        diagnostics,
+        [
+            HostingDiagnostic::InvalidJson {
+                path: PathBuf::from("/home/user/app/app.runtimeconfig.json"),
+                line: Some(4),
+                column: Some(5),
+                message: "Missing a name for object member.".to_owned(),
+            },
+            HostingDiagnostic::InvalidRuntimeConfig {
+                path: PathBuf::from("/home/user/app/app.runtimeconfig.json"),
+                dev_path: Some(PathBuf::from("/home/user/app/app.runtimeconfig.dev.json")),
+            },
+        ]
+    );
+}
+
+#[test]
+fn missing_runtime_config() {
+    let diagnostics = parse_lines(sample!("missing_runtimeconfig"));
+    assert_eq!(
+        diagnostics,
+        [
+            HostingDiagnostic::UnreadableFile {
+                path: PathBuf::from("/home/user/app/missing.runtimeconfig.json"),
+                reason: "No such file or directory".to_owned(),
+            },
+            HostingDiagnostic::InvalidRuntimeConfig {
+                path: PathBuf::from("/home/user/app/missing.runtimeconfig.json"),
+                dev_path: Some(PathBuf::from(
+                    "/home/user/app/missing.runtimeconfig.dev.json"
+                )),
+            },
+        ]
+    );
+}
+
+#[test]
+fn incompatible_config() {
+    let diagnostics = parse_lines(sample!("incompatible_config"));
+    assert_eq!(
+        diagnostics,
+        [
+            HostingDiagnostic::IncompatibleConfig {
+                framework: "Microsoft.AspNetCore.App".to_owned(),
+                requested_version: None,
+                loaded_version: None,
+            },
+            HostingDiagnostic::IncompatibleConfig {
+                framework: "Microsoft.NETCore.App".to_owned(),
+                requested_version: Some("9.0.0".to_owned()),
+                loaded_version: Some("8.0.10".to_owned()),
+            },
+        ]
+    );
+}
+
+#[test]
+fn missing_dependency() {
+    let diagnostics = parse_lines(sample!("missing_dependency"));
+    assert_eq!(
+        diagnostics,
+        [HostingDiagnostic::MissingDependency {
+            manifest: "app.deps.json".to_owned(),
+            package: Some("Newtonsoft.Json".to_owned()),
+            version: Some("13.0.3".to_owned()),
+            path: Some("lib/net6.0/Newtonsoft.Json.dll".to_owned()),
+        }]
+    );
+}
+
+#[test]
+fn missing_sdk() {
+    let diagnostics = parse_lines(sample!("missing_sdk"));
+    assert_eq!(
+        diagnostics,
+        [HostingDiagnostic::MissingSdk {
+            requested_version: Some("9.0.100".to_owned()),
+            global_json: Some(PathBuf::from("/home/user/repo/global.json")),
+        }]
+    );
+}
+
+#[test]
+fn single_line_errors() {
+    let diagnostics = parse_lines(sample!("misc"));
+    assert_eq!(
+        diagnostics,
+        [
+            HostingDiagnostic::AppNotFound {
+                path: PathBuf::from("/home/user/app/missing.dll"),
+            },
+            HostingDiagnostic::SelfContainedComponent,
+            HostingDiagnostic::CoreClrNotFound,
+            HostingDiagnostic::CoreClrInitFailure {
+                hresult: 0x8000_4005,
+            },
+            HostingDiagnostic::Other {
+                message: "Something completely different happened.".to_owned(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn consecutive_errors() {
+    let diagnostics = HostingDiagnostic::parse([
+        sample!("missing_dependency"),
+        sample!("missing_hostpolicy"),
+        sample!("missing_framework_net8"),
+    ]);
+    assert!(matches!(
+        diagnostics.as_slice(),
+        [
+            HostingDiagnostic::MissingDependency { .. },
+            HostingDiagnostic::MissingHostpolicy { .. },
+            HostingDiagnostic::MissingFramework { .. },
+        ]
+    ));
+}
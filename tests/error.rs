@@ -0,0 +1,41 @@
+use coreclr_hosting_shared::StatusCode;
+use hostfxr_sys::error::{ExitCode, HostingError};
+
+#[test]
+#[allow(clippy::cast_possible_wrap)]
+fn exit_code_or_hosting_error() {
+    assert_eq!(
+        HostingError::check_exit_code(0, Vec::new()),
+        Ok(ExitCode::SUCCESS)
+    );
+    assert_eq!(
+        HostingError::check_exit_code(-1, vec!["unrelated".to_owned()]),
+        Ok(ExitCode::new(-1))
+    );
+
+    let code = StatusCode::FrameworkMissingFailure as u32 as i32;
+    assert_eq!(
+        HostingError::check_exit_code(code, Vec::new()),
+        Ok(ExitCode::new(code))
+    );
+    let err = HostingError::check_exit_code(
+        code,
+        vec!["You must install or update .NET to run this application.".to_owned()],
+    )
+    .unwrap_err();
+    assert!(err.is(StatusCode::FrameworkMissingFailure));
+    assert_eq!(err.messages().len(), 1);
+}
+
+#[test]
+fn process_exit_code() {
+    assert_eq!(
+        std::process::ExitCode::from(ExitCode::new(3)),
+        std::process::ExitCode::from(3)
+    );
+    assert_eq!(
+        std::process::ExitCode::from(ExitCode::new(256)),
+        std::process::ExitCode::FAILURE
+    );
+    assert!(!ExitCode::new(-1).is_success());
+}
@@ -0,0 +1,30 @@
+"""Generates a minimal stand-in for the apphost template shipped with the .NET SDK (Microsoft.NETCore.App.Host).
+
+The template embeds the path of the app in a 1025 byte buffer initialized with the SHA-256 hash of "foobar"
+(see `EMBED_HASH_FULL_UTF8` in `corehost.cpp`) and an unbound bundle marker (see `bundle_marker.cpp`).
+"""
+
+import hashlib
+import struct
+
+APP_PATH_PLACEHOLDER = hashlib.sha256(b"foobar").hexdigest().encode()
+BUNDLE_SIGNATURE = bytes.fromhex("8b1202b96a612038727b930214d7a03213f5b9e6efae3318ee3b2dce24b36aae")
+EMBED_MAX = 1025
+
+
+def apphost():
+    image = b"\x7fELF" + b"\0" * 60
+    image += APP_PATH_PLACEHOLDER + b"\0" * (EMBED_MAX - len(APP_PATH_PLACEHOLDER))
+    image += b"\0" * (-len(image) % 8)
+    image += struct.pack("<q", 0) + BUNDLE_SIGNATURE
+    return image + b"\0" * 32
+
+
+FIXTURES = {
+    "apphost": apphost,
+}
+
+if __name__ == "__main__":
+    for name, build in FIXTURES.items():
+        with open(name, "wb") as file:
+            file.write(build())
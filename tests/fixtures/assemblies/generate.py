@@ -0,0 +1,388 @@
+"""Generates the minimal ECMA-335 assemblies used as test fixtures.
+
+The images contain just enough of the PE/COFF headers, the CLI header and the metadata tables
+(laid out the way the C# compiler emits them) to exercise the metadata reader, so that no .NET SDK
+is needed to regenerate them. Run `python3 generate.py` in this directory to update them.
+"""
+
+import struct
+
+# Element types (ECMA-335 II.23.1.16)
+VOID, BOOLEAN, CHAR, I1, U1, I2, U2, I4, U4, I8, U8, R4, R8, STRING = range(0x01, 0x0F)
+PTR, VALUETYPE, CLASS = 0x0F, 0x11, 0x12
+I, U, FNPTR, OBJECT = 0x18, 0x19, 0x1B, 0x1C
+
+T_MODULE, T_TYPEREF, T_TYPEDEF, T_FIELD, T_METHODDEF, T_PARAM = 0x00, 0x01, 0x02, 0x04, 0x06, 0x08
+T_MEMBERREF, T_CUSTOMATTRIBUTE, T_ASSEMBLY, T_ASSEMBLYREF, T_NESTEDCLASS = 0x0A, 0x0C, 0x20, 0x23, 0x29
+
+SCHEMAS = {
+    T_MODULE: "HSGGG",
+    T_TYPEREF: "ISS",
+    T_TYPEDEF: "LSSIII",
+    T_FIELD: "HSB",
+    T_METHODDEF: "LHHSBI",
+    T_PARAM: "HHS",
+    T_MEMBERREF: "ISB",
+    T_CUSTOMATTRIBUTE: "IIB",
+    T_ASSEMBLY: "LHHHHLBSS",
+    T_ASSEMBLYREF: "HHHHLBSSB",
+    T_NESTEDCLASS: "II",
+}
+
+
+def compressed(value):
+    if value < 0x80:
+        return bytes([value])
+    if value < 0x4000:
+        return struct.pack(">H", value | 0x8000)
+    return struct.pack(">I", value | 0xC0000000)
+
+
+def ser_string(value):
+    if value is None:
+        return b"\xff"
+    data = value.encode()
+    return compressed(len(data)) + data
+
+
+def align(data, alignment):
+    return data + b"\0" * (-len(data) % alignment)
+
+
+class Heap:
+    def __init__(self, initial):
+        self.data = bytearray(initial)
+        self.index = {}
+
+    def add(self, value, encoded):
+        if not value:
+            return 0
+        if value not in self.index:
+            self.index[value] = len(self.data)
+            self.data += encoded
+        return self.index[value]
+
+
+class Assembly:
+    def __init__(self, name, version=(1, 0, 0, 0), culture="", public_key=b"",
+                 runtime_version="v4.0.30319", flags=0):
+        self.strings = Heap(b"\0")
+        self.blobs = Heap(b"\0")
+        self.tables = {table: [] for table in SCHEMAS}
+        self.types = []  # (flags, name, namespace, extends, methods, fields)
+        self.attributes = []
+        self.tables[T_MODULE].append((0, self.string(name + ".dll"), 1, 0, 0))
+        self.tables[T_ASSEMBLY].append((0x8004, *version, flags | (1 if public_key else 0),
+                                        self.blob(public_key), self.string(name), self.string(culture)))
+        self.runtime_version = runtime_version
+        self.types.append([0, "<Module>", "", 0, [], []])
+
+    def string(self, value):
+        return self.strings.add(value, value.encode() + b"\0")
+
+    def blob(self, value):
+        return self.blobs.add(bytes(value), compressed(len(value)) + bytes(value))
+
+    def assembly_ref(self, name, version=(8, 0, 0, 0), token=bytes.fromhex("b03f5f7f11d50a3a")):
+        self.tables[T_ASSEMBLYREF].append((*version, 0, self.blob(token), self.string(name), 0, 0))
+        return len(self.tables[T_ASSEMBLYREF])
+
+    def type_ref(self, assembly_ref, namespace, name):
+        """Returns the TypeDefOrRef coded index of a new TypeRef."""
+        self.tables[T_TYPEREF].append(((assembly_ref << 2) | 2, self.string(name), self.string(namespace)))
+        return (len(self.tables[T_TYPEREF]) << 2) | 1
+
+    def type_def(self, namespace, name, flags=0x00100001, extends=0, enclosing=None):
+        """Returns the 1-based TypeDef row of a new type."""
+        self.types.append([flags, name, namespace, extends, [], []])
+        row = len(self.types)
+        if enclosing is not None:
+            self.tables[T_NESTEDCLASS].append((row, enclosing))
+        return row
+
+    def type_def_or_ref(self, row):
+        """Returns the TypeDefOrRef coded index of a TypeDef row."""
+        return row << 2
+
+    def field(self, type_row, name, signature, flags=0x0006):
+        self.types[type_row - 1][5].append((flags, name, signature))
+
+    def method(self, type_row, name, signature, flags=0x0096, body=b"\x2a", params=()):
+        """Adds a method and returns a handle that can be used as the parent of a custom attribute."""
+        methods = self.types[type_row - 1][4]
+        methods.append((flags, name, signature, body, params))
+        return (type_row, len(methods) - 1)
+
+    def member_ref(self, parent_type_ref, name, signature):
+        """Returns the CustomAttributeType coded index of a new MemberRef on a TypeRef."""
+        self.tables[T_MEMBERREF].append((((parent_type_ref >> 2) << 3) | 1, self.string(name), self.blob(signature)))
+        return (len(self.tables[T_MEMBERREF]) << 3) | 3
+
+    def custom_attribute(self, parent, constructor, value):
+        """`parent` is either "assembly", ("type", TypeDef row) or ("method", method handle)."""
+        self.attributes.append((parent, constructor, value))
+
+    def build(self, machine=0x14C, pe32_plus=False, cli_flags=1):
+        # Lay out fields and methods in type order.
+        method_rows = {}
+        code = bytearray()
+        body_rvas = []
+        text_rva = 0x2000
+        cli_header_size = 72
+        for type_index, (flags, name, namespace, extends, methods, fields) in enumerate(self.types):
+            field_list = len(self.tables[T_FIELD]) + 1
+            method_list = len(self.tables[T_METHODDEF]) + 1
+            for field_flags, field_name, signature in fields:
+                self.tables[T_FIELD].append((field_flags, self.string(field_name), self.blob(signature)))
+            for method_index, (method_flags, method_name, signature, body, params) in enumerate(methods):
+                code = align(code, 4)
+                rva = text_rva + cli_header_size + len(code)
+                code += bytes([(len(body) << 2) | 2]) + body
+                param_list = len(self.tables[T_PARAM]) + 1
+                for sequence, param_name in enumerate(params, 1):
+                    self.tables[T_PARAM].append((0, sequence, self.string(param_name)))
+                self.tables[T_METHODDEF].append((rva, 0, method_flags, self.string(method_name),
+                                                 self.blob(signature), param_list))
+                method_rows[(type_index + 1, method_index)] = len(self.tables[T_METHODDEF])
+            self.tables[T_TYPEDEF].append((flags, self.string(name), self.string(namespace), extends,
+                                           field_list, method_list))
+
+        for parent, constructor, value in self.attributes:
+            if parent == "assembly":
+                coded = (1 << 5) | 14
+            elif parent[0] == "type":
+                coded = (parent[1] << 5) | 3
+            else:
+                coded = (method_rows[parent[1]] << 5) | 0
+            self.tables[T_CUSTOMATTRIBUTE].append((coded, constructor, self.blob(value)))
+        self.tables[T_CUSTOMATTRIBUTE].sort(key=lambda row: row[0])
+        self.tables[T_NESTEDCLASS].sort(key=lambda row: row[0])
+
+        metadata = self.build_metadata()
+        code = align(code, 4)
+        metadata_rva = text_rva + cli_header_size + len(code)
+        cli_header = struct.pack("<IHHIIII", 72, 2, 5, metadata_rva, len(metadata), cli_flags, 0) + b"\0" * 48
+        text = cli_header + code + metadata
+        return build_pe(text, text_rva, cli=(text_rva, 72), machine=machine, pe32_plus=pe32_plus)
+
+    def build_metadata(self):
+        tables = bytearray(struct.pack("<IBBBB", 0, 2, 0, 0, 1))
+        valid = sum(1 << table for table, rows in self.tables.items() if rows)
+        tables += struct.pack("<QQ", valid, 0x000016003301FA00)
+        for table in sorted(self.tables):
+            if self.tables[table]:
+                tables += struct.pack("<I", len(self.tables[table]))
+        for table in sorted(self.tables):
+            for row in self.tables[table]:
+                for column, value in zip(SCHEMAS[table], row):
+                    if column == "L":
+                        tables += struct.pack("<I", value)
+                    else:
+                        assert value < 0x10000, "only small indices are supported"
+                        tables += struct.pack("<H", value)
+        tables = align(tables, 4)
+
+        guids = bytes(range(16))
+        streams = [
+            (b"#~", bytes(tables)),
+            (b"#Strings", align(bytes(self.strings.data), 4)),
+            (b"#US", align(b"\0", 4)),
+            (b"#GUID", guids),
+            (b"#Blob", align(bytes(self.blobs.data), 4)),
+        ]
+        version = align(self.runtime_version.encode() + b"\0", 4)
+        header_size = 16 + len(version) + 4 + sum(8 + len(align(name + b"\0", 4)) for name, _ in streams)
+        root = bytearray(struct.pack("<IHHII", 0x424A5342, 1, 1, 0, len(version)) + version)
+        root += struct.pack("<HH", 0, len(streams))
+        offset = header_size
+        for name, data in streams:
+            root += struct.pack("<II", offset, len(data)) + align(name + b"\0", 4)
+            offset += len(data)
+        for _, data in streams:
+            root += data
+        return bytes(root)
+
+
+def build_pe(text, text_rva=0x2000, cli=(0, 0), machine=0x14C, pe32_plus=False):
+    file_alignment = 0x200
+    dos = bytearray(b"MZ" + b"\0" * 0x3A + struct.pack("<I", 0x80))
+    dos = align(dos, 0x80)
+    optional_size = 0xF0 if pe32_plus else 0xE0
+    coff = struct.pack("<HHIIIHH", machine, 1, 0, 0, 0, optional_size, 0x2022 if pe32_plus else 0x2102)
+    headers_size = align(bytes(len(dos) + 4 + 20 + optional_size + 40), file_alignment)
+    image_size = text_rva + ((len(text) + 0x1FFF) & ~0x1FFF)
+    if pe32_plus:
+        optional = struct.pack("<HBBIIIII", 0x20B, 48, 0, len(align(text, file_alignment)), 0, 0, 0, text_rva)
+        optional += struct.pack("<QII", 0x180000000, 0x2000, file_alignment)
+    else:
+        optional = struct.pack("<HBBIIIIII", 0x10B, 48, 0, len(align(text, file_alignment)), 0, 0, 0, text_rva, 0)
+        optional += struct.pack("<III", 0x10000000, 0x2000, file_alignment)
+    optional += struct.pack("<HHHHHHIIIIHH", 4, 0, 0, 0, 4, 0, 0, image_size, len(headers_size), 0, 3, 0x8560)
+    if pe32_plus:
+        optional += struct.pack("<QQQQII", 0x400000, 0x4000, 0x100000, 0x2000, 0, 16)
+    else:
+        optional += struct.pack("<IIIIII", 0x100000, 0x1000, 0x100000, 0x1000, 0, 16)
+    directories = [(0, 0)] * 16
+    directories[14] = cli
+    for rva, size in directories:
+        optional += struct.pack("<II", rva, size)
+    assert len(optional) == optional_size
+    section = struct.pack("<8sIIIIIIHHI", b".text", len(text), text_rva, len(align(text, file_alignment)),
+                          len(headers_size), 0, 0, 0, 0, 0x60000020)
+    headers = align(bytes(dos) + b"PE\0\0" + coff + optional + section, file_alignment)
+    return headers + align(text, file_alignment)
+
+
+ECMA_PUBLIC_KEY = bytes.fromhex("00000000000000000400000000000000")
+
+
+def method_signature(return_type, *parameters):
+    return bytes([0x00, len(parameters)]) + bytes(return_type) + b"".join(bytes(p) for p in parameters)
+
+
+def unmanaged_callers_only(entry_point=None, calling_conventions=()):
+    named = []
+    if entry_point is not None:
+        named.append(bytes([0x53, STRING]) + ser_string("EntryPoint") + ser_string(entry_point))
+    if calling_conventions:
+        named.append(bytes([0x53, 0x1D, 0x50]) + ser_string("CallConvs")
+                     + struct.pack("<I", len(calling_conventions))
+                     + b"".join(ser_string(c) for c in calling_conventions))
+    return struct.pack("<HH", 1, len(named)) + b"".join(named)
+
+
+def library():
+    """A strong-named library without any types besides `<Module>`."""
+    return Assembly("Fixture.Library", (1, 2, 3, 4), public_key=ECMA_PUBLIC_KEY).build()
+
+
+def satellite():
+    """A satellite assembly with a culture, built for x64."""
+    assembly = Assembly("Fixture.Library.resources", (1, 2, 3, 4), culture="de-DE")
+    return assembly.build(machine=0x8664, pe32_plus=True)
+
+
+def exports():
+    """A library exposing methods marked with `[UnmanagedCallersOnly]`, equivalent to:
+
+    namespace Fixture;
+    public struct Point { public int X; public int Y; }
+    public static class Exports {
+        [UnmanagedCallersOnly(EntryPoint = "fixture_add")]
+        public static int Add(int a, int b) => a + b;
+        [UnmanagedCallersOnly]
+        public static void Log(byte* message, nuint length) { }
+        [UnmanagedCallersOnly(CallConvs = new[] { typeof(CallConvCdecl) })]
+        public static int Register(delegate* unmanaged<int, void> callback, void* context) => 0;
+        [UnmanagedCallersOnly]
+        public static Point Translate(Point point, float dx) => point;
+        public static int Helper(int value) => value;
+        public static class Nested {
+            [UnmanagedCallersOnly(EntryPoint = "fixture_version")]
+            public static nint GetVersion() => 0;
+        }
+    }
+    """
+    assembly = Assembly("Fixture.Exports", (1, 0, 0, 0))
+    runtime = assembly.assembly_ref("System.Runtime")
+    object_ = assembly.type_ref(runtime, "System", "Object")
+    value_type = assembly.type_ref(runtime, "System", "ValueType")
+    attribute = assembly.type_ref(runtime, "System.Runtime.InteropServices", "UnmanagedCallersOnlyAttribute")
+    constructor = assembly.member_ref(attribute, ".ctor", bytes([0x20, 0x00, VOID]))
+
+    point = assembly.type_def("Fixture", "Point", flags=0x00100109, extends=value_type)
+    assembly.field(point, "X", bytes([0x06, I4]))
+    assembly.field(point, "Y", bytes([0x06, I4]))
+    point_type = [VALUETYPE, *compressed(assembly.type_def_or_ref(point))]
+
+    exports = assembly.type_def("Fixture", "Exports", flags=0x00100181, extends=object_)
+    add = assembly.method(exports, "Add", method_signature([I4], [I4], [I4]), body=b"\x02\x03\x58\x2a",
+                          params=("a", "b"))
+    log = assembly.method(exports, "Log", method_signature([VOID], [PTR, U1], [U]), params=("message", "length"))
+    register = assembly.method(exports, "Register",
+                               method_signature([I4], [FNPTR, 0x09, 1, VOID, I4], [PTR, VOID]),
+                               body=b"\x16\x2a", params=("callback", "context"))
+    translate = assembly.method(exports, "Translate", method_signature(point_type, point_type, [R4]),
+                                body=b"\x02\x2a", params=("point", "dx"))
+    assembly.method(exports, "Helper", method_signature([I4], [I4]), body=b"\x02\x2a", params=("value",))
+
+    nested = assembly.type_def("", "Nested", flags=0x00100182, extends=object_, enclosing=exports)
+    get_version = assembly.method(nested, "GetVersion", method_signature([I]), body=b"\x16\xd3\x2a")
+
+    assembly.custom_attribute(("method", add), constructor, unmanaged_callers_only("fixture_add"))
+    assembly.custom_attribute(("method", log), constructor, unmanaged_callers_only())
+    assembly.custom_attribute(("method", register), constructor, unmanaged_callers_only(
+        calling_conventions=["System.Runtime.CompilerServices.CallConvCdecl, System.Runtime, Version=8.0.0.0, "
+                             "Culture=neutral, PublicKeyToken=b03f5f7f11d50a3a"]))
+    assembly.custom_attribute(("method", translate), constructor, unmanaged_callers_only())
+    assembly.custom_attribute(("method", get_version), constructor, unmanaged_callers_only("fixture_version"))
+    return assembly.build()
+
+
+def target_framework(assembly, runtime, framework_name, display_name):
+    """Applies `[assembly: TargetFramework(framework_name, FrameworkDisplayName = display_name)]`."""
+    attribute = assembly.type_ref(runtime, "System.Runtime.Versioning", "TargetFrameworkAttribute")
+    constructor = assembly.member_ref(attribute, ".ctor", bytes([0x20, 0x01, VOID, STRING]))
+    value = struct.pack("<H", 1) + ser_string(framework_name) + struct.pack("<H", 1)
+    value += bytes([0x54, STRING]) + ser_string("FrameworkDisplayName") + ser_string(display_name)
+    assembly.custom_attribute("assembly", constructor, value)
+
+
+def net8():
+    """A library targeting .NET 8."""
+    assembly = Assembly("Fixture.Net8", (1, 0, 0, 0))
+    runtime = assembly.assembly_ref("System.Runtime")
+    target_framework(assembly, runtime, ".NETCoreApp,Version=v8.0", ".NET 8.0")
+    return assembly.build()
+
+
+def net10():
+    """A library targeting .NET 10."""
+    assembly = Assembly("Fixture.Net10", (1, 0, 0, 0))
+    runtime = assembly.assembly_ref("System.Runtime")
+    target_framework(assembly, runtime, ".NETCoreApp,Version=v10.0", ".NET 10.0")
+    return assembly.build()
+
+
+def reference():
+    """A reference assembly as produced by `ProduceReferenceAssembly`, targeting .NET 8."""
+    assembly = Assembly("Fixture.Reference", (1, 0, 0, 0))
+    runtime = assembly.assembly_ref("System.Runtime")
+    target_framework(assembly, runtime, ".NETCoreApp,Version=v8.0", ".NET 8.0")
+    attribute = assembly.type_ref(runtime, "System.Runtime.CompilerServices", "ReferenceAssemblyAttribute")
+    constructor = assembly.member_ref(attribute, ".ctor", bytes([0x20, 0x00, VOID]))
+    assembly.custom_attribute("assembly", constructor, struct.pack("<HH", 1, 0))
+    return assembly.build()
+
+
+def x86_only():
+    """An IL-only library marked as requiring a 32-bit process (`<PlatformTarget>x86</PlatformTarget>`)."""
+    return Assembly("Fixture.X86", (1, 0, 0, 0)).build(cli_flags=0x3)
+
+
+def mixed_arm64():
+    """A mixed-mode (C++/CLI) library built for arm64, i.e. without `COMIMAGE_FLAGS_ILONLY`."""
+    return Assembly("Fixture.Mixed", (1, 0, 0, 0)).build(machine=0xAA64, pe32_plus=True, cli_flags=0)
+
+
+def native():
+    """A native PE image without a CLI header."""
+    return build_pe(b"\xc3" + b"\0" * 15, machine=0x8664, pe32_plus=True)
+
+
+FIXTURES = {
+    "Fixture.Library.dll": library,
+    "Fixture.Library.resources.dll": satellite,
+    "Fixture.Exports.dll": exports,
+    "Fixture.Net8.dll": net8,
+    "Fixture.Net10.dll": net10,
+    "Fixture.Reference.dll": reference,
+    "Fixture.X86.dll": x86_only,
+    "Fixture.Mixed.dll": mixed_arm64,
+    "Fixture.Native.dll": native,
+}
+
+if __name__ == "__main__":
+    for name, build in FIXTURES.items():
+        with open(name, "wb") as file:
+            file.write(build())
@@ -0,0 +1,130 @@
+// Bindings for Fixture.Exports, Version=1.0.0.0, Culture=neutral, PublicKeyToken=null.
+// Generated by hostfxr-sys, do not edit manually.
+
+/// Bindings for `Fixture.Exports`.
+pub mod fixture_exports {
+    #[allow(unused_imports)]
+    use super::*;
+
+    /// The assembly qualified name of `Fixture.Exports`.
+    pub const TYPE_NAME: &str = "Fixture.Exports, Fixture.Exports, Version=1.0.0.0, Culture=neutral, PublicKeyToken=null";
+
+    /// Signature of `Fixture.Exports.Add` (exported as `fixture_add`).
+    pub type AddFn = unsafe extern "system" fn(a: i32, b: i32) -> i32;
+
+    /// Loads `Fixture.Exports.Add` using the given loader.
+    ///
+    /// # Errors
+    /// Returns an error if the method could not be found.
+    pub fn add(
+        loader: &::hostfxr_sys::managed::FunctionPointerLoader,
+    ) -> ::core::result::Result<
+        ::hostfxr_sys::managed::ManagedFunction<AddFn>,
+        ::hostfxr_sys::managed::GetFunctionError,
+    > {
+        unsafe {
+            loader.get_function_pointer::<AddFn>(
+                TYPE_NAME,
+                "Add",
+                ::hostfxr_sys::managed::DelegateSignature::UnmanagedCallersOnly,
+            )
+        }
+    }
+
+    /// Signature of `Fixture.Exports.Log`.
+    pub type LogFn = unsafe extern "system" fn(message: *mut u8, length: usize);
+
+    /// Loads `Fixture.Exports.Log` using the given loader.
+    ///
+    /// # Errors
+    /// Returns an error if the method could not be found.
+    pub fn log(
+        loader: &::hostfxr_sys::managed::FunctionPointerLoader,
+    ) -> ::core::result::Result<
+        ::hostfxr_sys::managed::ManagedFunction<LogFn>,
+        ::hostfxr_sys::managed::GetFunctionError,
+    > {
+        unsafe {
+            loader.get_function_pointer::<LogFn>(
+                TYPE_NAME,
+                "Log",
+                ::hostfxr_sys::managed::DelegateSignature::UnmanagedCallersOnly,
+            )
+        }
+    }
+
+    /// Signature of `Fixture.Exports.Register`.
+    pub type RegisterFn = unsafe extern "system" fn(callback: *const ::core::ffi::c_void, context: *mut ::core::ffi::c_void) -> i32;
+
+    /// Loads `Fixture.Exports.Register` using the given loader.
+    ///
+    /// # Errors
+    /// Returns an error if the method could not be found.
+    pub fn register(
+        loader: &::hostfxr_sys::managed::FunctionPointerLoader,
+    ) -> ::core::result::Result<
+        ::hostfxr_sys::managed::ManagedFunction<RegisterFn>,
+        ::hostfxr_sys::managed::GetFunctionError,
+    > {
+        unsafe {
+            loader.get_function_pointer::<RegisterFn>(
+                TYPE_NAME,
+                "Register",
+                ::hostfxr_sys::managed::DelegateSignature::UnmanagedCallersOnly,
+            )
+        }
+    }
+
+    /// Signature of `Fixture.Exports.Translate`.
+    pub type TranslateFn = unsafe extern "system" fn(point: Point, dx: f32) -> Point;
+
+    /// Loads `Fixture.Exports.Translate` using the given loader.
+    ///
+    /// # Errors
+    /// Returns an error if the method could not be found.
+    pub fn translate(
+        loader: &::hostfxr_sys::managed::FunctionPointerLoader,
+    ) -> ::core::result::Result<
+        ::hostfxr_sys::managed::ManagedFunction<TranslateFn>,
+        ::hostfxr_sys::managed::GetFunctionError,
+    > {
+        unsafe {
+            loader.get_function_pointer::<TranslateFn>(
+                TYPE_NAME,
+                "Translate",
+                ::hostfxr_sys::managed::DelegateSignature::UnmanagedCallersOnly,
+            )
+        }
+    }
+}
+
+/// Bindings for `Fixture.Exports+Nested`.
+pub mod fixture_exports_nested {
+    #[allow(unused_imports)]
+    use super::*;
+
+    /// The assembly qualified name of `Fixture.Exports+Nested`.
+    pub const TYPE_NAME: &str = "Fixture.Exports+Nested, Fixture.Exports, Version=1.0.0.0, Culture=neutral, PublicKeyToken=null";
+
+    /// Signature of `Fixture.Exports+Nested.GetVersion` (exported as `fixture_version`).
+    pub type GetVersionFn = unsafe extern "system" fn() -> isize;
+
+    /// Loads `Fixture.Exports+Nested.GetVersion` using the given loader.
+    ///
+    /// # Errors
+    /// Returns an error if the method could not be found.
+    pub fn get_version(
+        loader: &::hostfxr_sys::managed::FunctionPointerLoader,
+    ) -> ::core::result::Result<
+        ::hostfxr_sys::managed::ManagedFunction<GetVersionFn>,
+        ::hostfxr_sys::managed::GetFunctionError,
+    > {
+        unsafe {
+            loader.get_function_pointer::<GetVersionFn>(
+                TYPE_NAME,
+                "GetVersion",
+                ::hostfxr_sys::managed::DelegateSignature::UnmanagedCallersOnly,
+            )
+        }
+    }
+}
@@ -0,0 +1,83 @@
+"""Generates minimal single-file bundles in the format written by the .NET bundler (Microsoft.NET.HostModel.Bundle).
+
+A bundle is a host executable with the files of the app appended, followed by a manifest.
+The offset of the manifest is stored in the host in front of the bundle signature.
+"""
+
+import struct
+
+# The signature following the bundle header offset in the apphost (see `bundle_marker.cpp`).
+BUNDLE_SIGNATURE = bytes.fromhex("8b1202b96a612038727b930214d7a03213f5b9e6efae3318ee3b2dce24b36aae")
+
+UNKNOWN, ASSEMBLY, NATIVE_BINARY, DEPS_JSON, RUNTIME_CONFIG_JSON, SYMBOLS = range(6)
+
+
+def string(value):
+    """A string as written by `BinaryWriter.Write(string)`, prefixed by its 7-bit encoded length."""
+    data = value.encode()
+    length = len(data)
+    prefix = bytearray()
+    while True:
+        byte = length & 0x7F
+        length >>= 7
+        prefix.append(byte | (0x80 if length else 0))
+        if not length:
+            return bytes(prefix) + data
+
+
+def host():
+    """A stand-in for an apphost with an unbound bundle marker."""
+    return bytearray(b"\x7fELF" + b"\0" * 60 + struct.pack("<q", 0) + BUNDLE_SIGNATURE + b"\0" * 24)
+
+
+def bundle(files, major_version=6, minor_version=0, bundle_id="fixture-bundle-id", flags=0):
+    image = host()
+    entries = []
+    locations = {}
+    for relative_path, file_type, data in files:
+        if file_type == ASSEMBLY:
+            image += b"\0" * (-len(image) % 16)
+        entries.append((len(image), len(data), 0, file_type, relative_path))
+        if file_type in (DEPS_JSON, RUNTIME_CONFIG_JSON):
+            locations[file_type] = (len(image), len(data))
+        image += data
+
+    header_offset = len(image)
+    manifest = struct.pack("<IIi", major_version, minor_version, len(entries)) + string(bundle_id)
+    if major_version >= 2:
+        for file_type in (DEPS_JSON, RUNTIME_CONFIG_JSON):
+            manifest += struct.pack("<qq", *locations.get(file_type, (0, 0)))
+        manifest += struct.pack("<Q", flags)
+    for offset, size, compressed_size, file_type, relative_path in entries:
+        manifest += struct.pack("<qq", offset, size)
+        if major_version >= 6:
+            manifest += struct.pack("<q", compressed_size)
+        manifest += bytes([file_type]) + string(relative_path)
+    image += manifest
+
+    marker = image.index(BUNDLE_SIGNATURE) - 8
+    image[marker:marker + 8] = struct.pack("<q", header_offset)
+    return bytes(image)
+
+
+RUNTIME_CONFIG = b'{"runtimeOptions":{"tfm":"net8.0","framework":{"name":"Microsoft.NETCore.App","version":"8.0.0"}}}'
+DEPS = b'{"runtimeTarget":{"name":".NETCoreApp,Version=v8.0"},"targets":{},"libraries":{}}'
+
+FILES = [
+    ("App.dll", ASSEMBLY, b"MZ app assembly"),
+    ("App.deps.json", DEPS_JSON, DEPS),
+    ("App.runtimeconfig.json", RUNTIME_CONFIG_JSON, RUNTIME_CONFIG),
+    ("runtimes/linux-x64/native/libnative.so", NATIVE_BINARY, b"\x7fELF native"),
+    ("App.pdb", SYMBOLS, b"BSJB symbols"),
+]
+
+FIXTURES = {
+    "app.bundle": lambda: bundle(FILES),
+    "app-v1.bundle": lambda: bundle(FILES[:1], major_version=1),
+    "unbound.host": lambda: bytes(host()),
+}
+
+if __name__ == "__main__":
+    for name, build in FIXTURES.items():
+        with open(name, "wb") as file:
+            file.write(build())
@@ -0,0 +1,281 @@
+#![cfg(feature = "netcore3_0")]
+
+use std::{cell::Cell, ffi::c_void, path::Path, ptr};
+
+use coreclr_hosting_shared::char_t;
+
+use hostfxr_sys::{
+    component_entry_point_fn,
+    managed::{
+        AssemblyDelegateLoader, DelegateSignature, GetFunctionError, InvokeError, ManagedErrorKind,
+        ManagedFunction,
+    },
+    pdcstr::PdCStr,
+};
+
+thread_local! {
+    static ARGUMENT: Cell<(*const c_void, i32)> = const { Cell::new((ptr::null(), -1)) };
+    static DELEGATE_TYPE_NAME: Cell<Option<String>> = const { Cell::new(None) };
+}
+
+// Records its argument and returns its size.
+// Fails if the first byte of the argument is 0xff and replaces it with 42 if it is 0.
+extern "system" fn entry_point(args: *const c_void, size: i32) -> i32 {
+    ARGUMENT.set((args, size));
+    if size > 0 {
+        let first = args.cast::<u8>().cast_mut();
+        match unsafe { *first } {
+            0xff => return 0x8013_1509_u32.cast_signed(),
+            0 => unsafe { *first = 42 },
+            _ => {}
+        }
+    }
+    size
+}
+
+fn entry_point_function() -> ManagedFunction<component_entry_point_fn> {
+    let entry_point: component_entry_point_fn = entry_point;
+    unsafe { ManagedFunction::from_ptr(entry_point as *const c_void) }
+}
+
+unsafe extern "system" fn load_assembly_and_get_function_pointer(
+    _assembly_path: *const char_t,
+    _type_name: *const char_t,
+    method_name: *const char_t,
+    delegate_type_name: *const char_t,
+    _reserved: *const c_void,
+    delegate: *mut *const c_void,
+) -> i32 {
+    DELEGATE_TYPE_NAME.set(match delegate_type_name {
+        name if name.is_null() => None,
+        #[cfg(feature = "net5_0")]
+        name if name == hostfxr_sys::UNMANAGED_CALLERS_ONLY_METHOD => {
+            Some("UnmanagedCallersOnly".to_owned())
+        }
+        name => Some(unsafe { PdCStr::from_ptr(name) }.to_string_lossy()),
+    });
+    match unsafe { PdCStr::from_ptr(method_name) }
+        .to_string_lossy()
+        .as_str()
+    {
+        "Missing" => 0x8013_1513_u32.cast_signed(),
+        "Null" => 0,
+        _ => {
+            unsafe { *delegate = entry_point as *const c_void };
+            0
+        }
+    }
+}
+
+fn delegate_loader() -> AssemblyDelegateLoader {
+    unsafe { AssemblyDelegateLoader::from_delegate(load_assembly_and_get_function_pointer) }
+}
+
+#[test]
+fn function_from_ptr() {
+    let function = entry_point_function();
+    assert_eq!(function.as_ptr(), entry_point as *const c_void);
+
+    let value = 7_u32;
+    let arg = ptr::from_ref(&value).cast();
+    assert_eq!(unsafe { function(arg, 4) }, 4);
+    assert_eq!(ARGUMENT.get(), (arg, 4));
+    assert_eq!(unsafe { function.into_inner()(ptr::null(), 0) }, 0);
+    assert_eq!(ARGUMENT.get(), (ptr::null(), 0));
+}
+
+#[test]
+fn invoke() {
+    let function = entry_point_function();
+    assert_eq!(unsafe { function.invoke() }, Ok(0));
+    assert_eq!(ARGUMENT.get(), (ptr::null(), 0));
+
+    let value = [1_u32, 2, 3];
+    assert_eq!(unsafe { function.invoke_with(&value) }, Ok(12));
+    assert_eq!(ARGUMENT.get(), (value.as_ptr().cast(), 12));
+
+    let bytes = [1_u8, 2];
+    assert_eq!(unsafe { function.invoke_with_bytes(&bytes) }, Ok(2));
+    assert_eq!(ARGUMENT.get(), (bytes.as_ptr().cast(), 2));
+
+    let mut bytes = [0_u8, 2, 3];
+    assert_eq!(unsafe { function.invoke_with_bytes_mut(&mut bytes) }, Ok(3));
+    assert_eq!(ARGUMENT.get(), (bytes.as_ptr().cast(), 3));
+    assert_eq!(bytes, [42, 2, 3]);
+
+    let mut value = 0_u64;
+    assert_eq!(unsafe { function.invoke_with_mut(&mut value) }, Ok(8));
+    assert_eq!(value, 42);
+}
+
+#[test]
+fn invoke_failure() {
+    let function = entry_point_function();
+    assert_eq!(
+        unsafe { function.invoke_with_bytes_mut(&mut [0xff]) },
+        Err(InvokeError::Failed {
+            result: 0x8013_1509_u32.cast_signed()
+        })
+    );
+}
+
+#[test]
+fn delegate_signature() {
+    let loader = delegate_loader();
+    let assembly_path = Path::new("App.dll");
+
+    let function = loader
+        .get_function_with_default_signature(assembly_path, "App.Program, App", "Main")
+        .unwrap();
+    assert_eq!(function.as_ptr(), entry_point as *const c_void);
+    assert_eq!(DELEGATE_TYPE_NAME.take(), None);
+
+    let _: ManagedFunction<extern "system" fn(i32) -> i32> = unsafe {
+        loader.get_function(
+            assembly_path,
+            "App.Program, App",
+            "Main",
+            "App.Program+CustomDelegate, App",
+        )
+    }
+    .unwrap();
+    assert_eq!(
+        DELEGATE_TYPE_NAME.take().as_deref(),
+        Some("App.Program+CustomDelegate, App")
+    );
+
+    #[allow(unused_mut)]
+    let mut signatures = vec![
+        (DelegateSignature::Default, None),
+        (
+            DelegateSignature::DelegateType("App.Program+CustomDelegate, App"),
+            Some("App.Program+CustomDelegate, App"),
+        ),
+    ];
+    #[cfg(feature = "net5_0")]
+    signatures.push((
+        DelegateSignature::UnmanagedCallersOnly,
+        Some("UnmanagedCallersOnly"),
+    ));
+    for (signature, delegate_type_name) in signatures {
+        let _: ManagedFunction<extern "system" fn()> = unsafe {
+            loader.get_function_with_signature(assembly_path, "App.Program, App", "Main", signature)
+        }
+        .unwrap();
+        assert_eq!(DELEGATE_TYPE_NAME.take().as_deref(), delegate_type_name);
+    }
+}
+
+#[test]
+fn get_function_errors() {
+    let loader = delegate_loader();
+    let assembly_path = Path::new("App.dll");
+
+    let error = loader
+        .get_function_with_default_signature(assembly_path, "App.Program, App", "Missing")
+        .unwrap_err();
+    assert_eq!(
+        error,
+        GetFunctionError::Failed {
+            type_name: "App.Program, App".to_owned(),
+            method_name: "Missing".to_owned(),
+            kind: ManagedErrorKind::MethodNotFound,
+            hresult: 0x8013_1513,
+        }
+    );
+    assert_eq!(error.kind(), Some(ManagedErrorKind::MethodNotFound));
+    assert_eq!(
+        loader
+            .get_function_with_default_signature(assembly_path, "App.Program, App", "Null")
+            .unwrap_err(),
+        GetFunctionError::NullPointer
+    );
+
+    for (assembly_path, type_name, method_name, delegate_type_name) in [
+        ("App\0.dll", "App.Program, App", "Main", "App.Delegate, App"),
+        ("App.dll", "App.Program\0, App", "Main", "App.Delegate, App"),
+        ("App.dll", "App.Program, App", "Ma\0in", "App.Delegate, App"),
+        ("App.dll", "App.Program, App", "Main", "App.Delegate\0, App"),
+    ] {
+        DELEGATE_TYPE_NAME.set(Some("not called".to_owned()));
+        let result: Result<ManagedFunction<component_entry_point_fn>, _> = unsafe {
+            loader.get_function_with_signature(
+                Path::new(assembly_path),
+                type_name,
+                method_name,
+                DelegateSignature::DelegateType(delegate_type_name),
+            )
+        };
+        assert!(matches!(
+            result.unwrap_err(),
+            GetFunctionError::ContainsNul(_)
+        ));
+        assert_eq!(DELEGATE_TYPE_NAME.take().as_deref(), Some("not called"));
+    }
+}
+
+#[cfg(feature = "net8_0")]
+#[test]
+fn custom_load_context() {
+    use hostfxr_sys::managed::{
+        AssemblyBytesLoader, AssemblyLoader, LoadAssemblyError, LoadContext,
+    };
+
+    unsafe extern "system" fn load_assembly(
+        _assembly_path: *const char_t,
+        _load_context: *const c_void,
+        _reserved: *const c_void,
+    ) -> i32 {
+        unreachable!()
+    }
+
+    unsafe extern "system" fn load_assembly_bytes(
+        _assembly_bytes: *const u8,
+        _assembly_bytes_len: usize,
+        _symbols_bytes: *const u8,
+        _symbols_bytes_len: usize,
+        _load_context: *const c_void,
+        _reserved: *const c_void,
+    ) -> i32 {
+        unreachable!()
+    }
+
+    let context = LoadContext::Custom(ptr::dangling());
+    let loader = unsafe { AssemblyLoader::from_delegate(load_assembly) };
+    assert_eq!(
+        loader.load_into(Path::new("App.dll"), context),
+        Err(LoadAssemblyError::UnsupportedLoadContext)
+    );
+    let loader = unsafe { AssemblyBytesLoader::from_delegate(load_assembly_bytes) };
+    assert_eq!(
+        loader.load_into(&[0x4d, 0x5a], None, context),
+        Err(LoadAssemblyError::UnsupportedLoadContext)
+    );
+}
+
+#[test]
+fn error_kind_from_hresult() {
+    for (hresult, kind) in [
+        (0x8007_0002, ManagedErrorKind::AssemblyNotFound), // COR_E_FILENOTFOUND
+        (0x8007_0003, ManagedErrorKind::AssemblyNotFound), // COR_E_DIRECTORYNOTFOUND
+        (0x8013_1621, ManagedErrorKind::AssemblyLoadFailed), // COR_E_FILELOAD
+        (0x8013_1040, ManagedErrorKind::AssemblyLoadFailed), // FUSION_E_REF_DEF_MISMATCH
+        (0x8007_000B, ManagedErrorKind::BadImageFormat),   // COR_E_BADIMAGEFORMAT
+        (0x8013_1522, ManagedErrorKind::TypeNotFound),     // COR_E_TYPELOAD
+        (0x8013_1513, ManagedErrorKind::MethodNotFound),   // COR_E_MISSINGMETHOD
+        (0x8007_0057, ManagedErrorKind::InvalidArgument),  // E_INVALIDARG
+        (0x8000_4003, ManagedErrorKind::InvalidArgument),  // E_POINTER
+        (0x8013_1047, ManagedErrorKind::InvalidArgument),  // FUSION_E_INVALID_NAME
+        (0x8013_1509, ManagedErrorKind::InvalidOperation), // COR_E_INVALIDOPERATION
+        (0x8013_1515, ManagedErrorKind::NotSupported),     // COR_E_NOTSUPPORTED
+        (0x8000_80A7, ManagedErrorKind::FeatureDisabled),  // HostFeatureDisabled
+        (0x8013_1511, ManagedErrorKind::Other),            // COR_E_MISSINGFIELD
+        (0x8000_4005, ManagedErrorKind::Other),            // E_FAIL
+    ] {
+        assert_eq!(
+            ManagedErrorKind::from_hresult(hresult),
+            kind,
+            "{hresult:#010x}"
+        );
+    }
+}
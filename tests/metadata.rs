@@ -0,0 +1,264 @@
+use std::io;
+
+use hostfxr_sys::{
+    metadata::{
+        AssemblyIdentity, MetadataError, MetadataReader, ParameterType, TargetFramework,
+        ValidationOptions,
+    },
+    type_name::{AssemblyVersion, PublicKeyToken, TypeName},
+};
+
+macro_rules! fixture {
+    ($name:literal) => {
+        include_bytes!(concat!("fixtures/assemblies/", $name))
+    };
+}
+
+#[test]
+fn strong_named_identity() {
+    let identity = AssemblyIdentity::from_bytes(fixture!("Fixture.Library.dll")).unwrap();
+    assert_eq!(identity.name, "Fixture.Library");
+    assert_eq!(identity.version, AssemblyVersion::new(1, 2, 3, 4));
+    assert_eq!(identity.culture, None);
+    assert_eq!(
+        identity.public_key_token(),
+        PublicKeyToken::Token([0xb7, 0x7a, 0x5c, 0x56, 0x19, 0x34, 0xe0, 0x89])
+    );
+    assert_eq!(
+        identity.to_string(),
+        "Fixture.Library, Version=1.2.3.4, Culture=neutral, PublicKeyToken=b77a5c561934e089"
+    );
+}
+
+#[test]
+fn satellite_identity() {
+    let identity = AssemblyIdentity::from_bytes(fixture!("Fixture.Library.resources.dll")).unwrap();
+    assert_eq!(identity.culture.as_deref(), Some("de-DE"));
+    assert_eq!(identity.public_key, None);
+    assert_eq!(
+        identity.to_string(),
+        "Fixture.Library.resources, Version=1.2.3.4, Culture=de-DE, PublicKeyToken=null"
+    );
+}
+
+#[test]
+fn qualify_type_name() {
+    let identity = AssemblyIdentity::read("tests/fixtures/assemblies/Fixture.Library.dll").unwrap();
+    let type_name = identity.qualify(TypeName::new("Fixture.Program").nested("Inner"));
+    assert_eq!(
+        type_name.to_string(),
+        "Fixture.Program+Inner, Fixture.Library, Version=1.2.3.4, Culture=neutral, PublicKeyToken=b77a5c561934e089"
+    );
+}
+
+#[test]
+fn invalid_images() {
+    assert_eq!(
+        AssemblyIdentity::from_bytes(b"#!/bin/sh\n"),
+        Err(MetadataError::NotPortableExecutable)
+    );
+    assert_eq!(
+        AssemblyIdentity::read("tests/fixtures/assemblies/Missing.dll"),
+        Err(MetadataError::Io(io::ErrorKind::NotFound))
+    );
+
+    let image = fixture!("Fixture.Exports.dll");
+    for len in 0..image.len() {
+        if let Ok(reader) = MetadataReader::new(&image[..len]) {
+            let _ = reader.unmanaged_callers_only_methods();
+        }
+    }
+}
+
+#[test]
+fn unmanaged_callers_only_methods() {
+    let reader = MetadataReader::new(fixture!("Fixture.Exports.dll")).unwrap();
+    assert_eq!(reader.runtime_version(), "v4.0.30319");
+
+    let methods = reader.unmanaged_callers_only_methods().unwrap();
+    let summary = methods
+        .iter()
+        .map(|method| {
+            (
+                method.type_name.type_name.full_name(),
+                method.method_name.as_str(),
+                method.entry_point.as_deref(),
+                method.signature.rust_fn_type().unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        summary,
+        [
+            (
+                "Fixture.Exports".to_owned(),
+                "Add",
+                Some("fixture_add"),
+                "unsafe extern \"system\" fn(i32, i32) -> i32".to_owned()
+            ),
+            (
+                "Fixture.Exports".to_owned(),
+                "Log",
+                None,
+                "unsafe extern \"system\" fn(*mut u8, usize)".to_owned()
+            ),
+            (
+                "Fixture.Exports".to_owned(),
+                "Register",
+                None,
+                "unsafe extern \"system\" fn(*const ::core::ffi::c_void, *mut ::core::ffi::c_void) -> i32".to_owned()
+            ),
+            (
+                "Fixture.Exports".to_owned(),
+                "Translate",
+                None,
+                "unsafe extern \"system\" fn(Point, f32) -> Point".to_owned()
+            ),
+            (
+                "Fixture.Exports+Nested".to_owned(),
+                "GetVersion",
+                Some("fixture_version"),
+                "unsafe extern \"system\" fn() -> isize".to_owned()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn unmanaged_callers_only_details() {
+    let reader = MetadataReader::new(fixture!("Fixture.Exports.dll")).unwrap();
+    let methods = reader.unmanaged_callers_only_methods().unwrap();
+
+    let add = &methods[0];
+    assert_eq!(
+        add.type_name.to_string(),
+        "Fixture.Exports, Fixture.Exports, Version=1.0.0.0, Culture=neutral, PublicKeyToken=null"
+    );
+    let names = add
+        .signature
+        .parameters
+        .iter()
+        .map(|parameter| parameter.name.as_deref())
+        .collect::<Vec<_>>();
+    assert_eq!(names, [Some("a"), Some("b")]);
+
+    let register = &methods[2];
+    assert_eq!(
+        register.calling_conventions,
+        ["System.Runtime.CompilerServices.CallConvCdecl, System.Runtime, Version=8.0.0.0, Culture=neutral, PublicKeyToken=b03f5f7f11d50a3a"]
+    );
+    assert_eq!(
+        register.signature.parameters[0].parameter_type,
+        ParameterType::FunctionPointer
+    );
+
+    let translate = &methods[3];
+    assert_eq!(
+        translate.signature.return_type,
+        ParameterType::ValueType(TypeName::new("Fixture.Point"))
+    );
+    assert!(translate.signature.is_blittable());
+
+    let nested = &methods[4];
+    assert_eq!(
+        nested.type_name.to_string(),
+        "Fixture.Exports+Nested, Fixture.Exports, Version=1.0.0.0, Culture=neutral, PublicKeyToken=null"
+    );
+}
+
+#[test]
+fn target_framework() {
+    let reader = MetadataReader::new(fixture!("Fixture.Net10.dll")).unwrap();
+    let target_framework = reader.target_framework().unwrap().unwrap();
+    assert_eq!(target_framework.identifier, ".NETCoreApp");
+    assert_eq!(target_framework.version, AssemblyVersion::new(10, 0, 0, 0));
+    assert_eq!(target_framework.to_string(), ".NETCoreApp,Version=v10.0");
+    assert!(!target_framework.is_supported_by(AssemblyVersion::new(8, 0, 0, 0)));
+    assert!(target_framework.is_supported_by(AssemblyVersion::new(10, 0, 1, 0)));
+
+    let netstandard = TargetFramework::parse(".NETStandard,Version=v2.1").unwrap();
+    assert!(!netstandard.is_supported_by(AssemblyVersion::new(2, 1, 0, 0)));
+    assert!(netstandard.is_supported_by(AssemblyVersion::new(3, 0, 0, 0)));
+    assert_eq!(TargetFramework::parse(".NETCoreApp"), None);
+
+    let reader = MetadataReader::new(fixture!("Fixture.Library.dll")).unwrap();
+    assert_eq!(reader.target_framework(), Ok(None));
+}
+
+#[test]
+fn validate() {
+    let net8 = ValidationOptions::new().with_runtime_version(AssemblyVersion::new(8, 0, 0, 0));
+    let identity = net8.validate(fixture!("Fixture.Net8.dll")).unwrap();
+    assert_eq!(identity.name, "Fixture.Net8");
+    assert!(net8.validate(fixture!("Fixture.Library.dll")).is_ok());
+
+    let err = net8.validate(fixture!("Fixture.Net10.dll")).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "assembly targets .NETCoreApp,Version=v10.0, which is not supported by runtime version 8.0"
+    );
+    assert!(ValidationOptions::new()
+        .validate(fixture!("Fixture.Net10.dll"))
+        .is_ok());
+
+    assert_eq!(
+        net8.validate(fixture!("Fixture.Reference.dll")),
+        Err(MetadataError::ReferenceAssembly)
+    );
+    let reader = MetadataReader::new(fixture!("Fixture.Reference.dll")).unwrap();
+    assert_eq!(reader.is_reference_assembly(), Ok(true));
+    assert!(net8
+        .clone()
+        .allow_reference_assemblies(true)
+        .validate(fixture!("Fixture.Reference.dll"))
+        .is_ok());
+
+    assert_eq!(
+        net8.validate(fixture!("Fixture.Native.dll")),
+        Err(MetadataError::NotManaged)
+    );
+    assert_eq!(
+        net8.validate(b"\x7fELF\x02\x01\x01\0"),
+        Err(MetadataError::NativeImage("ELF"))
+    );
+    assert_eq!(
+        net8.validate(&[0xCF, 0xFA, 0xED, 0xFE, 0x07, 0, 0, 0x01]),
+        Err(MetadataError::NativeImage("Mach-O"))
+    );
+}
+
+#[test]
+fn validate_machine() {
+    let x64 = ValidationOptions::new().with_machine(0x8664);
+    let x86 = ValidationOptions::new().with_machine(0x014C);
+    let arm64 = ValidationOptions::new().with_machine(0xAA64);
+
+    // IL-only assemblies run everywhere.
+    assert!(x64.validate(fixture!("Fixture.Library.dll")).is_ok());
+    assert!(arm64
+        .validate(fixture!("Fixture.Library.resources.dll"))
+        .is_ok());
+
+    let err = x64.validate(fixture!("Fixture.X86.dll")).unwrap_err();
+    assert_eq!(
+        err,
+        MetadataError::MachineMismatch {
+            machine: 0x014C,
+            expected: 0x8664
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "assembly cannot run on x64 (built for x86)"
+    );
+    assert!(x86.validate(fixture!("Fixture.X86.dll")).is_ok());
+
+    assert!(arm64.validate(fixture!("Fixture.Mixed.dll")).is_ok());
+    assert_eq!(
+        x64.validate(fixture!("Fixture.Mixed.dll")),
+        Err(MetadataError::MachineMismatch {
+            machine: 0xAA64,
+            expected: 0x8664
+        })
+    );
+}
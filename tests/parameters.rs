@@ -0,0 +1,48 @@
+use std::{mem, path::PathBuf};
+
+use hostfxr_sys::{
+    hostfxr_initialize_parameters, parameters::InitializeParameters, pdcstr::PdCStr,
+};
+
+fn read(ptr: *const coreclr_hosting_shared::char_t) -> Option<PathBuf> {
+    (!ptr.is_null()).then(|| unsafe { PdCStr::from_ptr(ptr) }.into())
+}
+
+#[test]
+fn raw_parameters() {
+    let parameters = InitializeParameters::new("/app/App", "/usr/share/dotnet").unwrap();
+    // moving the parameters must not invalidate the pointers.
+    let parameters = Box::new(parameters);
+    let raw = parameters.as_raw();
+    assert_eq!(raw.size, mem::size_of::<hostfxr_initialize_parameters>());
+    assert_eq!(read(raw.host_path), Some(PathBuf::from("/app/App")));
+    assert_eq!(
+        read(raw.dotnet_root),
+        Some(PathBuf::from("/usr/share/dotnet"))
+    );
+    assert_eq!(parameters.host_path(), Some(PathBuf::from("/app/App")));
+
+    let cloned = parameters.clone();
+    drop(parameters);
+    assert_eq!(
+        read(cloned.as_raw().dotnet_root),
+        Some(PathBuf::from("/usr/share/dotnet"))
+    );
+}
+
+#[test]
+fn builder() {
+    let parameters = InitializeParameters::builder()
+        .with_dotnet_root("/usr/share/dotnet")
+        .build()
+        .unwrap();
+    assert!(parameters.as_raw().host_path.is_null());
+    assert_eq!(parameters.host_path(), None);
+    assert_eq!(
+        read(parameters.as_raw().dotnet_root),
+        Some(PathBuf::from("/usr/share/dotnet"))
+    );
+
+    let err = InitializeParameters::new("/app/\0App", "/usr/share/dotnet").unwrap_err();
+    assert_eq!(err.nul_position(), 5);
+}
@@ -0,0 +1,75 @@
+use std::path::{Path, PathBuf};
+
+use hostfxr_sys::{
+    path_list::{PathList, PathListError},
+    pdcstr::PdCString,
+};
+
+const SEPARATOR: &str = if cfg!(windows) { ";" } else { ":" };
+
+fn join(paths: &[&str]) -> String {
+    paths.join(SEPARATOR)
+}
+
+#[test]
+fn parse_and_join() {
+    let joined = join(&["/app/App.dll", "", "/fx/System.Runtime.dll", ""]);
+    let list = PathList::parse(&joined);
+    assert_eq!(
+        list.paths(),
+        [
+            PathBuf::from("/app/App.dll"),
+            PathBuf::from("/fx/System.Runtime.dll")
+        ]
+    );
+    assert_eq!(
+        list.join(),
+        *join(&["/app/App.dll", "/fx/System.Runtime.dll"])
+    );
+
+    let pdcstring = list.to_pdcstring();
+    assert_eq!(PathList::from_pdcstr(&pdcstring), list);
+    assert_eq!(pdcstring, PdCString::from_os_str(list.join()).unwrap());
+
+    assert!(PathList::parse("").is_empty());
+    assert_eq!(PathList::new().join(), "");
+}
+
+#[test]
+fn edit() {
+    let mut list = PathList::parse(join(&["/a", "/b", "/a", "/c", "/b"]));
+    list.dedup();
+    assert_eq!(list, PathList::parse(join(&["/a", "/b", "/c"])));
+
+    list.push("/d").unwrap();
+    list.insert(0, "/z").unwrap();
+    assert!(list.remove("/b"));
+    assert!(!list.remove("/b"));
+    assert!(list.contains(Path::new("/d")));
+    list.retain(|path| path != Path::new("/c"));
+    assert_eq!(
+        list.into_iter().collect::<Vec<_>>(),
+        ["/z", "/a", "/d"].map(PathBuf::from)
+    );
+}
+
+#[test]
+fn invalid_entries() {
+    let mut list = PathList::new();
+    let with_separator = format!("/a{SEPARATOR}/b");
+    assert_eq!(
+        list.push(&with_separator),
+        Err(PathListError::ContainsSeparator(with_separator.into()))
+    );
+    assert_eq!(
+        list.push("/a\0"),
+        Err(PathListError::ContainsNul("/a\0".into()))
+    );
+    assert_eq!(list.insert(0, ""), Err(PathListError::Empty));
+    assert!(list.is_empty());
+
+    assert_eq!(
+        PathList::try_from(vec![PathBuf::from("/a"), PathBuf::new()]),
+        Err(PathListError::Empty)
+    );
+}
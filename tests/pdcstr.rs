@@ -0,0 +1,59 @@
+use std::{
+    borrow::Cow,
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
+
+use hostfxr_sys::pdcstr::{PdCStr, PdCString, ToPdCStr};
+
+#[test]
+fn os_str_round_trip() {
+    let path = Path::new("dir/App.dll");
+    let s = PdCString::from_os_str(path).unwrap();
+    assert_eq!(s.len(), 11);
+    assert_eq!(s.as_slice_with_nul().last(), Some(&0));
+    assert_eq!(PathBuf::from(s.clone()), path);
+    assert_eq!(s.to_string_lossy(), "dir/App.dll");
+    assert_eq!(format!("{s:?}"), "\"dir/App.dll\"");
+
+    let borrowed = unsafe { PdCStr::from_ptr(s.as_ptr()) };
+    assert_eq!(borrowed, s.as_pdcstr());
+    assert_eq!(borrowed.to_owned(), s);
+
+    let empty: PdCString = "".parse().unwrap();
+    assert!(empty.is_empty());
+    assert_eq!(empty.into_vec_with_nul(), [0]);
+}
+
+#[cfg(unix)]
+#[test]
+fn non_utf8_round_trip() {
+    use std::os::unix::ffi::OsStringExt;
+
+    let invalid = OsString::from_vec(vec![b'a', 0xFF, b'b']);
+    let s = PdCString::from_os_str(&invalid).unwrap();
+    assert_eq!(s.to_os_string(), invalid);
+    assert_eq!(s.to_string_lossy(), "a\u{FFFD}b");
+}
+
+#[test]
+fn interior_nul() {
+    let err = PdCString::from_os_str("App\0.dll").unwrap_err();
+    assert_eq!(err.nul_position(), 3);
+    assert!("App\0.dll".to_pdcstr().is_err());
+}
+
+#[test]
+fn to_pdcstr() {
+    let s = PdCString::from_os_str("App.dll").unwrap();
+    assert!(matches!(s.to_pdcstr(), Ok(Cow::Borrowed(_))));
+    assert!(matches!(s.as_pdcstr().to_pdcstr(), Ok(Cow::Borrowed(_))));
+    for converted in [
+        "App.dll".to_pdcstr().unwrap(),
+        String::from("App.dll").to_pdcstr().unwrap(),
+        Path::new("App.dll").to_pdcstr().unwrap(),
+        OsString::from("App.dll").to_pdcstr().unwrap(),
+    ] {
+        assert_eq!(converted.as_ref(), s.as_pdcstr());
+    }
+}
@@ -0,0 +1,145 @@
+#![cfg(feature = "serde")]
+
+use std::path::{Path, PathBuf};
+
+use hostfxr_sys::runtime_config::{
+    self, FrameworkReference, RollForward, RuntimeConfig, RuntimeConfigError,
+};
+use serde_json::{json, Value};
+
+const CONFIG_PATH: &str = "tests/fixtures/runtimeconfig/App.runtimeconfig.json";
+
+#[test]
+fn read() {
+    let config = RuntimeConfig::read(CONFIG_PATH).unwrap();
+    let options = &config.runtime_options;
+    assert_eq!(options.tfm.as_deref(), Some("net8.0"));
+    assert_eq!(options.framework, None);
+    assert_eq!(options.roll_forward, Some(RollForward::Major));
+    assert_eq!(
+        config
+            .frameworks()
+            .map(|framework| (framework.name.as_str(), framework.roll_forward))
+            .collect::<Vec<_>>(),
+        [
+            ("Microsoft.NETCore.App", None),
+            ("Microsoft.AspNetCore.App", Some(RollForward::LatestMinor))
+        ]
+    );
+    assert_eq!(options.config_properties["System.GC.Server"], true);
+    assert_eq!(
+        options.extra["customOption"],
+        json!({ "nested": [1, 2, 3] })
+    );
+    assert_eq!(config.extra["comment"], "unknown top-level key");
+    assert!(!config.is_self_contained());
+}
+
+#[test]
+fn round_trip() {
+    let json = std::fs::read_to_string(CONFIG_PATH).unwrap();
+    let config = RuntimeConfig::from_json(&json).unwrap();
+    let written = config.to_json();
+    assert_eq!(RuntimeConfig::from_json(&written).unwrap(), config);
+
+    // nothing is lost, only the roll forward policy is normalized.
+    let original: Value =
+        serde_json::from_str(&json.replace("latestMinor", "LatestMinor")).unwrap();
+    let written: Value = serde_json::from_str(&written).unwrap();
+    assert_eq!(written, original);
+    assert_eq!(
+        written["runtimeOptions"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .collect::<Vec<_>>(),
+        [
+            "tfm",
+            "frameworks",
+            "rollForward",
+            "configProperties",
+            "customOption"
+        ]
+    );
+}
+
+#[test]
+fn generate() {
+    let config = RuntimeConfig::for_framework(
+        "net8.0",
+        FrameworkReference::new("Microsoft.NETCore.App", "8.0.0"),
+    );
+    assert_eq!(
+        serde_json::from_str::<Value>(&config.to_json()).unwrap(),
+        json!({
+            "runtimeOptions": {
+                "tfm": "net8.0",
+                "framework": { "name": "Microsoft.NETCore.App", "version": "8.0.0" }
+            }
+        })
+    );
+}
+
+#[test]
+fn dev_config() {
+    assert_eq!(
+        runtime_config::dev_config_path(Path::new(CONFIG_PATH)),
+        Path::new("tests/fixtures/runtimeconfig/App.runtimeconfig.dev.json")
+    );
+
+    let config = RuntimeConfig::read_with_dev_config(CONFIG_PATH).unwrap();
+    let options = &config.runtime_options;
+    assert_eq!(options.roll_forward, Some(RollForward::Major));
+    assert_eq!(options.apply_patches, Some(false));
+    assert_eq!(
+        options.additional_probing_paths,
+        [PathBuf::from("/home/user/.nuget/packages")]
+    );
+    assert_eq!(options.config_properties["System.GC.Server"], true);
+    assert_eq!(options.config_properties["App.DevSetting"], 42);
+    assert_eq!(config.frameworks().count(), 2);
+}
+
+#[test]
+fn single_probing_path() {
+    let config = RuntimeConfig::from_json(
+        r#"{"runtimeOptions":{"additionalProbingPaths":"/home/user/.nuget/packages"}}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        config.runtime_options.additional_probing_paths,
+        [PathBuf::from("/home/user/.nuget/packages")]
+    );
+    assert!(matches!(
+        RuntimeConfig::from_json(r#"{"runtimeOptions":{"additionalProbingPaths":1}}"#),
+        Err(RuntimeConfigError::Json(_))
+    ));
+}
+
+#[test]
+fn invalid_dev_config() {
+    // the host ignores a dev config it cannot parse.
+    let path = "tests/fixtures/runtimeconfig/InvalidDev.runtimeconfig.json";
+    assert!(RuntimeConfig::read(runtime_config::dev_config_path(Path::new(path))).is_err());
+    assert_eq!(
+        RuntimeConfig::read_with_dev_config(path),
+        RuntimeConfig::read(path)
+    );
+}
+
+#[test]
+fn invalid() {
+    assert!(matches!(
+        RuntimeConfig::from_json(r#"{"runtimeOptions":{"rollForward":"Sideways"}}"#),
+        Err(RuntimeConfigError::Json(_))
+    ));
+    assert!(matches!(
+        RuntimeConfig::from_json("{"),
+        Err(RuntimeConfigError::Json(_))
+    ));
+    assert_eq!(
+        RuntimeConfig::read("tests/fixtures/runtimeconfig/Missing.runtimeconfig.json"),
+        Err(RuntimeConfigError::Io(std::io::ErrorKind::NotFound))
+    );
+    assert_eq!("latestpatch".parse(), Ok(RollForward::LatestPatch));
+}
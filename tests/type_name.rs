@@ -0,0 +1,280 @@
+use hostfxr_sys::type_name::{
+    AssemblyName, AssemblyQualifiedName, AssemblyVersion, PublicKeyToken, TypeModifier, TypeName,
+    TypeNameError,
+};
+
+fn round_trip(s: &str) -> AssemblyQualifiedName {
+    let name = AssemblyQualifiedName::parse(s).unwrap();
+    assert_eq!(
+        AssemblyQualifiedName::parse(&name.to_string()).unwrap(),
+        name
+    );
+    name
+}
+
+#[test]
+fn nested_types() {
+    let name = round_trip("App.Program+Nested+Inner, App");
+    assert_eq!(name.type_name.namespace.as_deref(), Some("App"));
+    assert_eq!(name.type_name.name, "Program");
+    assert_eq!(name.type_name.nested, ["Nested", "Inner"]);
+    assert_eq!(name.type_name.full_name(), "App.Program+Nested+Inner");
+    assert_eq!(name.assembly, Some(AssemblyName::new("App")));
+    assert_eq!(name.to_string(), "App.Program+Nested+Inner, App");
+
+    let built = TypeName::new("App.Program")
+        .nested("Nested")
+        .nested("Inner")
+        .qualified(AssemblyName::new("App"));
+    assert_eq!(built, name);
+}
+
+#[test]
+fn generic_arguments() {
+    let name = round_trip(
+        "System.Collections.Generic.Dictionary`2[[System.String, System.Private.CoreLib],[System.Collections.Generic.List`1[[System.Int32, System.Private.CoreLib]], System.Private.CoreLib]], System.Private.CoreLib",
+    );
+    let type_name = &name.type_name;
+    assert_eq!(type_name.name, "Dictionary`2");
+    assert_eq!(type_name.declared_arity(), 2);
+    assert_eq!(type_name.generic_arguments.len(), 2);
+    let list = &type_name.generic_arguments[1];
+    assert_eq!(list.type_name.name, "List`1");
+    assert_eq!(
+        list.type_name.generic_arguments[0],
+        TypeName::new("System.Int32").qualified(AssemblyName::new("System.Private.CoreLib"))
+    );
+    assert_eq!(
+        list.assembly
+            .as_ref()
+            .map(|assembly| assembly.name.as_str()),
+        Some("System.Private.CoreLib")
+    );
+
+    // arguments without an assembly do not need brackets, and the arity may be declared by a nested type.
+    let name = round_trip("Outer+Inner`1[Argument]");
+    assert_eq!(name.type_name.nested, ["Inner`1"]);
+    assert_eq!(name.type_name.declared_arity(), 1);
+    assert_eq!(name.to_string(), "Outer+Inner`1[Argument]");
+
+    // the arity is appended to names without one when formatting.
+    let built = TypeName::new("App.Wrapper").generic_argument(TypeName::new("System.Int32"));
+    assert_eq!(built.to_string(), "App.Wrapper`1[System.Int32]");
+
+    // but generic arguments without a declared arity are rejected when parsing.
+    assert_eq!(
+        AssemblyQualifiedName::parse("A[[B, C]], D"),
+        Err(TypeNameError::ArityMismatch {
+            declared: 0,
+            arguments: 1
+        })
+    );
+}
+
+#[test]
+fn escaping() {
+    for c in ['\\', ',', '+', '&', '*', '[', ']'] {
+        let type_name = TypeName::new(&format!("App.A{c}B"));
+        assert_eq!(type_name.to_string(), format!("App.A\\{c}B"));
+        let parsed: TypeName = type_name.to_string().parse().unwrap();
+        assert_eq!(parsed, type_name);
+    }
+    for c in ['\\', ',', '=', '"', '\'', '[', ']'] {
+        let assembly = AssemblyName::new(format!("My{c}Lib")).with_culture(format!("c{c}"));
+        assert_eq!(assembly.to_string(), format!("My\\{c}Lib, Culture=c\\{c}"));
+        assert_eq!(
+            AssemblyName::parse(&assembly.to_string()).unwrap(),
+            assembly
+        );
+
+        let name = TypeName::new("A`1")
+            .generic_argument(TypeName::new("B").qualified(assembly.clone()))
+            .qualified(assembly);
+        assert_eq!(
+            AssemblyQualifiedName::parse(&name.to_string()).unwrap(),
+            name
+        );
+    }
+
+    assert_eq!(AssemblyName::new("My[Lib]").to_string(), "My\\[Lib\\]");
+    assert_eq!(round_trip("A, B\\[x").to_string(), "A, B\\[x");
+
+    // whitespace around names is trimmed unless escaped.
+    let name = round_trip("  App.\\ Program\\ +\\ Nested , \\ Lib\\ , Key = \\ value\\  ");
+    assert_eq!(name.type_name.name, " Program ");
+    assert_eq!(name.type_name.nested, [" Nested"]);
+    let assembly = name.assembly.unwrap();
+    assert_eq!(assembly.name, " Lib ");
+    assert_eq!(
+        assembly.properties,
+        [("Key".to_owned(), " value ".to_owned())]
+    );
+
+    // quoted values may contain special characters and be empty.
+    let assembly = AssemblyName::parse("Lib, Culture='', Key=\"a, b\"").unwrap();
+    assert_eq!(assembly.culture.as_deref(), Some(""));
+    assert_eq!(assembly.properties[0].1, "a, b");
+    assert_eq!(assembly.to_string(), "Lib, Culture=\"\", Key=a\\, b");
+    assert_eq!(
+        AssemblyName::parse(&assembly.to_string()).unwrap(),
+        assembly
+    );
+}
+
+#[test]
+fn modifiers() {
+    let name = round_trip("System.Int32*[][*][,,]&");
+    assert_eq!(
+        name.type_name.modifiers,
+        [
+            TypeModifier::Pointer,
+            TypeModifier::SzArray,
+            TypeModifier::Array { rank: 1 },
+            TypeModifier::Array { rank: 3 },
+            TypeModifier::ByRef
+        ]
+    );
+    assert_eq!(name.to_string(), "System.Int32*[][*][,,]&");
+
+    let name = round_trip("List`1[[System.Int32[], System.Private.CoreLib]][], App");
+    assert_eq!(name.type_name.modifiers, [TypeModifier::SzArray]);
+    assert_eq!(
+        name.type_name.generic_arguments[0].type_name.modifiers,
+        [TypeModifier::SzArray]
+    );
+}
+
+#[test]
+fn assembly_properties() {
+    let name = round_trip(
+        "App.Program, App, Version=1.2.3.4, Culture=neutral, PublicKeyToken=B77A5C561934E089, ProcessorArchitecture=MSIL",
+    );
+    let assembly = name.assembly.unwrap();
+    assert_eq!(assembly.version, Some(AssemblyVersion::new(1, 2, 3, 4)));
+    assert_eq!(assembly.culture.as_deref(), Some("neutral"));
+    assert_eq!(
+        assembly.public_key_token,
+        Some(PublicKeyToken::Token([
+            0xb7, 0x7a, 0x5c, 0x56, 0x19, 0x34, 0xe0, 0x89
+        ]))
+    );
+    assert_eq!(
+        assembly.properties,
+        [("ProcessorArchitecture".to_owned(), "MSIL".to_owned())]
+    );
+    assert_eq!(
+        assembly.to_string(),
+        "App, Version=1.2.3.4, Culture=neutral, PublicKeyToken=b77a5c561934e089, ProcessorArchitecture=MSIL"
+    );
+
+    let assembly = AssemblyName::parse("App, version=1.2, publickeytoken=NULL").unwrap();
+    assert_eq!(assembly.version, Some(AssemblyVersion::new(1, 2, 0, 0)));
+    assert_eq!(assembly.public_key_token, Some(PublicKeyToken::Null));
+    assert_eq!(
+        assembly.to_string(),
+        "App, Version=1.2.0.0, PublicKeyToken=null"
+    );
+
+    assert_eq!(
+        AssemblyName::parse("App, Version=1"),
+        Err(TypeNameError::InvalidVersion("1".to_owned()))
+    );
+    assert_eq!(
+        AssemblyName::parse("App, PublicKeyToken=abc"),
+        Err(TypeNameError::InvalidPublicKeyToken("abc".to_owned()))
+    );
+    assert_eq!(
+        AssemblyName::parse("App, Culture"),
+        Err(TypeNameError::InvalidAssemblyProperty("Culture".to_owned()))
+    );
+}
+
+#[test]
+fn parse_errors() {
+    assert_eq!(
+        AssemblyQualifiedName::parse(""),
+        Err(TypeNameError::UnexpectedEnd)
+    );
+    assert_eq!(
+        AssemblyQualifiedName::parse("A`1[[B, C]"),
+        Err(TypeNameError::UnexpectedEnd)
+    );
+    assert_eq!(
+        AssemblyQualifiedName::parse("A[*"),
+        Err(TypeNameError::UnexpectedEnd)
+    );
+    assert_eq!(
+        AssemblyQualifiedName::parse("A]"),
+        Err(TypeNameError::UnexpectedChar {
+            position: 1,
+            found: ']'
+        })
+    );
+    assert_eq!(
+        "A, B".parse::<TypeName>(),
+        Err(TypeNameError::UnexpectedAssemblyName)
+    );
+}
+
+#[test]
+fn validate() {
+    assert_eq!(
+        AssemblyQualifiedName::parse("A`2[[B, C]], D")
+            .unwrap()
+            .validate(),
+        Err(TypeNameError::ArityMismatch {
+            declared: 2,
+            arguments: 1
+        })
+    );
+    assert_eq!(
+        TypeName::new("A")
+            .modifier(TypeModifier::ByRef)
+            .modifier(TypeModifier::Pointer)
+            .validate(),
+        Err(TypeNameError::ByRefNotLast)
+    );
+    assert_eq!(
+        TypeName::new("A")
+            .modifier(TypeModifier::Array { rank: 33 })
+            .validate(),
+        Err(TypeNameError::InvalidArrayRank(33))
+    );
+    assert_eq!(
+        TypeName::new("App. ").validate(),
+        Err(TypeNameError::EmptyName)
+    );
+    assert_eq!(
+        TypeName::new("App..Program").validate(),
+        Err(TypeNameError::EmptyName)
+    );
+    assert_eq!(
+        TypeName::new("A\0").validate(),
+        Err(TypeNameError::ContainsNul)
+    );
+    assert_eq!(
+        TypeName::new("A`1")
+            .generic_argument(TypeName::new("B").qualified(AssemblyName::new("")))
+            .validate(),
+        Err(TypeNameError::EmptyName)
+    );
+    assert_eq!(
+        AssemblyName::new("App").with_culture(" ").validate(),
+        Err(TypeNameError::EmptyName)
+    );
+
+    let valid = TypeName::new("System.Collections.Generic.List`1")
+        .nested("Enumerator")
+        .generic_argument(
+            TypeName::new("System.Int32").qualified(AssemblyName::new("System.Private.CoreLib")),
+        )
+        .modifier(TypeModifier::Array { rank: 32 })
+        .modifier(TypeModifier::ByRef)
+        .qualified(
+            AssemblyName::new("System.Private.CoreLib")
+                .with_version(AssemblyVersion::new(8, 0, 0, 0))
+                .with_culture("neutral")
+                .with_public_key_token(PublicKeyToken::Null),
+        );
+    assert_eq!(valid.validate(), Ok(()));
+}